@@ -0,0 +1,4 @@
+// Recompile when the embedded migrations change
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+}
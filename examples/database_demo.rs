@@ -36,8 +36,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    // Generate a timestamped table name
-    let table_name = DatabaseManager::generate_table_name();
+    // Claim a timestamped table name
+    let table_name = db.reserve_table_name().await?;
     println!("📅 Generated table name: {}", table_name);
 
     // Create repository table
@@ -55,8 +55,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📝 Inserting {} repositories...", repositories.len());
 
     // Insert repositories
-    let inserted_count = db.insert_repositories(&table_name, &repositories).await?;
-    println!("✅ Inserted {} repositories", inserted_count);
+    let outcome = db.insert_repositories(&table_name, &repositories).await?;
+    println!("✅ Inserted {} repositories", outcome.inserted);
 
     // Create and save query metadata
     let mut metadata = QueryMetadata::new(
@@ -100,8 +100,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Test conflict handling by inserting the same repositories again
     println!("🔄 Testing conflict handling (inserting same repositories)...");
-    let updated_count = db.insert_repositories(&table_name, &repositories).await?;
-    println!("✅ Updated {} repositories (no duplicates created)", updated_count);
+    let outcome = db.insert_repositories(&table_name, &repositories).await?;
+    println!("✅ Updated {} repositories (no duplicates created)", outcome.updated);
 
     // Verify table still has the same number of repositories
     let updated_stats = db.get_table_stats(&table_name).await?;
@@ -142,7 +142,7 @@ fn create_sample_repository(id: i64, full_name: &str) -> Repository {
         fork: false,
         archived: false,
         disabled: false,
-        created_at: Utc::now() - chrono::Duration::days(365 * (id as i64)), // Vary creation dates
+        created_at: Utc::now() - chrono::Duration::days(365 * id), // Vary creation dates
         updated_at: Utc::now() - chrono::Duration::days(30),
         pushed_at: Some(Utc::now() - chrono::Duration::days(7)),
         owner: RepositoryOwner {
@@ -169,5 +169,8 @@ fn create_sample_repository(id: i64, full_name: &str) -> Repository {
         has_wiki: true,
         has_pages: false,
         has_downloads: true,
+        score: None,
+        text_matches: Vec::new(),
+        matched_language_filter: None,
     }
 }
\ No newline at end of file
@@ -0,0 +1,33 @@
+use github_pg_query::{GitHubClient, AppError};
+use std::env;
+
+/// List the most starred open source projects on GitHub
+///
+/// To run this example:
+/// ```bash
+/// export GITHUB_TOKEN="your_github_token_here"
+/// cargo run --example top_os_projects
+/// ```
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let token = env::var("GITHUB_TOKEN")
+        .map_err(|_| AppError::environment("GITHUB_TOKEN"))?;
+
+    let client = GitHubClient::new(token)?;
+    let results = client
+        .search_repositories("stars:>50000 is:public", Some(20), Some(1))
+        .await?;
+
+    println!("🌟 Top open source projects ({} total matches)", results.total_count);
+    for (rank, repo) in results.items.iter().enumerate() {
+        println!(
+            "{:>3}. {:<45} ⭐ {:>7}  {}",
+            rank + 1,
+            repo.full_name,
+            repo.stargazers_count,
+            repo.language.as_deref().unwrap_or("-")
+        );
+    }
+
+    Ok(())
+}
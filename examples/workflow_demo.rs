@@ -1,5 +1,5 @@
 use github_pg_query::{
-    CliConfig, GitHubClient, QueryMetadata, ProgressIndicator, DatabaseManager, Result
+    CliConfig, GitHubClient, QueryMetadata, ProgressIndicator, Result
 };
 use std::time::Instant;
 
@@ -36,7 +36,7 @@ async fn main() -> Result<()> {
 
     // Step 2: Initialize GitHub client
     println!("🐙 Step 2: GitHub Client Initialization");
-    let github_client = GitHubClient::new(config.github_token.clone())?;
+    let _github_client = GitHubClient::new(config.github_token.clone())?;
     println!("   ✅ GitHub client initialized");
     println!();
 
@@ -58,7 +58,7 @@ async fn main() -> Result<()> {
 
     // Step 4: Query metadata management
     println!("📊 Step 4: Query Metadata Management");
-    let table_name = DatabaseManager::generate_table_name();
+    let table_name = QueryMetadata::generate_table_name();
     println!("   📋 Generated table name: {}", table_name);
     
     let mut query_metadata = QueryMetadata::new(
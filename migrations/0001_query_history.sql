@@ -0,0 +1,15 @@
+-- Query history recorded for every search run
+CREATE TABLE IF NOT EXISTS query_history (
+    id UUID PRIMARY KEY,
+    search_query TEXT NOT NULL,
+    table_name VARCHAR(50) NOT NULL,
+    result_count BIGINT NOT NULL DEFAULT 0,
+    executed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    duration_ms BIGINT NOT NULL DEFAULT 0,
+    success BOOLEAN NOT NULL DEFAULT FALSE,
+    error_message TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_query_history_executed_at ON query_history(executed_at);
+CREATE INDEX IF NOT EXISTS idx_query_history_table_name ON query_history(table_name);
+CREATE INDEX IF NOT EXISTS idx_query_history_success ON query_history(success);
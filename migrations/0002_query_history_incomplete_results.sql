@@ -0,0 +1,2 @@
+-- Final incomplete_results flag after --incomplete retries
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS incomplete_results BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,53 @@
+-- Shared tables written by the --enrich passes, keyed by github_id
+
+CREATE TABLE IF NOT EXISTS repo_stars_timeline (
+    github_id BIGINT NOT NULL,
+    full_name VARCHAR(255) NOT NULL,
+    user_id BIGINT NOT NULL,
+    user_login VARCHAR(255) NOT NULL,
+    starred_at TIMESTAMPTZ NOT NULL,
+    fetched_at TIMESTAMPTZ DEFAULT NOW(),
+    PRIMARY KEY (github_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_repo_stars_timeline_starred_at ON repo_stars_timeline(github_id, starred_at);
+
+CREATE TABLE IF NOT EXISTS repo_ci (
+    github_id BIGINT PRIMARY KEY,
+    full_name VARCHAR(255) NOT NULL,
+    has_workflows BOOLEAN NOT NULL DEFAULT FALSE,
+    workflow_count INTEGER NOT NULL DEFAULT 0,
+    workflow_paths TEXT[] DEFAULT '{}',
+    latest_run_id BIGINT,
+    latest_run_status VARCHAR(50),
+    latest_run_conclusion VARCHAR(50),
+    latest_run_branch VARCHAR(255),
+    latest_run_at TIMESTAMPTZ,
+    ci_passing BOOLEAN NOT NULL DEFAULT FALSE,
+    fetched_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS repo_security (
+    github_id BIGINT PRIMARY KEY,
+    full_name VARCHAR(255) NOT NULL,
+    advisories_critical INTEGER NOT NULL DEFAULT 0,
+    advisories_high INTEGER NOT NULL DEFAULT 0,
+    advisories_medium INTEGER NOT NULL DEFAULT 0,
+    advisories_low INTEGER NOT NULL DEFAULT 0,
+    dependabot_available BOOLEAN NOT NULL DEFAULT FALSE,
+    dependabot_critical INTEGER,
+    dependabot_high INTEGER,
+    dependabot_medium INTEGER,
+    dependabot_low INTEGER,
+    fetched_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS repo_scorecards (
+    github_id BIGINT PRIMARY KEY,
+    full_name VARCHAR(255) NOT NULL,
+    score DOUBLE PRECISION NOT NULL,
+    scorecard_date VARCHAR(50) NOT NULL,
+    scorecard_version VARCHAR(50),
+    checks JSONB NOT NULL DEFAULT '[]',
+    fetched_at TIMESTAMPTZ DEFAULT NOW()
+);
@@ -0,0 +1,8 @@
+-- Search parameters, tool version and token fingerprint so runs can be reproduced and audited
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS per_page INTEGER;
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS pages_fetched INTEGER;
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS sort VARCHAR(20);
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS sort_order VARCHAR(10);
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS api_base_url TEXT;
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS tool_version VARCHAR(50);
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS token_fingerprint VARCHAR(64);
@@ -0,0 +1,4 @@
+-- Link each successful run to the previous snapshot of the same query for trend analysis
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS previous_query_id UUID REFERENCES query_history(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_query_history_search_query ON query_history(search_query, executed_at);
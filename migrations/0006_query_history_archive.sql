@@ -0,0 +1,3 @@
+-- Object storage location of archived snapshots, used to restore them later
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS archive_url TEXT;
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ;
@@ -0,0 +1,20 @@
+-- Searches queued by `serve`, run by its workers and retried on failure
+
+CREATE TABLE IF NOT EXISTS search_jobs (
+    id UUID PRIMARY KEY,
+    search_query TEXT NOT NULL,
+    per_page INTEGER,
+    pages INTEGER,
+    status VARCHAR(16) NOT NULL DEFAULT 'queued' CHECK (status IN ('queued', 'running', 'failed', 'done')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 3,
+    last_error TEXT,
+    query_id UUID REFERENCES query_history(id) ON DELETE SET NULL,
+    report JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    run_after TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_jobs_queue ON search_jobs(status, run_after);
@@ -0,0 +1,17 @@
+-- Rate limit headers of every GitHub response, for tuning long crawls
+
+CREATE TABLE IF NOT EXISTS rate_limit_log (
+    id BIGSERIAL PRIMARY KEY,
+    query_id UUID REFERENCES query_history(id) ON DELETE CASCADE,
+    observed_at TIMESTAMPTZ NOT NULL,
+    endpoint TEXT NOT NULL,
+    status SMALLINT NOT NULL,
+    resource VARCHAR(64) NOT NULL,
+    quota_limit INTEGER,
+    remaining INTEGER,
+    used INTEGER,
+    reset_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limit_log_query ON rate_limit_log(query_id, observed_at);
+CREATE INDEX IF NOT EXISTS idx_rate_limit_log_observed_at ON rate_limit_log(observed_at);
@@ -0,0 +1,2 @@
+-- Rows rolled back when a batch of the snapshot insert failed
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS rows_failed BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,13 @@
+-- Search items that could not be mapped to a repository row, kept for inspection
+
+CREATE TABLE IF NOT EXISTS quarantined_items (
+    id BIGSERIAL PRIMARY KEY,
+    query_id UUID REFERENCES query_history(id) ON DELETE CASCADE,
+    table_name VARCHAR(100) NOT NULL,
+    github_id BIGINT,
+    error TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    quarantined_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_quarantined_items_query ON quarantined_items(query_id);
@@ -0,0 +1,23 @@
+-- Registry of snapshot tables, kept in step with CREATE TABLE / DROP TABLE and
+-- linked to the query_history run that wrote each one
+
+CREATE TABLE IF NOT EXISTS snapshot_tables (
+    table_name VARCHAR(100) PRIMARY KEY,
+    kind VARCHAR(16) NOT NULL CHECK (kind IN ('repositories', 'topics')),
+    query_id UUID REFERENCES query_history(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_snapshot_tables_query ON snapshot_tables(query_id);
+
+-- Register snapshots created before the registry existed
+INSERT INTO snapshot_tables (table_name, kind, query_id, created_at)
+SELECT DISTINCT ON (h.table_name)
+       h.table_name,
+       CASE WHEN h.table_name LIKE '%topics\_%' THEN 'topics' ELSE 'repositories' END,
+       h.id,
+       h.executed_at
+FROM query_history h
+WHERE to_regclass(quote_ident(h.table_name)) IS NOT NULL
+ORDER BY h.table_name, h.executed_at DESC
+ON CONFLICT (table_name) DO NOTHING;
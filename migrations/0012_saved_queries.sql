@@ -0,0 +1,13 @@
+-- Named search templates for `save-query` and `run`, and the template behind each run
+
+CREATE TABLE IF NOT EXISTS saved_queries (
+    name VARCHAR(100) PRIMARY KEY,
+    template TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS saved_query VARCHAR(100)
+    REFERENCES saved_queries(name) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_query_history_saved_query ON query_history(saved_query);
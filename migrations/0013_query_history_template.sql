@@ -0,0 +1,5 @@
+-- Query as written, before `{{...}}` placeholders were filled in, so runs of a
+-- template with date helpers are recognized as the same query
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS query_template TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_query_history_query_template ON query_history(query_template, executed_at);
@@ -0,0 +1,3 @@
+-- Client-side filters (--exclude-owner, --only-owner, --exclude-forks,
+-- --exclude-archived) a run applied before storing, as command line flags
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS result_filter TEXT;
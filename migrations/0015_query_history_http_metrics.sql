@@ -0,0 +1,3 @@
+-- Client-observed HTTP traffic of a run: requests, bytes, retries,
+-- rate limit sleep, and p50/p95 latency (see HttpMetrics)
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS http_metrics JSONB;
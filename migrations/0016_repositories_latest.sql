@@ -0,0 +1,14 @@
+-- Most recent row per repository across all repository snapshots, kept up
+-- to date by runs with --update-latest. `data` holds the snapshot row as
+-- JSON, so snapshots of any column set can feed the table.
+
+CREATE TABLE IF NOT EXISTS repositories_latest (
+    github_id BIGINT PRIMARY KEY,
+    full_name VARCHAR(255) NOT NULL,
+    table_name VARCHAR(100) NOT NULL,
+    fetched_at TIMESTAMPTZ NOT NULL,
+    data JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_repositories_latest_full_name ON repositories_latest(full_name);
+CREATE INDEX IF NOT EXISTS idx_repositories_latest_table ON repositories_latest(table_name);
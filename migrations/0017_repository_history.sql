@@ -0,0 +1,18 @@
+-- Versioned (SCD type 2) history of tracked repository fields, written by
+-- runs with --track-changes. A repository's current version has
+-- valid_to = NULL; a change closes it and opens a new version.
+
+CREATE TABLE IF NOT EXISTS repository_history (
+    id BIGSERIAL PRIMARY KEY,
+    github_id BIGINT NOT NULL,
+    full_name VARCHAR(255) NOT NULL,
+    state JSONB NOT NULL,
+    valid_from TIMESTAMPTZ NOT NULL,
+    valid_to TIMESTAMPTZ,
+    table_name VARCHAR(100) NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_repository_history_current
+    ON repository_history(github_id) WHERE valid_to IS NULL;
+CREATE INDEX IF NOT EXISTS idx_repository_history_github_id ON repository_history(github_id, valid_from);
+CREATE INDEX IF NOT EXISTS idx_repository_history_full_name ON repository_history(full_name);
@@ -0,0 +1,6 @@
+-- Free-form labels (--label key=value) and a note (--note) to organize runs
+
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS labels JSONB NOT NULL DEFAULT '{}'::jsonb;
+ALTER TABLE query_history ADD COLUMN IF NOT EXISTS note TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_query_history_labels ON query_history USING GIN (labels);
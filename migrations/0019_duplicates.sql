@@ -0,0 +1,18 @@
+-- Likely mirrors and forks not marked as forks, written by the duplicates
+-- command: same repository name under another owner, a near-identical
+-- description, and about the same size as an older repository.
+
+CREATE TABLE IF NOT EXISTS duplicates (
+    id BIGSERIAL PRIMARY KEY,
+    table_name VARCHAR(100) NOT NULL,
+    github_id BIGINT NOT NULL,
+    full_name VARCHAR(255) NOT NULL,
+    original_github_id BIGINT NOT NULL,
+    original_full_name VARCHAR(255) NOT NULL,
+    description_similarity DOUBLE PRECISION NOT NULL,
+    size_difference DOUBLE PRECISION NOT NULL,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (table_name, github_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_duplicates_original ON duplicates(table_name, original_github_id);
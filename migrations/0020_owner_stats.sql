@@ -0,0 +1,17 @@
+-- Per-owner aggregates of a snapshot, written by `analyze owners`: how many
+-- repositories each owner has, their stars, dominant language, and age.
+
+CREATE TABLE IF NOT EXISTS owner_stats (
+    id BIGSERIAL PRIMARY KEY,
+    table_name VARCHAR(100) NOT NULL,
+    owner_login VARCHAR(255) NOT NULL,
+    owner_type VARCHAR(50),
+    repositories BIGINT NOT NULL,
+    total_stars BIGINT NOT NULL,
+    dominant_language VARCHAR(100),
+    avg_repo_age_days DOUBLE PRECISION NOT NULL,
+    analyzed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (table_name, owner_login)
+);
+
+CREATE INDEX IF NOT EXISTS idx_owner_stats_stars ON owner_stats(table_name, total_stars DESC);
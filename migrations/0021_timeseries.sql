@@ -0,0 +1,44 @@
+-- Compact time series written by runs with --timeseries, shaped for the
+-- Grafana PostgreSQL data source: one row per run in run_metrics and one
+-- row per repository and run in star_counts, each keyed by `time`. The
+-- grafana_* views expose them as time/metric/value columns.
+
+CREATE TABLE IF NOT EXISTS run_metrics (
+    query_id UUID PRIMARY KEY,
+    time TIMESTAMPTZ NOT NULL,
+    search_query TEXT NOT NULL,
+    success BOOLEAN NOT NULL,
+    result_count BIGINT NOT NULL,
+    duration_ms BIGINT NOT NULL,
+    pages_fetched INTEGER,
+    api_requests BIGINT,
+    retries BIGINT,
+    rate_limit_sleep_ms BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS idx_run_metrics_time ON run_metrics(time);
+
+CREATE TABLE IF NOT EXISTS star_counts (
+    github_id BIGINT NOT NULL,
+    time TIMESTAMPTZ NOT NULL,
+    full_name VARCHAR(255) NOT NULL,
+    stargazers_count BIGINT NOT NULL,
+    PRIMARY KEY (github_id, time)
+);
+
+CREATE INDEX IF NOT EXISTS idx_star_counts_time ON star_counts(time);
+CREATE INDEX IF NOT EXISTS idx_star_counts_full_name ON star_counts(full_name, time);
+
+CREATE OR REPLACE VIEW grafana_run_metrics AS
+SELECT time, search_query AS metric, result_count, duration_ms, api_requests,
+       CASE WHEN success THEN 1 ELSE 0 END AS succeeded
+FROM run_metrics;
+
+CREATE OR REPLACE VIEW grafana_star_counts AS
+SELECT time, full_name AS metric, stargazers_count AS value
+FROM star_counts;
+
+CREATE OR REPLACE VIEW grafana_star_growth AS
+SELECT time, full_name AS metric,
+       stargazers_count - LAG(stargazers_count) OVER (PARTITION BY github_id ORDER BY time) AS value
+FROM star_counts;
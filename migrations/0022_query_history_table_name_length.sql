@@ -0,0 +1,3 @@
+-- Snapshot names may use all 63 characters of a PostgreSQL identifier
+-- (--output-table, rename), so match the other shared tables' width
+ALTER TABLE query_history ALTER COLUMN table_name TYPE VARCHAR(100);
@@ -0,0 +1,189 @@
+//! # Alerts Module
+//!
+//! Alert rules evaluated after each repository run, comparing the fresh
+//! snapshot with the previous snapshot of the same query. Rules come from a
+//! TOML file given with `--alert-rules`; alerts that fire are printed,
+//! added to the run report, and so delivered with the `--notify-url`
+//! notification.
+//!
+//! ```toml
+//! [[alert]]
+//! name = "10k club"
+//! when = "stars-crossed"
+//! threshold = 10000
+//!
+//! [[alert]]
+//! name = "new in the top 50"
+//! when = "entered-top"
+//! top = 50
+//! ```
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AppError, Result};
+
+/// What makes an alert fire, comparing a repository with its previous snapshot row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(tag = "when", rename_all = "kebab-case")]
+pub enum AlertCondition {
+    /// Stars went from below `threshold` to `threshold` or more
+    StarsCrossed { threshold: i64 },
+    /// The repository is among the `top` most-starred and was not before
+    EnteredTop { top: i64 },
+    /// The repository gained at least `min_gain` stars
+    StarGain { min_gain: i64 },
+}
+
+impl AlertCondition {
+    /// Name used in rule files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertCondition::StarsCrossed { .. } => "stars-crossed",
+            AlertCondition::EnteredTop { .. } => "entered-top",
+            AlertCondition::StarGain { .. } => "star-gain",
+        }
+    }
+
+    /// The rule's number, which must be positive
+    fn limit(&self) -> i64 {
+        match *self {
+            AlertCondition::StarsCrossed { threshold } => threshold,
+            AlertCondition::EnteredTop { top } => top,
+            AlertCondition::StarGain { min_gain } => min_gain,
+        }
+    }
+}
+
+/// A named alert condition
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    #[serde(flatten)]
+    pub condition: AlertCondition,
+}
+
+/// Layout of a rules file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AlertRulesFile {
+    #[serde(default, rename = "alert")]
+    alerts: Vec<AlertRule>,
+}
+
+/// Alert rules of a `--alert-rules` file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AlertRules {
+    rules: Vec<AlertRule>,
+}
+
+impl AlertRules {
+    /// Rules of a TOML document
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let file: AlertRulesFile =
+            toml::from_str(toml).map_err(|error| AppError::configuration(format!("Invalid alert rules: {}", error)))?;
+        for rule in &file.alerts {
+            if rule.name.trim().is_empty() {
+                return Err(AppError::validation("alert", "every rule needs a name"));
+            }
+            if rule.condition.limit() <= 0 {
+                return Err(AppError::validation(
+                    "alert",
+                    format!("'{}': the {} value must be positive", rule.name, rule.condition.as_str()),
+                ));
+            }
+        }
+        Ok(Self { rules: file.alerts })
+    }
+
+    /// Rules of the file at `path`
+    pub fn load(path: &Path) -> Result<Self> {
+        let toml = std::fs::read_to_string(path).map_err(|error| {
+            AppError::configuration(format!("Cannot read alert rules {}: {}", path.display(), error))
+        })?;
+        Self::from_toml(&toml)
+            .map_err(|error| AppError::configuration(format!("{} ({})", error, path.display())))
+    }
+
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+/// A rule that fired for one repository
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Alert {
+    /// Name of the rule
+    pub rule: String,
+    pub full_name: String,
+    pub stargazers_count: i64,
+    /// Stars in the previous snapshot
+    pub previous_stars: Option<i64>,
+    /// Rank by stars in the fresh snapshot, for `entered-top`
+    pub rank: Option<i64>,
+}
+
+impl Alert {
+    /// One-line description for logs and chat messages
+    pub fn message(&self) -> String {
+        let stars = match self.previous_stars {
+            Some(previous) => format!("{} → {} stars", previous, self.stargazers_count),
+            None => format!("{} stars", self.stargazers_count),
+        };
+        match self.rank {
+            Some(rank) => format!("{}: {} is #{} ({})", self.rule, self.full_name, rank, stars),
+            None => format!("{}: {} ({})", self.rule, self.full_name, stars),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alert_rules_from_toml() {
+        let rules = AlertRules::from_toml(
+            "[[alert]]\nname = \"10k\"\nwhen = \"stars-crossed\"\nthreshold = 10000\n\n\
+             [[alert]]\nname = \"top\"\nwhen = \"entered-top\"\ntop = 50\n\n\
+             [[alert]]\nname = \"rising\"\nwhen = \"star-gain\"\nmin_gain = 500\n",
+        )
+        .unwrap();
+        assert_eq!(
+            rules.rules().iter().map(|rule| rule.condition).collect::<Vec<_>>(),
+            vec![
+                AlertCondition::StarsCrossed { threshold: 10000 },
+                AlertCondition::EnteredTop { top: 50 },
+                AlertCondition::StarGain { min_gain: 500 },
+            ]
+        );
+        assert!(AlertRules::from_toml("").unwrap().is_empty());
+
+        assert!(AlertRules::from_toml("[[alert]]\nname = \"x\"\nwhen = \"forked\"\n").is_err());
+        assert!(AlertRules::from_toml("[[alert]]\nname = \"x\"\nwhen = \"entered-top\"\n").is_err());
+        assert!(AlertRules::from_toml("[[alert]]\nname = \"x\"\nwhen = \"entered-top\"\ntop = 0\n").is_err());
+        assert!(AlertRules::from_toml("[[alert]]\nname = \"\"\nwhen = \"entered-top\"\ntop = 5\n").is_err());
+        assert!(AlertRules::from_toml("[[rule]]\nname = \"x\"\n").is_err());
+        assert!(AlertRules::load(Path::new("/nonexistent/alerts.toml")).is_err());
+    }
+
+    #[test]
+    fn test_alert_message() {
+        let alert = Alert {
+            rule: "10k".to_string(),
+            full_name: "serde-rs/serde".to_string(),
+            stargazers_count: 10_012,
+            previous_stars: Some(9_950),
+            rank: None,
+        };
+        assert_eq!(alert.message(), "10k: serde-rs/serde (9950 → 10012 stars)");
+
+        let alert = Alert { rank: Some(3), previous_stars: None, ..alert };
+        assert_eq!(alert.message(), "10k: serde-rs/serde is #3 (10012 stars)");
+    }
+}
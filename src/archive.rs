@@ -0,0 +1,685 @@
+//! # Archive Module
+//!
+//! Long-term storage of snapshots in object storage. `archive <table> --to
+//! <url>` dumps the table to gzip-compressed NDJSON (or Parquet with the
+//! `parquet` feature), uploads it to S3, GCS, or a local directory, and
+//! records the object URL in `query_history.archive_url`. `restore <url>`
+//! reverses this, recreating the snapshot table from the archived file.
+
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use object_store::aws::AmazonS3Builder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutPayload};
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+use std::io::{Read, Write};
+use std::str::FromStr;
+
+use crate::{
+    AppError, ColumnSet, DatabaseManager, InsertOutcome, Repository, RepositoryLicense, RepositoryOwner, Result, TextMatch,
+    ValidationMode, EXPORT_BATCH_SIZE, REPOSITORY_COLUMNS,
+};
+
+/// File format of an archived snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// One JSON object per row, gzip-compressed
+    Ndjson,
+    /// Apache Parquet with a schema inferred from the rows (requires the `parquet` feature)
+    Parquet,
+}
+
+impl ArchiveFormat {
+    /// All supported formats
+    pub const ALL: &'static [ArchiveFormat] = &[ArchiveFormat::Ndjson, ArchiveFormat::Parquet];
+
+    /// Name used on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Ndjson => "ndjson",
+            ArchiveFormat::Parquet => "parquet",
+        }
+    }
+
+    /// File extension of archives in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Ndjson => "ndjson.gz",
+            ArchiveFormat::Parquet => "parquet",
+        }
+    }
+
+    /// Format and table name of an archive file named `<table>.<extension>`
+    pub fn from_file_name(file_name: &str) -> Result<(Self, &str)> {
+        ArchiveFormat::ALL
+            .iter()
+            .find_map(|format| {
+                file_name
+                    .strip_suffix(format.extension())
+                    .and_then(|stem| stem.strip_suffix('.'))
+                    .map(|table| (*format, table))
+            })
+            .ok_or_else(|| {
+                AppError::configuration(format!(
+                    "Cannot tell the format of '{}' (expected <table>.ndjson.gz or <table>.parquet)",
+                    file_name
+                ))
+            })
+    }
+}
+
+impl fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ArchiveFormat {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        ArchiveFormat::ALL
+            .iter()
+            .copied()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| {
+                AppError::configuration(format!(
+                    "Unknown archive format '{}' (supported: ndjson, parquet)",
+                    value
+                ))
+            })
+    }
+}
+
+/// Object store location given as `s3://bucket/prefix`, `gs://bucket/prefix`, or `file:///dir`
+pub struct ArchiveLocation {
+    url: Url,
+    store: Box<dyn ObjectStore>,
+    prefix: ObjectPath,
+}
+
+impl ArchiveLocation {
+    /// Open the store holding the object at `url`, returning it with the object's file name
+    ///
+    /// Plain filesystem paths are accepted as well as `s3://`, `gs://`, and
+    /// `file://` URLs.
+    pub fn parse_object(url: &str) -> Result<(Self, String)> {
+        let url = if url.contains("://") {
+            url.to_string()
+        } else {
+            let path = std::fs::canonicalize(url)?;
+            Url::from_file_path(&path)
+                .map_err(|_| AppError::configuration(format!("Invalid archive path '{}'", path.display())))?
+                .to_string()
+        };
+
+        let (parent, file_name) = url
+            .rsplit_once('/')
+            .filter(|(_, file_name)| !file_name.is_empty())
+            .ok_or_else(|| AppError::configuration(format!("Archive URL '{}' does not name a file", url)))?;
+        Ok((Self::parse(parent)?, file_name.to_string()))
+    }
+
+    /// Open the store behind `url`
+    ///
+    /// S3 and GCS credentials and regions are read from the standard
+    /// `AWS_*` and `GOOGLE_*` environment variables.
+    pub fn parse(url: &str) -> Result<Self> {
+        let url = Url::parse(url)
+            .map_err(|e| AppError::configuration(format!("Invalid archive URL '{}': {}", url, e)))?;
+        let bucket_url = format!("{}://{}", url.scheme(), url.host_str().unwrap_or_default());
+
+        let (store, prefix): (Box<dyn ObjectStore>, &str) = match url.scheme() {
+            "s3" => (
+                Box::new(AmazonS3Builder::from_env().with_url(bucket_url).build().map_err(store_error)?),
+                url.path(),
+            ),
+            "gs" => (
+                Box::new(GoogleCloudStorageBuilder::from_env().with_url(bucket_url).build().map_err(store_error)?),
+                url.path(),
+            ),
+            "file" => {
+                std::fs::create_dir_all(url.path())?;
+                (Box::new(LocalFileSystem::new_with_prefix(url.path()).map_err(store_error)?), "")
+            }
+            scheme => {
+                return Err(AppError::configuration(format!(
+                    "Unsupported archive scheme '{}' (supported: s3, gs, file)",
+                    scheme
+                )))
+            }
+        };
+
+        let prefix = ObjectPath::from(prefix.trim_matches('/'));
+        Ok(Self { url, store, prefix })
+    }
+
+    /// Object path of a file under the location's prefix
+    pub fn object_path(&self, file_name: &str) -> ObjectPath {
+        self.prefix.child(file_name)
+    }
+
+    /// Full URL of a file under the location
+    pub fn object_url(&self, file_name: &str) -> String {
+        format!("{}/{}", self.url.as_str().trim_end_matches('/'), file_name)
+    }
+
+    /// Download `file_name`
+    pub async fn get(&self, file_name: &str) -> Result<Vec<u8>> {
+        let object = self.store.get(&self.object_path(file_name)).await.map_err(store_error)?;
+        Ok(object.bytes().await.map_err(store_error)?.to_vec())
+    }
+
+    /// Upload `bytes` as `file_name`, returning the object URL
+    pub async fn put(&self, file_name: &str, bytes: Vec<u8>) -> Result<String> {
+        self.store
+            .put(&self.object_path(file_name), PutPayload::from(bytes))
+            .await
+            .map_err(store_error)?;
+        Ok(self.object_url(file_name))
+    }
+}
+
+fn store_error(error: object_store::Error) -> AppError {
+    AppError::internal(format!("Object store error: {}", error))
+}
+
+/// Summary of an uploaded archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveOutcome {
+    /// URL of the uploaded object
+    pub url: String,
+    /// Rows written
+    pub rows: u64,
+    /// Size of the uploaded object in bytes
+    pub bytes: usize,
+}
+
+/// Dump a snapshot table, upload it, and record the location in query_history
+pub async fn archive_table(
+    db_manager: &DatabaseManager,
+    location: &ArchiveLocation,
+    table_name: &str,
+    format: ArchiveFormat,
+) -> Result<ArchiveOutcome> {
+    let mut rows = Vec::new();
+    let mut after_id = 0;
+    loop {
+        let batch = db_manager.export_rows_json(table_name, after_id, EXPORT_BATCH_SIZE).await?;
+        let Some((last_id, _)) = batch.last() else {
+            break;
+        };
+        after_id = *last_id;
+        rows.extend(batch.into_iter().map(|(_, row)| row));
+    }
+
+    let bytes = match format {
+        ArchiveFormat::Ndjson => encode_ndjson_gz(&rows)?,
+        ArchiveFormat::Parquet => encode_parquet(&rows)?,
+    };
+    let size = bytes.len();
+    let url = location
+        .put(&format!("{}.{}", table_name, format.extension()), bytes)
+        .await?;
+    db_manager.record_archive(table_name, &url).await?;
+
+    Ok(ArchiveOutcome {
+        url,
+        rows: rows.len() as u64,
+        bytes: size,
+    })
+}
+
+/// Summary of a restored archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoreOutcome {
+    /// Table the rows were restored into
+    pub table_name: String,
+    /// Rows written
+    pub rows: InsertOutcome,
+}
+
+/// Recreate a repository snapshot table from an archive file
+///
+/// The table is created with the columns the archive carries, so a snapshot
+/// taken with `--columns minimal` comes back as a minimal table. Every row
+/// is checked before the table is created, so a corrupt archive leaves the
+/// database untouched: with [`Repository::validate`] when the archive has
+/// every column it reads, otherwise with the checks of the columns it has.
+/// The table is named after the file unless `table_name` is given, and must
+/// not exist yet. Enrichment columns (`head_sha`, `commit_count`) are not
+/// restored.
+pub async fn restore_archive(
+    db_manager: &DatabaseManager,
+    url: &str,
+    table_name: Option<&str>,
+) -> Result<RestoreOutcome> {
+    let (location, file_name) = ArchiveLocation::parse_object(url)?;
+    let (format, archived_table) = ArchiveFormat::from_file_name(&file_name)?;
+    let table_name = table_name.unwrap_or(archived_table).to_string();
+    DatabaseManager::validate_table_name(&table_name)?;
+    if !table_name.starts_with(&db_manager.repository_table_prefix()) {
+        return Err(AppError::configuration(format!(
+            "Only repository snapshots can be restored, not {}",
+            table_name
+        )));
+    }
+    if db_manager.primary_table_exists(&table_name).await? {
+        return Err(AppError::configuration(format!(
+            "Table {} already exists; pass --table to restore under another name",
+            table_name
+        )));
+    }
+
+    let bytes = location.get(&file_name).await?;
+    let rows = match format {
+        ArchiveFormat::Ndjson => decode_ndjson_gz(&bytes)?,
+        ArchiveFormat::Parquet => decode_parquet(&bytes)?,
+    };
+    let present: Vec<String> = match rows.first() {
+        Some(Value::Object(first)) => REPOSITORY_COLUMNS
+            .iter()
+            .filter(|column| first.contains_key(column.name))
+            .map(|column| column.name.to_string())
+            .collect(),
+        _ => ColumnSet::Extended.columns().iter().map(|column| column.name.to_string()).collect(),
+    };
+    let complete = VALIDATED_COLUMNS.iter().all(|column| present.iter().any(|name| name == column));
+    let repositories = rows
+        .into_iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let repository = SnapshotRow::deserialize(row)
+                .map_err(|e| AppError::validation(format!("row {}", index + 1), e.to_string()))?
+                .into_repository();
+            let checked = if complete { repository.validate() } else { validate_present(&repository, &present) };
+            checked.map_err(|e| {
+                AppError::validation(format!("row {} ({})", index + 1, repository.full_name), e.to_string())
+            })?;
+            Ok(repository)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // Rows were checked above against the columns they carry
+    let mut restorer = db_manager.clone().with_columns(&ColumnSet::Custom(present))?;
+    if !complete {
+        restorer = restorer.with_validation(ValidationMode::Off);
+    }
+    restorer.create_repository_table(&table_name).await?;
+    // Index the rows that made it in even if the insert failed
+    let rows = restorer.insert_repositories(&table_name, &repositories).await;
+    let indexed = restorer.create_deferred_indexes(&table_name).await;
+    let rows = rows?;
+    indexed?;
+    Ok(RestoreOutcome { table_name, rows })
+}
+
+/// Columns [`Repository::validate`] reads besides `full_name` and the counts
+const VALIDATED_COLUMNS: &[&str] = &[
+    "name",
+    "html_url",
+    "clone_url",
+    "ssh_url",
+    "default_branch",
+    "visibility",
+    "owner_login",
+    "owner_type",
+    "owner_avatar_url",
+    "owner_html_url",
+];
+
+/// Checks of [`Repository::validate`] that apply to the archived columns `present`
+fn validate_present(repository: &Repository, present: &[String]) -> Result<()> {
+    let has = |column: &str| present.iter().any(|name| name == column);
+    if repository.full_name.is_empty() {
+        return Err(AppError::validation("full_name", "cannot be empty"));
+    }
+    let text = [
+        ("name", &repository.name),
+        ("html_url", &repository.html_url),
+        ("default_branch", &repository.default_branch),
+        ("owner_login", &repository.owner.login),
+    ];
+    if let Some((column, _)) = text.iter().find(|(column, value)| has(column) && value.is_empty()) {
+        return Err(AppError::validation(*column, "cannot be empty"));
+    }
+    if has("html_url") && !repository.html_url.starts_with("https://github.com/") {
+        return Err(AppError::validation("html_url", "must be a valid GitHub URL"));
+    }
+    if has("visibility") && !["public", "private", "internal"].contains(&repository.visibility.as_str()) {
+        return Err(AppError::validation("visibility", "must be 'public', 'private', or 'internal'"));
+    }
+    let counts = [
+        ("size_kb", repository.size),
+        ("stargazers_count", repository.stargazers_count),
+        ("watchers_count", repository.watchers_count),
+        ("forks_count", repository.forks_count),
+        ("open_issues_count", repository.open_issues_count),
+    ];
+    if let Some((column, _)) = counts.iter().find(|(column, value)| has(column) && *value < 0) {
+        return Err(AppError::validation(*column, "cannot be negative"));
+    }
+    Ok(())
+}
+
+/// A row of a repository snapshot table as archived by `row_to_json`
+///
+/// Only the columns every [`ColumnSet`] has are required; columns a
+/// `minimal`, `core`, or custom table lacks take the same defaults as
+/// `Repository`'s `FromRow` (empty strings, zero, `false`, `None`, and the
+/// Unix epoch for dates).
+#[derive(Debug, Deserialize)]
+struct SnapshotRow {
+    github_id: i64,
+    full_name: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    html_url: String,
+    #[serde(default)]
+    clone_url: String,
+    #[serde(default)]
+    ssh_url: String,
+    #[serde(default)]
+    size_kb: i64,
+    #[serde(default)]
+    stargazers_count: i64,
+    #[serde(default)]
+    watchers_count: i64,
+    #[serde(default)]
+    forks_count: i64,
+    #[serde(default)]
+    open_issues_count: i64,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    default_branch: String,
+    #[serde(default)]
+    visibility: String,
+    #[serde(default)]
+    private: bool,
+    #[serde(default)]
+    fork: bool,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    disabled: bool,
+    #[serde(default)]
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pushed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    owner_id: i64,
+    #[serde(default)]
+    owner_login: String,
+    #[serde(default)]
+    owner_type: String,
+    #[serde(default)]
+    owner_avatar_url: String,
+    #[serde(default)]
+    owner_html_url: String,
+    #[serde(default)]
+    owner_site_admin: bool,
+    #[serde(default)]
+    license_key: Option<String>,
+    #[serde(default)]
+    license_name: Option<String>,
+    #[serde(default)]
+    license_spdx_id: Option<String>,
+    #[serde(default)]
+    license_url: Option<String>,
+    #[serde(default)]
+    topics: Option<Vec<String>>,
+    #[serde(default)]
+    has_issues: bool,
+    #[serde(default)]
+    has_projects: bool,
+    #[serde(default)]
+    has_wiki: bool,
+    #[serde(default)]
+    has_pages: bool,
+    #[serde(default)]
+    has_downloads: bool,
+    #[serde(default)]
+    score: Option<f64>,
+    #[serde(default)]
+    text_matches: Option<Vec<TextMatch>>,
+    #[serde(default)]
+    matched_language_filter: Option<String>,
+}
+
+impl SnapshotRow {
+    fn into_repository(self) -> Repository {
+        let license = match (self.license_key, self.license_name) {
+            (Some(key), Some(name)) => Some(RepositoryLicense {
+                key,
+                name,
+                spdx_id: self.license_spdx_id,
+                url: self.license_url,
+            }),
+            _ => None,
+        };
+
+        Repository {
+            id: self.github_id,
+            full_name: self.full_name,
+            name: self.name,
+            description: self.description,
+            html_url: self.html_url,
+            clone_url: self.clone_url,
+            ssh_url: self.ssh_url,
+            size: self.size_kb,
+            stargazers_count: self.stargazers_count,
+            watchers_count: self.watchers_count,
+            forks_count: self.forks_count,
+            open_issues_count: self.open_issues_count,
+            language: self.language,
+            default_branch: self.default_branch,
+            visibility: self.visibility,
+            private: self.private,
+            fork: self.fork,
+            archived: self.archived,
+            disabled: self.disabled,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            pushed_at: self.pushed_at,
+            owner: RepositoryOwner {
+                id: self.owner_id,
+                login: self.owner_login,
+                owner_type: self.owner_type,
+                avatar_url: self.owner_avatar_url,
+                html_url: self.owner_html_url,
+                site_admin: self.owner_site_admin,
+            },
+            license,
+            topics: self.topics.unwrap_or_default(),
+            has_issues: self.has_issues,
+            has_projects: self.has_projects,
+            has_wiki: self.has_wiki,
+            has_pages: self.has_pages,
+            has_downloads: self.has_downloads,
+            score: self.score,
+            text_matches: self.text_matches.unwrap_or_default(),
+            matched_language_filter: self.matched_language_filter,
+        }
+    }
+}
+
+/// Rows of a gzip-compressed NDJSON archive
+pub fn decode_ndjson_gz(bytes: &[u8]) -> Result<Vec<Value>> {
+    let mut text = String::new();
+    GzDecoder::new(bytes).read_to_string(&mut text)?;
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+/// Rows of a Parquet archive
+#[cfg(feature = "parquet")]
+pub fn decode_parquet(bytes: &[u8]) -> Result<Vec<Value>> {
+    use arrow::json::ArrayWriter;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let parquet_error = |e: &dyn fmt::Display| AppError::internal(format!("Failed to read Parquet: {}", e));
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::copy_from_slice(bytes))
+        .and_then(|builder| builder.build())
+        .map_err(|e| parquet_error(&e))?;
+    let mut writer = ArrayWriter::new(Vec::new());
+    for batch in reader {
+        writer.write(&batch.map_err(|e| parquet_error(&e))?).map_err(|e| parquet_error(&e))?;
+    }
+    writer.finish().map_err(|e| parquet_error(&e))?;
+
+    let json = writer.into_inner();
+    if json.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// Rows of a Parquet archive (unavailable: built without the `parquet` feature)
+#[cfg(not(feature = "parquet"))]
+pub fn decode_parquet(_bytes: &[u8]) -> Result<Vec<Value>> {
+    Err(AppError::configuration(
+        "Restoring Parquet archives requires building with the `parquet` feature",
+    ))
+}
+
+/// Gzip-compressed newline-delimited JSON
+pub fn encode_ndjson_gz(rows: &[String]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for row in rows {
+        encoder.write_all(row.as_bytes())?;
+        encoder.write_all(b"\n")?;
+    }
+    Ok(encoder.finish()?)
+}
+
+/// Parquet file with a schema inferred from the JSON rows
+#[cfg(feature = "parquet")]
+pub fn encode_parquet(rows: &[String]) -> Result<Vec<u8>> {
+    use arrow::json::reader::{infer_json_schema_from_iterator, ReaderBuilder};
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+
+    let parquet_error = |e: &dyn fmt::Display| AppError::internal(format!("Failed to write Parquet: {}", e));
+
+    let values: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|row| serde_json::from_str(row))
+        .collect::<std::result::Result<_, _>>()?;
+    let schema = Arc::new(
+        infer_json_schema_from_iterator(values.iter().map(Ok)).map_err(|e| parquet_error(&e))?,
+    );
+
+    let mut decoder = ReaderBuilder::new(schema.clone()).build_decoder().map_err(|e| parquet_error(&e))?;
+    decoder.serialize(&values).map_err(|e| parquet_error(&e))?;
+
+    let mut writer = ArrowWriter::try_new(Vec::new(), schema, None).map_err(|e| parquet_error(&e))?;
+    while let Some(batch) = decoder.flush().map_err(|e| parquet_error(&e))? {
+        writer.write(&batch).map_err(|e| parquet_error(&e))?;
+    }
+    writer.into_inner().map_err(|e| parquet_error(&e))
+}
+
+/// Parquet file with a schema inferred from the JSON rows (unavailable: built without the `parquet` feature)
+#[cfg(not(feature = "parquet"))]
+pub fn encode_parquet(_rows: &[String]) -> Result<Vec<u8>> {
+    Err(AppError::configuration(
+        "--format parquet requires building with the `parquet` feature",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_archive_format_round_trip() {
+        for format in ArchiveFormat::ALL {
+            assert_eq!(format.as_str().parse::<ArchiveFormat>().unwrap(), *format);
+        }
+        assert!("csv".parse::<ArchiveFormat>().is_err());
+    }
+
+    #[test]
+    fn test_encode_ndjson_gz() {
+        let rows = vec!["{\"id\":1}".to_string(), "{\"id\":2}".to_string()];
+        let mut decoded = String::new();
+        GzDecoder::new(encode_ndjson_gz(&rows).unwrap().as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, "{\"id\":1}\n{\"id\":2}\n");
+    }
+
+    #[test]
+    fn test_archive_format_from_file_name() {
+        assert_eq!(
+            ArchiveFormat::from_file_name("repos_1.ndjson.gz").unwrap(),
+            (ArchiveFormat::Ndjson, "repos_1")
+        );
+        assert_eq!(
+            ArchiveFormat::from_file_name("repos_1.parquet").unwrap(),
+            (ArchiveFormat::Parquet, "repos_1")
+        );
+        assert!(ArchiveFormat::from_file_name("repos_1.csv").is_err());
+    }
+
+    #[test]
+    fn test_ndjson_round_trip() {
+        let rows = vec!["{\"id\":1}".to_string(), "{\"id\":2}".to_string()];
+        let decoded = decode_ndjson_gz(&encode_ndjson_gz(&rows).unwrap()).unwrap();
+        assert_eq!(decoded, vec![serde_json::json!({"id": 1}), serde_json::json!({"id": 2})]);
+    }
+
+    #[test]
+    fn test_archive_location_paths() {
+        let location = ArchiveLocation::parse("s3://snapshots/github/daily/").unwrap();
+        assert_eq!(location.object_path("repos_1.ndjson.gz").as_ref(), "github/daily/repos_1.ndjson.gz");
+        assert_eq!(location.object_url("repos_1.ndjson.gz"), "s3://snapshots/github/daily/repos_1.ndjson.gz");
+
+        assert!(ArchiveLocation::parse("ftp://example.com/archives").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_put_to_local_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let location = ArchiveLocation::parse(&format!("file://{}", dir.path().display())).unwrap();
+        let url = location.put("repos_1.ndjson.gz", b"data".to_vec()).await.unwrap();
+        assert!(url.ends_with("/repos_1.ndjson.gz"));
+        assert_eq!(std::fs::read(dir.path().join("repos_1.ndjson.gz")).unwrap(), b"data");
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_encode_parquet() {
+        let rows = vec!["{\"id\":1,\"name\":\"a\"}".to_string(), "{\"id\":2,\"name\":null}".to_string()];
+        let bytes = encode_parquet(&rows).unwrap();
+        assert_eq!(&bytes[..4], b"PAR1");
+        assert_eq!(
+            decode_parquet(&bytes).unwrap(),
+            vec![serde_json::json!({"id": 1, "name": "a"}), serde_json::json!({"id": 2})]
+        );
+    }
+
+    #[cfg(not(feature = "parquet"))]
+    #[test]
+    fn test_encode_parquet_without_feature() {
+        assert!(encode_parquet(&[]).unwrap_err().to_string().contains("parquet"));
+    }
+}
@@ -0,0 +1,244 @@
+//! # Audit Module
+//!
+//! Audit trail written by `history export`: one record per recorded run,
+//! saying what was queried when, by which tool version and token, how many
+//! API requests it took, and which snapshot table it wrote, with that
+//! table's entry in the `snapshot_tables` registry. Records are written as
+//! CSV for spreadsheets or as a JSON array.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{AppError, Result};
+
+/// File format of an exported audit trail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// A JSON array of records
+    Json,
+}
+
+impl AuditFormat {
+    /// All supported formats
+    pub const ALL: &'static [AuditFormat] = &[AuditFormat::Csv, AuditFormat::Json];
+
+    /// Name used on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditFormat::Csv => "csv",
+            AuditFormat::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for AuditFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditFormat {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        AuditFormat::ALL
+            .iter()
+            .copied()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| {
+                AppError::configuration(format!("Unknown audit format '{}' (supported: csv, json)", value))
+            })
+    }
+}
+
+/// One recorded run with the registry entry of the table it wrote
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditRecord {
+    pub query_id: uuid::Uuid,
+    pub executed_at: DateTime<Utc>,
+    pub search_query: String,
+    /// Saved query the search was rendered from
+    pub saved_query: Option<String>,
+    pub success: bool,
+    pub error_message: Option<String>,
+    pub result_count: i64,
+    pub rows_failed: i64,
+    pub duration_ms: i64,
+    /// Version of this tool that ran the query
+    pub tool_version: Option<String>,
+    pub api_base_url: Option<String>,
+    /// Truncated SHA-256 of the token used
+    pub token_fingerprint: Option<String>,
+    /// HTTP requests sent, including retries; `None` for runs recorded before they were counted
+    pub api_requests: Option<u64>,
+    pub retries: Option<u64>,
+    pub rate_limit_sleep_ms: Option<u64>,
+    /// `X-Request-Id` the run sent with its requests
+    pub request_id: Option<String>,
+    pub table_name: String,
+    /// `repositories` or `topics` while the table is registered
+    pub table_kind: Option<String>,
+    /// When the table was registered; `None` once it was dropped or renamed
+    pub table_registered_at: Option<DateTime<Utc>>,
+    pub archive_url: Option<String>,
+    pub labels: BTreeMap<String, String>,
+    pub note: Option<String>,
+}
+
+/// Column names of the CSV format, in order
+const CSV_COLUMNS: &[&str] = &[
+    "query_id",
+    "executed_at",
+    "search_query",
+    "saved_query",
+    "success",
+    "error_message",
+    "result_count",
+    "rows_failed",
+    "duration_ms",
+    "tool_version",
+    "api_base_url",
+    "token_fingerprint",
+    "api_requests",
+    "retries",
+    "rate_limit_sleep_ms",
+    "request_id",
+    "table_name",
+    "table_kind",
+    "table_registered_at",
+    "archive_url",
+    "labels",
+    "note",
+];
+
+impl AuditRecord {
+    /// Values in [`CSV_COLUMNS`] order; missing values are empty and labels are `key=value;...`
+    fn csv_values(&self) -> Vec<String> {
+        let text = |value: &Option<String>| value.clone().unwrap_or_default();
+        let number = |value: Option<u64>| value.map(|n| n.to_string()).unwrap_or_default();
+        vec![
+            self.query_id.to_string(),
+            self.executed_at.to_rfc3339(),
+            self.search_query.clone(),
+            text(&self.saved_query),
+            self.success.to_string(),
+            text(&self.error_message),
+            self.result_count.to_string(),
+            self.rows_failed.to_string(),
+            self.duration_ms.to_string(),
+            text(&self.tool_version),
+            text(&self.api_base_url),
+            text(&self.token_fingerprint),
+            number(self.api_requests),
+            number(self.retries),
+            number(self.rate_limit_sleep_ms),
+            text(&self.request_id),
+            self.table_name.clone(),
+            text(&self.table_kind),
+            self.table_registered_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+            text(&self.archive_url),
+            self.labels
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(";"),
+            text(&self.note),
+        ]
+    }
+}
+
+/// `records` in `format`
+pub fn render_audit(records: &[AuditRecord], format: AuditFormat) -> Result<String> {
+    match format {
+        AuditFormat::Csv => {
+            let mut out = CSV_COLUMNS.join(",");
+            out.push('\n');
+            for record in records {
+                let values: Vec<String> = record.csv_values().iter().map(|value| csv_field(value)).collect();
+                out.push_str(&values.join(","));
+                out.push('\n');
+            }
+            Ok(out)
+        }
+        AuditFormat::Json => Ok(format!("{}\n", serde_json::to_string_pretty(records)?)),
+    }
+}
+
+/// Quote a CSV field when it holds a separator, quote, or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> AuditRecord {
+        AuditRecord {
+            query_id: uuid::Uuid::nil(),
+            executed_at: "2024-01-02T03:04:05Z".parse().unwrap(),
+            search_query: "language:rust stars:>100".to_string(),
+            saved_query: None,
+            success: false,
+            error_message: Some("HTTP 502, \"Bad Gateway\"".to_string()),
+            result_count: 0,
+            rows_failed: 0,
+            duration_ms: 1500,
+            tool_version: Some("0.1.0".to_string()),
+            api_base_url: Some("https://api.github.com".to_string()),
+            token_fingerprint: Some("0123456789abcdef".to_string()),
+            api_requests: Some(3),
+            retries: Some(2),
+            rate_limit_sleep_ms: None,
+            request_id: Some("crawl-7".to_string()),
+            table_name: "repos_20240102030405".to_string(),
+            table_kind: None,
+            table_registered_at: None,
+            archive_url: None,
+            labels: BTreeMap::from([("env".to_string(), "ci".to_string()), ("team".to_string(), "infra".to_string())]),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_audit_format_from_str() {
+        assert_eq!("csv".parse::<AuditFormat>().unwrap(), AuditFormat::Csv);
+        assert_eq!("json".parse::<AuditFormat>().unwrap(), AuditFormat::Json);
+        assert!("xlsx".parse::<AuditFormat>().is_err());
+    }
+
+    #[test]
+    fn test_render_audit_csv() {
+        let csv = render_audit(&[record()], AuditFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].split(',').count(), CSV_COLUMNS.len());
+        assert_eq!(
+            lines[1],
+            "00000000-0000-0000-0000-000000000000,2024-01-02T03:04:05+00:00,language:rust stars:>100,,false,\
+             \"HTTP 502, \"\"Bad Gateway\"\"\",0,0,1500,0.1.0,https://api.github.com,0123456789abcdef,3,2,,crawl-7,\
+             repos_20240102030405,,,,env=ci;team=infra,"
+        );
+        assert_eq!(render_audit(&[], AuditFormat::Csv).unwrap().lines().count(), 1);
+    }
+
+    #[test]
+    fn test_render_audit_json() {
+        let json = render_audit(&[record()], AuditFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[0]["api_requests"], 3);
+        assert_eq!(value[0]["tool_version"], "0.1.0");
+        assert_eq!(value[0]["labels"]["team"], "infra");
+        assert_eq!(value[0]["table_kind"], serde_json::Value::Null);
+    }
+}
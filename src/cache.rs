@@ -0,0 +1,121 @@
+//! # Response Cache Module
+//!
+//! Optional on-disk cache of GitHub search responses. Re-running a query
+//! during iterative analysis would otherwise spend search quota on pages
+//! fetched minutes ago; with a cache, a page requested again within the TTL
+//! is read from disk. Entries are keyed by a SHA-256 of everything that
+//! shapes the response (API URL, endpoint, query, paging, media type, and a
+//! token fingerprint), one file per page, and expire by modification time.
+//! The cache is best-effort: an unreadable or unwritable entry is treated as
+//! a miss.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use sha2::{Digest, Sha256};
+
+/// Directory name under the user's cache directory
+const CACHE_DIR_NAME: &str = "github-pg-query";
+
+/// Search responses cached on disk for a limited time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl ResponseCache {
+    /// Cache in `dir` whose entries are used for `ttl` after they were written
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self { dir: dir.into(), ttl }
+    }
+
+    /// `$XDG_CACHE_HOME/github-pg-query`, or `~/.cache/github-pg-query`
+    pub fn default_dir() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+        Some(base.join(CACHE_DIR_NAME))
+    }
+
+    /// Directory holding the entries
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// How long an entry is used after it was written
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Key of the response identified by `parts`
+    pub fn key(parts: &[&str]) -> String {
+        let mut hasher = Sha256::new();
+        for part in parts {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// Cached body for `key`, if one was written within the TTL
+    pub async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.path(key);
+        let modified = tokio::fs::metadata(&path).await.ok()?.modified().ok()?;
+        let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+        if age >= self.ttl {
+            return None;
+        }
+        tokio::fs::read(&path).await.ok()
+    }
+
+    /// Store `body` under `key`
+    ///
+    /// The body is written to a temporary file and renamed into place, so
+    /// concurrent runs never read a partial entry. Failures are logged and
+    /// otherwise ignored.
+    pub async fn put(&self, key: &str, body: &[u8]) {
+        let path = self.path(key);
+        let temporary = self.dir.join(format!("{}.{}.tmp", key, fastrand::u64(..)));
+        let result = async {
+            tokio::fs::create_dir_all(&self.dir).await?;
+            tokio::fs::write(&temporary, body).await?;
+            tokio::fs::rename(&temporary, &path).await
+        }
+        .await;
+        if let Err(error) = result {
+            let _ = tokio::fs::remove_file(&temporary).await;
+            tracing::warn!(path = %path.display(), %error, "could not write response cache entry");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_entries_expire_after_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path().join("cache"), Duration::from_secs(600));
+        let key = ResponseCache::key(&["https://api.github.com", "repositories", "language:rust", "30", "1"]);
+        assert_eq!(cache.get(&key).await, None);
+
+        cache.put(&key, b"{\"total_count\":0}").await;
+        assert_eq!(cache.get(&key).await.as_deref(), Some(&b"{\"total_count\":0}"[..]));
+
+        let expired = ResponseCache::new(dir.path().join("cache"), Duration::ZERO);
+        assert_eq!(expired.get(&key).await, None);
+    }
+
+    #[test]
+    fn test_key_separates_parts() {
+        assert_ne!(ResponseCache::key(&["ab", "c"]), ResponseCache::key(&["a", "bc"]));
+        assert_eq!(ResponseCache::key(&["a"]).len(), 64);
+    }
+}
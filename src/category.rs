@@ -0,0 +1,206 @@
+//! # Category Module
+//!
+//! Rule-based classification of repositories into ecosystems such as
+//! `web-framework`, `cli`, `library`, and `infra`, stored in the snapshot's
+//! `category` column by `--enrich category`. Rules are data: the built-in
+//! set lives in `rules/categories.toml`, and `--category-rules FILE` adds
+//! rules in the same format that are tried first.
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::{AppError, Repository, Result};
+
+/// Built-in rules, tried after any from `--category-rules`
+pub const DEFAULT_CATEGORY_RULES: &str = include_str!("../rules/categories.toml");
+
+/// Longest category name, the width of the `category` column
+pub const MAX_CATEGORY_LENGTH: usize = 64;
+
+/// One rule: the category a repository gets when every set list has a hit
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryRule {
+    pub category: String,
+    /// Topics, any of which matches
+    #[serde(default)]
+    pub topics: Vec<String>,
+    /// Primary languages, any of which matches (case-insensitive)
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// Root file names, any of which matches; `*.ext` matches a suffix
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+/// Layout of a rules file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    /// Try the built-in rules after the file's own
+    #[serde(default = "extend_defaults")]
+    extend_defaults: bool,
+    #[serde(default, rename = "rule")]
+    rules: Vec<CategoryRule>,
+}
+
+fn extend_defaults() -> bool {
+    true
+}
+
+/// Outcome of [`CategoryRules::classify`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Classification<'a> {
+    /// The first matching rule's category
+    Category(&'a str),
+    /// No rule matches
+    Unclassified,
+    /// A rule that could still match needs the repository's root file names
+    NeedsFiles,
+}
+
+/// Ordered classification rules
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CategoryRules {
+    rules: Vec<CategoryRule>,
+}
+
+impl CategoryRules {
+    /// The built-in rules
+    pub fn builtin() -> Self {
+        Self::from_toml(DEFAULT_CATEGORY_RULES).expect("built-in category rules are valid")
+    }
+
+    /// Rules of a TOML document, without the built-in ones
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        Ok(Self { rules: parse(toml)?.rules })
+    }
+
+    /// Rules of `path` followed by the built-in rules, or the built-in rules alone
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::builtin());
+        };
+        let toml = std::fs::read_to_string(path).map_err(|error| {
+            AppError::configuration(format!("Cannot read category rules {}: {}", path.display(), error))
+        })?;
+        let file = parse(&toml)
+            .map_err(|error| AppError::configuration(format!("Invalid category rules {}: {}", path.display(), error)))?;
+        let mut rules = file.rules;
+        if file.extend_defaults {
+            rules.extend(Self::builtin().rules);
+        }
+        Ok(Self { rules })
+    }
+
+    pub fn rules(&self) -> &[CategoryRule] {
+        &self.rules
+    }
+
+    /// Category of `repo`, given its root file names once they are known
+    ///
+    /// Returns [`Classification::NeedsFiles`] when `files` is `None` and the
+    /// first rule that could still match looks at files, so callers only
+    /// list a repository's files when the result depends on them.
+    pub fn classify<'a>(&'a self, repo: &Repository, files: Option<&[String]>) -> Classification<'a> {
+        for rule in &self.rules {
+            let topics = rule.topics.is_empty() || repo.topics.iter().any(|topic| rule.topics.contains(topic));
+            let language = rule.languages.is_empty()
+                || repo
+                    .language
+                    .as_deref()
+                    .is_some_and(|language| rule.languages.iter().any(|l| l.eq_ignore_ascii_case(language)));
+            if !topics || !language {
+                continue;
+            }
+            if rule.files.is_empty() {
+                return Classification::Category(&rule.category);
+            }
+            let Some(files) = files else {
+                return Classification::NeedsFiles;
+            };
+            if files.iter().any(|file| rule.files.iter().any(|pattern| file_matches(pattern, file))) {
+                return Classification::Category(&rule.category);
+            }
+        }
+        Classification::Unclassified
+    }
+}
+
+/// Parse and check a rules document
+fn parse(toml: &str) -> Result<RulesFile> {
+    let file: RulesFile =
+        toml::from_str(toml).map_err(|error| AppError::configuration(format!("Invalid category rules: {}", error)))?;
+    for rule in &file.rules {
+        let valid = !rule.category.is_empty()
+            && rule.category.len() <= MAX_CATEGORY_LENGTH
+            && rule.category.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !valid {
+            return Err(AppError::validation(
+                "category",
+                format!("'{}' must be 1-{} lowercase letters, digits, '-' or '_'", rule.category, MAX_CATEGORY_LENGTH),
+            ));
+        }
+    }
+    Ok(file)
+}
+
+/// Whether a root file name matches a rule pattern, case-insensitively
+fn file_matches(pattern: &str, file: &str) -> bool {
+    match pattern.strip_prefix('*') {
+        Some(suffix) => file.to_ascii_lowercase().ends_with(&suffix.to_ascii_lowercase()),
+        None => pattern.eq_ignore_ascii_case(file),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repository(language: Option<&str>, topics: &[&str]) -> Repository {
+        let mut repo = crate::models::test_repository(1, "octocat/example");
+        repo.language = language.map(str::to_string);
+        repo.topics = topics.iter().map(|topic| topic.to_string()).collect();
+        repo
+    }
+
+    #[test]
+    fn test_builtin_rules_classify() {
+        let rules = CategoryRules::builtin();
+        let files = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(rules.classify(&repository(Some("Rust"), &["cli", "git"]), None), Classification::Category("cli"));
+        assert_eq!(rules.classify(&repository(Some("Go"), &["web-framework"]), None), Classification::Category("web-framework"));
+        assert_eq!(rules.classify(&repository(Some("HCL"), &[]), None), Classification::Category("infra"));
+
+        let plain = repository(Some("Rust"), &[]);
+        assert_eq!(rules.classify(&plain, None), Classification::NeedsFiles);
+        assert_eq!(rules.classify(&plain, Some(&files(&["Cargo.toml", "src"]))), Classification::Category("library"));
+        assert_eq!(rules.classify(&plain, Some(&files(&["modules", "network.TF"]))), Classification::Category("infra"));
+        assert_eq!(rules.classify(&plain, Some(&files(&["README.md"]))), Classification::Unclassified);
+    }
+
+    #[test]
+    fn test_custom_rules_come_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rules.toml");
+        std::fs::write(&path, "[[rule]]\ncategory = \"game\"\ntopics = [\"game\"]\nlanguages = [\"rust\"]\n").unwrap();
+        let rules = CategoryRules::load(Some(&path)).unwrap();
+        assert_eq!(rules.rules().len(), CategoryRules::builtin().rules().len() + 1);
+        assert_eq!(rules.classify(&repository(Some("Rust"), &["game", "cli"]), None), Classification::Category("game"));
+        assert_eq!(rules.classify(&repository(Some("C"), &["game", "cli"]), None), Classification::Category("cli"));
+
+        std::fs::write(&path, "extend_defaults = false\n[[rule]]\ncategory = \"game\"\ntopics = [\"game\"]\n").unwrap();
+        let rules = CategoryRules::load(Some(&path)).unwrap();
+        assert_eq!(rules.classify(&repository(Some("Rust"), &["cli"]), None), Classification::Unclassified);
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        assert!(CategoryRules::from_toml("[[rule]]\ncategory = \"Web Framework\"\n").is_err());
+        assert!(CategoryRules::from_toml("[[rule]]\ncategory = \"cli\"\nkeywords = [\"x\"]\n").is_err());
+        assert!(CategoryRules::from_toml("[[rule]\n").is_err());
+        assert!(CategoryRules::load(Some(Path::new("/nonexistent/rules.toml"))).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+//! # Fault Injection Module
+//!
+//! Deliberate failures for hardening the retry, circuit breaker, and
+//! transaction logic: synthetic 500 responses, truncated bodies, slow
+//! responses, and database connections dropped before a batch commits.
+//! Faults are only injected by binaries built with the `chaos` feature, from
+//! integration tests or the hidden `--chaos` flag, e.g.
+//! `--chaos http-500=0.2,truncate=0.05,slow=0.1,delay=1500,db-drop=0.1,seed=7`.
+//! A seed makes a run's faults reproducible.
+
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::header::{HeaderValue, CONTENT_LENGTH};
+use reqwest::{ResponseBuilderExt, StatusCode};
+
+use crate::{AppError, Result};
+
+/// Delay of slow responses when the spec sets none
+const DEFAULT_SLOW_DELAY: Duration = Duration::from_millis(2000);
+
+/// How often each kind of fault is injected
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaultConfig {
+    /// Share of GitHub requests answered with a synthetic 500 (`http-500`)
+    pub server_error_rate: f64,
+    /// Share of GitHub responses whose body is cut short (`truncate`)
+    pub truncate_rate: f64,
+    /// Share of GitHub requests delayed by `slow_delay` (`slow`)
+    pub slow_rate: f64,
+    /// Delay of slow requests (`delay`, in milliseconds)
+    pub slow_delay: Duration,
+    /// Share of insert batches whose connection drops before commit (`db-drop`)
+    pub db_drop_rate: f64,
+    /// Seed of the fault sequence; random when `None`
+    pub seed: Option<u64>,
+}
+
+impl Default for FaultConfig {
+    fn default() -> Self {
+        Self {
+            server_error_rate: 0.0,
+            truncate_rate: 0.0,
+            slow_rate: 0.0,
+            slow_delay: DEFAULT_SLOW_DELAY,
+            db_drop_rate: 0.0,
+            seed: None,
+        }
+    }
+}
+
+impl FaultConfig {
+    /// Check that every rate is between 0 and 1
+    pub fn validate(&self) -> Result<()> {
+        for (field, rate) in [
+            ("http-500", self.server_error_rate),
+            ("truncate", self.truncate_rate),
+            ("slow", self.slow_rate),
+            ("db-drop", self.db_drop_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(AppError::validation(field, "must be a rate between 0 and 1"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses `key=value` pairs separated by commas
+impl FromStr for FaultConfig {
+    type Err = AppError;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        let mut config = FaultConfig::default();
+        for pair in spec.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| AppError::configuration(format!("Invalid fault '{}': expected key=value", pair)))?;
+            let invalid = || AppError::configuration(format!("Invalid value '{}' for fault '{}'", value, key));
+            let rate = || value.parse::<f64>().map_err(|_| invalid());
+            match key {
+                "http-500" => config.server_error_rate = rate()?,
+                "truncate" => config.truncate_rate = rate()?,
+                "slow" => config.slow_rate = rate()?,
+                "delay" => config.slow_delay = Duration::from_millis(value.parse().map_err(|_| invalid())?),
+                "db-drop" => config.db_drop_rate = rate()?,
+                "seed" => config.seed = Some(value.parse().map_err(|_| invalid())?),
+                _ => {
+                    return Err(AppError::configuration(format!(
+                        "Unknown fault '{}' (supported: http-500, truncate, slow, delay, db-drop, seed)",
+                        key
+                    )))
+                }
+            }
+        }
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// Faults injected so far
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultCounts {
+    pub server_errors: u64,
+    pub truncated_bodies: u64,
+    pub slow_responses: u64,
+    pub dropped_connections: u64,
+}
+
+/// Injects the faults of a [`FaultConfig`]; clones share one fault sequence
+#[derive(Debug, Clone)]
+pub struct FaultInjector {
+    config: FaultConfig,
+    rng: Arc<Mutex<fastrand::Rng>>,
+    counts: Arc<Mutex<FaultCounts>>,
+}
+
+impl FaultInjector {
+    /// Create an injector for `config`
+    #[cfg(feature = "chaos")]
+    pub fn new(config: FaultConfig) -> Result<Self> {
+        config.validate()?;
+        let rng = match config.seed {
+            Some(seed) => fastrand::Rng::with_seed(seed),
+            None => fastrand::Rng::new(),
+        };
+        Ok(Self {
+            config,
+            rng: Arc::new(Mutex::new(rng)),
+            counts: Arc::new(Mutex::new(FaultCounts::default())),
+        })
+    }
+
+    /// Create an injector (unavailable: built without the `chaos` feature)
+    #[cfg(not(feature = "chaos"))]
+    pub fn new(_config: FaultConfig) -> Result<Self> {
+        Err(AppError::configuration("--chaos requires building with the `chaos` feature"))
+    }
+
+    /// Faults injected so far
+    pub fn counts(&self) -> FaultCounts {
+        *self.counts.lock().expect("fault counts lock poisoned")
+    }
+
+    fn roll(&self, rate: f64) -> bool {
+        rate > 0.0 && self.rng.lock().expect("fault injection lock poisoned").f64() < rate
+    }
+
+    fn count(&self, update: impl FnOnce(&mut FaultCounts)) {
+        update(&mut self.counts.lock().expect("fault counts lock poisoned"));
+    }
+
+    /// Delay a request, or answer it with a synthetic 500 instead of sending it
+    pub async fn before_request(&self, url: &reqwest::Url) -> Result<Option<reqwest::Response>> {
+        if self.roll(self.config.slow_rate) {
+            self.count(|counts| counts.slow_responses += 1);
+            tracing::warn!(path = url.path(), delay_ms = self.config.slow_delay.as_millis() as u64, "injected slow response");
+            tokio::time::sleep(self.config.slow_delay).await;
+        }
+        if !self.roll(self.config.server_error_rate) {
+            return Ok(None);
+        }
+        self.count(|counts| counts.server_errors += 1);
+        tracing::warn!(path = url.path(), "injected HTTP 500");
+        let response = http::Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .url(url.clone())
+            .header("content-type", "application/json")
+            .body(r#"{"message": "Injected fault"}"#.as_bytes().to_vec())
+            .map_err(|error| AppError::internal(format!("Failed to build injected response: {}", error)))?;
+        Ok(Some(reqwest::Response::from(response)))
+    }
+
+    /// Cut the body of a response short
+    pub async fn after_response(&self, response: reqwest::Response) -> Result<reqwest::Response> {
+        if !self.roll(self.config.truncate_rate) {
+            return Ok(response);
+        }
+        let status = response.status();
+        let version = response.version();
+        let url = response.url().clone();
+        let mut headers = response.headers().clone();
+        let mut body = response.bytes().await?.to_vec();
+        let keep = self.rng.lock().expect("fault injection lock poisoned").usize(..body.len().max(1));
+        body.truncate(keep);
+        self.count(|counts| counts.truncated_bodies += 1);
+        tracing::warn!(path = url.path(), kept_bytes = keep, "injected truncated body");
+
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(body.len()));
+        let mut builder = http::Response::builder().status(status).version(version).url(url);
+        if let Some(builder_headers) = builder.headers_mut() {
+            *builder_headers = headers;
+        }
+        let response = builder
+            .body(body)
+            .map_err(|error| AppError::internal(format!("Failed to rebuild truncated response: {}", error)))?;
+        Ok(reqwest::Response::from(response))
+    }
+
+    /// Fail `operation` as if its database connection had dropped
+    pub fn check_connection(&self, operation: &str) -> Result<()> {
+        if !self.roll(self.config.db_drop_rate) {
+            return Ok(());
+        }
+        self.count(|counts| counts.dropped_connections += 1);
+        tracing::warn!(operation, "injected dropped database connection");
+        Err(AppError::Database(sqlx::Error::Io(std::io::Error::new(
+            std::io::ErrorKind::ConnectionReset,
+            format!("connection dropped by fault injection during {}", operation),
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fault_spec() {
+        let config: FaultConfig = "http-500=0.2, truncate=0.05,slow=1,delay=150,db-drop=0.1,seed=7".parse().unwrap();
+        assert_eq!(config.server_error_rate, 0.2);
+        assert_eq!(config.truncate_rate, 0.05);
+        assert_eq!(config.slow_rate, 1.0);
+        assert_eq!(config.slow_delay, Duration::from_millis(150));
+        assert_eq!(config.db_drop_rate, 0.1);
+        assert_eq!(config.seed, Some(7));
+        assert_eq!("".parse::<FaultConfig>().unwrap(), FaultConfig::default());
+
+        assert!("http-500=1.5".parse::<FaultConfig>().is_err());
+        assert!("timeout=0.1".parse::<FaultConfig>().is_err());
+        assert!("truncate".parse::<FaultConfig>().is_err());
+    }
+
+    #[cfg(not(feature = "chaos"))]
+    #[test]
+    fn test_injector_without_feature() {
+        let error = FaultInjector::new(FaultConfig::default()).unwrap_err();
+        assert!(error.to_string().contains("chaos"));
+    }
+
+    #[cfg(feature = "chaos")]
+    #[tokio::test]
+    async fn test_injects_configured_faults() {
+        let config = FaultConfig { server_error_rate: 1.0, db_drop_rate: 1.0, seed: Some(1), ..FaultConfig::default() };
+        let injector = FaultInjector::new(config).unwrap();
+        let url = reqwest::Url::parse("https://api.github.com/search/repositories").unwrap();
+        let response = injector.before_request(&url).await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(matches!(injector.check_connection("insert"), Err(AppError::Database(_))));
+
+        let quiet = FaultInjector::new(FaultConfig::default()).unwrap();
+        assert!(quiet.before_request(&url).await.unwrap().is_none());
+        quiet.check_connection("insert").unwrap();
+        assert_eq!(injector.counts(), FaultCounts { server_errors: 1, dropped_connections: 1, ..FaultCounts::default() });
+    }
+}
@@ -0,0 +1,161 @@
+//! # Circuit Breaker
+//!
+//! Stops sending requests to a degraded GitHub API. After
+//! `failure_threshold` consecutive server errors or timeouts the breaker
+//! opens and every request fails fast with [`AppError::CircuitOpen`] until
+//! the cool-down has passed. The next request is then let through as a probe:
+//! a success closes the breaker, another failure opens it again.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{AppError, Result};
+
+/// When the breaker opens and for how long
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the breaker; 0 disables it
+    pub failure_threshold: u32,
+    /// How long requests fail fast once the breaker is open
+    pub cool_down: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cool_down: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Consecutive-failure circuit breaker shared by clones of a client
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Create a closed breaker
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: Arc::new(Mutex::new(BreakerState::default())),
+        }
+    }
+
+    /// Settings this breaker was created with
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// Whether requests currently fail fast
+    pub fn is_open(&self) -> bool {
+        self.check().is_err()
+    }
+
+    /// Fail fast while the breaker is open
+    pub fn check(&self) -> Result<()> {
+        let state = self.state.lock().expect("circuit breaker lock poisoned");
+        match state.open_until {
+            Some(until) if until > Instant::now() => {
+                let remaining = until - Instant::now();
+                Err(AppError::circuit_open(remaining.as_secs_f64().ceil() as u64))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Close the breaker after a request GitHub answered normally
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().expect("circuit breaker lock poisoned");
+        state.consecutive_failures = 0;
+        state.open_until = None;
+    }
+
+    /// Count a server error or timeout; returns `true` when this failure opened the breaker
+    pub fn record_failure(&self) -> bool {
+        if self.config.failure_threshold == 0 {
+            return false;
+        }
+
+        let mut state = self.state.lock().expect("circuit breaker lock poisoned");
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if state.consecutive_failures < self.config.failure_threshold {
+            return false;
+        }
+
+        state.open_until = Some(Instant::now() + self.config.cool_down);
+        true
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(CircuitBreakerConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_after_threshold() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 3,
+            cool_down: Duration::from_secs(60),
+        });
+
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        breaker.record_success();
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        assert!(!breaker.is_open());
+
+        assert!(breaker.record_failure());
+        assert!(breaker.clone().is_open());
+        assert!(matches!(breaker.check(), Err(AppError::CircuitOpen { retry_after_seconds: 60 })));
+    }
+
+    #[test]
+    fn test_breaker_probes_after_cool_down() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            cool_down: Duration::from_millis(20),
+        });
+        breaker.record_failure();
+        assert!(breaker.record_failure());
+        assert!(breaker.is_open());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.check().is_ok());
+        // A failed probe opens the breaker again straight away
+        assert!(breaker.record_failure());
+        assert!(breaker.is_open());
+
+        std::thread::sleep(Duration::from_millis(30));
+        breaker.record_success();
+        assert!(!breaker.record_failure());
+    }
+
+    #[test]
+    fn test_zero_threshold_disables_breaker() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 0,
+            ..CircuitBreakerConfig::default()
+        });
+        for _ in 0..100 {
+            assert!(!breaker.record_failure());
+        }
+        assert!(!breaker.is_open());
+    }
+}
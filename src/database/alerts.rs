@@ -0,0 +1,93 @@
+//! Evaluating alert rules against two snapshots
+//!
+//! Each [`AlertCondition`] is one query joining the fresh snapshot to the
+//! previous one on `github_id`, so only repositories present in the fresh
+//! snapshot can fire, and rank comparisons use each snapshot's own ranking.
+
+use sqlx::FromRow;
+
+use crate::{Alert, AlertCondition, AlertRules, DatabaseManager, Result};
+
+/// Row returned by the alert queries
+#[derive(FromRow)]
+struct AlertRow {
+    full_name: String,
+    stargazers_count: i64,
+    previous_stars: Option<i64>,
+    rank: Option<i64>,
+}
+
+impl DatabaseManager {
+    /// Snapshot table written by a `query_history` run
+    pub async fn snapshot_table_of(&self, query_id: uuid::Uuid) -> Result<Option<String>> {
+        let sql = format!("SELECT table_name FROM {} WHERE id = $1", self.shared_table("query_history"));
+        Ok(sqlx::query_scalar(&sql).bind(query_id).fetch_optional(&self.pool).await?)
+    }
+
+    /// Alerts `rules` raise for `table_name` compared with `previous_table`
+    pub async fn evaluate_alerts(&self, rules: &AlertRules, table_name: &str, previous_table: &str) -> Result<Vec<Alert>> {
+        let current = self.qualified_table(table_name)?;
+        let previous = self.qualified_table(previous_table)?;
+
+        let mut alerts = Vec::new();
+        for rule in rules.rules() {
+            let (sql, limit) = match rule.condition {
+                AlertCondition::StarsCrossed { threshold } => (
+                    format!(
+                        r#"
+                        SELECT c.full_name, c.stargazers_count, p.stargazers_count AS previous_stars, NULL::BIGINT AS rank
+                        FROM {} c JOIN {} p ON p.github_id = c.github_id
+                        WHERE c.stargazers_count >= $1 AND p.stargazers_count < $1
+                        ORDER BY c.stargazers_count DESC, c.full_name
+                        "#,
+                        current, previous
+                    ),
+                    threshold,
+                ),
+                AlertCondition::EnteredTop { top } => (
+                    format!(
+                        r#"
+                        WITH c AS (
+                            SELECT github_id, full_name, stargazers_count,
+                                   ROW_NUMBER() OVER (ORDER BY stargazers_count DESC, github_id) AS rank
+                            FROM {}
+                        ), p AS (
+                            SELECT github_id, stargazers_count,
+                                   ROW_NUMBER() OVER (ORDER BY stargazers_count DESC, github_id) AS rank
+                            FROM {}
+                        )
+                        SELECT c.full_name, c.stargazers_count, p.stargazers_count AS previous_stars, c.rank
+                        FROM c LEFT JOIN p ON p.github_id = c.github_id
+                        WHERE c.rank <= $1 AND (p.rank IS NULL OR p.rank > $1)
+                        ORDER BY c.rank
+                        "#,
+                        current, previous
+                    ),
+                    top,
+                ),
+                AlertCondition::StarGain { min_gain } => (
+                    format!(
+                        r#"
+                        SELECT c.full_name, c.stargazers_count, p.stargazers_count AS previous_stars, NULL::BIGINT AS rank
+                        FROM {} c JOIN {} p ON p.github_id = c.github_id
+                        WHERE c.stargazers_count - p.stargazers_count >= $1
+                        ORDER BY c.stargazers_count - p.stargazers_count DESC, c.full_name
+                        "#,
+                        current, previous
+                    ),
+                    min_gain,
+                ),
+            };
+
+            let rows: Vec<AlertRow> = sqlx::query_as(&sql).bind(limit).fetch_all(&self.pool).await?;
+            alerts.extend(rows.into_iter().map(|row| Alert {
+                rule: rule.name.clone(),
+                full_name: row.full_name,
+                stargazers_count: row.stargazers_count,
+                previous_stars: row.previous_stars,
+                rank: row.rank,
+            }));
+        }
+        Ok(alerts)
+    }
+}
@@ -0,0 +1,233 @@
+//! Ready-made aggregate views over a snapshot table
+//!
+//! `--create-views` materializes a few common aggregates next to each
+//! repository snapshot so BI tools can chart them without writing SQL.
+//! Snapshots do not change after a run, so the views never need a refresh.
+
+use crate::{AppError, DatabaseManager, Result};
+
+/// Percentile cut-points reported by [`DatabaseManager::get_star_distribution`] by default
+pub const DEFAULT_PERCENTILES: &[f64] = &[0.25, 0.5, 0.75, 0.9, 0.99];
+
+/// Shape of a count column across a snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    /// Requested cut-points as (percentile 0.0 - 1.0, value)
+    pub percentiles: Vec<(f64, f64)>,
+    /// Power-of-ten buckets from 0 up to the largest value, including empty ones
+    pub buckets: Vec<DistributionBucket>,
+}
+
+/// Rows whose value falls in `min..=max`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DistributionBucket {
+    pub min: i64,
+    pub max: i64,
+    pub repositories: i64,
+}
+
+impl DistributionBucket {
+    /// Bucket covering values with `exponent` + 1 digits; bucket 0 also holds 0
+    fn for_exponent(exponent: u32, repositories: i64) -> Self {
+        Self {
+            min: if exponent == 0 { 0 } else { 10i64.pow(exponent) },
+            max: 10i64.pow(exponent + 1) - 1,
+            repositories,
+        }
+    }
+}
+
+/// Analytics views created per snapshot, as (suffix, SELECT over `{table}`)
+const ANALYTICS_VIEWS: &[(&str, &str)] = &[
+    (
+        "by_language",
+        r#"
+        SELECT COALESCE(language, '(none)') AS language,
+               COUNT(*) AS repositories,
+               SUM(stargazers_count)::BIGINT AS stars,
+               SUM(forks_count)::BIGINT AS forks,
+               ROUND(AVG(stargazers_count))::BIGINT AS avg_stars
+        FROM {table}
+        GROUP BY 1
+        ORDER BY repositories DESC, stars DESC
+        "#,
+    ),
+    (
+        "top_owners",
+        r#"
+        SELECT owner_login,
+               owner_type,
+               COUNT(*) AS repositories,
+               SUM(stargazers_count)::BIGINT AS stars,
+               SUM(forks_count)::BIGINT AS forks
+        FROM {table}
+        GROUP BY owner_login, owner_type
+        ORDER BY stars DESC, repositories DESC
+        "#,
+    ),
+    (
+        "star_buckets",
+        r#"
+        SELECT bucket,
+               CASE WHEN bucket = 0 THEN 0 ELSE POWER(10, bucket)::BIGINT END AS min_stars,
+               POWER(10, bucket + 1)::BIGINT - 1 AS max_stars,
+               COUNT(*) AS repositories
+        FROM (
+            SELECT CASE
+                       WHEN stargazers_count < 10 THEN 0
+                       ELSE FLOOR(LOG(stargazers_count::NUMERIC))::INT
+                   END AS bucket
+            FROM {table}
+        ) buckets
+        GROUP BY bucket
+        ORDER BY bucket
+        "#,
+    ),
+];
+
+impl DatabaseManager {
+    /// Names of the analytics views for a snapshot table
+    pub fn analytics_view_names(table_name: &str) -> Vec<String> {
+        ANALYTICS_VIEWS
+            .iter()
+            .map(|(suffix, _)| format!("{}_{}", table_name, suffix))
+            .collect()
+    }
+
+    /// Create (or rebuild) the materialized analytics views for a repository snapshot
+    ///
+    /// Returns the names of the created views.
+    pub async fn create_analytics_views(&self, table_name: &str) -> Result<Vec<String>> {
+        Self::validate_table_name(table_name)?;
+        let table = self.qualified_table(table_name)?;
+
+        let mut created = Vec::with_capacity(ANALYTICS_VIEWS.len());
+        let mut tx = self.pool.begin().await?;
+        for (suffix, select) in ANALYTICS_VIEWS {
+            let view_name = format!("{}_{}", table_name, suffix);
+            let view = self.qualified_table(&view_name).map_err(|_| {
+                AppError::table_creation(&view_name, "view name exceeds 63 characters")
+            })?;
+
+            sqlx::query(&format!("DROP MATERIALIZED VIEW IF EXISTS {}", view))
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(&format!(
+                "CREATE MATERIALIZED VIEW {} AS {}",
+                view,
+                select.replace("{table}", &table)
+            ))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::table_creation(&view_name, e.to_string()))?;
+
+            created.push(view_name);
+        }
+        tx.commit().await?;
+
+        Ok(created)
+    }
+
+    /// Percentiles and log-scale histogram of `stargazers_count`
+    pub async fn get_star_distribution(&self, table_name: &str, percentiles: &[f64]) -> Result<Distribution> {
+        self.get_distribution(table_name, "stargazers_count", percentiles).await
+    }
+
+    /// Percentiles and log-scale histogram of `forks_count`
+    pub async fn get_fork_distribution(&self, table_name: &str, percentiles: &[f64]) -> Result<Distribution> {
+        self.get_distribution(table_name, "forks_count", percentiles).await
+    }
+
+    async fn get_distribution(&self, table_name: &str, column: &str, percentiles: &[f64]) -> Result<Distribution> {
+        if let Some(p) = percentiles.iter().find(|p| !(0.0..=1.0).contains(*p)) {
+            return Err(AppError::validation("percentiles", format!("{} is outside 0.0 - 1.0", p)));
+        }
+        Self::validate_table_name(table_name)?;
+        let table = self.qualified_table(table_name)?;
+
+        let values: Option<Vec<f64>> = sqlx::query_scalar(&format!(
+            "SELECT PERCENTILE_CONT($1::FLOAT8[]) WITHIN GROUP (ORDER BY {})::FLOAT8[] FROM {}",
+            column, table
+        ))
+        .bind(percentiles)
+        .fetch_one(&self.read_pool)
+        .await?;
+        let percentiles = match values {
+            Some(values) => percentiles.iter().copied().zip(values).collect(),
+            None => Vec::new(),
+        };
+
+        let counts: Vec<(i32, i64)> = sqlx::query_as(&format!(
+            r#"
+            SELECT CASE WHEN {column} < 10 THEN 0 ELSE FLOOR(LOG({column}::NUMERIC))::INT END AS bucket,
+                   COUNT(*)
+            FROM {table}
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+            column = column,
+            table = table
+        ))
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let top = counts.last().map_or(0, |(bucket, _)| *bucket);
+        let buckets = if counts.is_empty() {
+            Vec::new()
+        } else {
+            (0..=top)
+                .map(|exponent| {
+                    let repositories = counts
+                        .iter()
+                        .find(|(bucket, _)| *bucket == exponent)
+                        .map_or(0, |(_, count)| *count);
+                    DistributionBucket::for_exponent(exponent as u32, repositories)
+                })
+                .collect()
+        };
+
+        Ok(Distribution { percentiles, buckets })
+    }
+
+    /// Drop the analytics views of a snapshot table, if any
+    pub async fn drop_analytics_views(&self, table_name: &str) -> Result<()> {
+        for view_name in Self::analytics_view_names(table_name) {
+            if let Ok(view) = self.qualified_table(&view_name) {
+                sqlx::query(&format!("DROP MATERIALIZED VIEW IF EXISTS {}", view))
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analytics_view_names() {
+        assert_eq!(
+            DatabaseManager::analytics_view_names("repos_20240101000000"),
+            vec![
+                "repos_20240101000000_by_language",
+                "repos_20240101000000_top_owners",
+                "repos_20240101000000_star_buckets",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distribution_bucket_bounds() {
+        assert_eq!(DistributionBucket::for_exponent(0, 3), DistributionBucket { min: 0, max: 9, repositories: 3 });
+        assert_eq!(DistributionBucket::for_exponent(3, 1), DistributionBucket { min: 1000, max: 9999, repositories: 1 });
+    }
+
+    #[test]
+    fn test_analytics_views_reference_table() {
+        for (_, select) in ANALYTICS_VIEWS {
+            assert!(select.contains("FROM {table}"));
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! The `history export` audit trail
+//!
+//! Joins each `query_history` run to the `snapshot_tables` entry of the
+//! table it wrote. Dropped tables leave the registry, so their runs keep
+//! their table name but carry no registry fields.
+
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+
+use crate::{AuditRecord, DatabaseManager, Result};
+
+impl DatabaseManager {
+    /// Runs executed at or after `since` (all runs when `None`), oldest first
+    pub async fn audit_trail(&self, since: Option<DateTime<Utc>>) -> Result<Vec<AuditRecord>> {
+        let sql = format!(
+            r#"
+            SELECT h.*, s.kind AS table_kind, s.created_at AS table_registered_at
+            FROM {} h
+            LEFT JOIN {} s ON s.query_id = h.id AND s.table_name = h.table_name
+            WHERE $1::TIMESTAMPTZ IS NULL OR h.executed_at >= $1
+            ORDER BY h.executed_at, h.id
+            "#,
+            self.shared_table("query_history"),
+            self.shared_table("snapshot_tables")
+        );
+        let rows = sqlx::query(&sql).bind(since).fetch_all(&self.read_pool).await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let run = Self::query_metadata_from_row(row);
+                let metrics = run.http_metrics.as_ref();
+                AuditRecord {
+                    query_id: run.id,
+                    executed_at: run.executed_at,
+                    api_requests: metrics.map(|m| m.requests),
+                    retries: metrics.map(|m| m.retries),
+                    rate_limit_sleep_ms: metrics.map(|m| m.rate_limit_sleep_ms),
+                    request_id: metrics.and_then(|m| m.request_id.clone()),
+                    search_query: run.search_query,
+                    saved_query: run.saved_query,
+                    success: run.success,
+                    error_message: run.error_message,
+                    result_count: run.result_count,
+                    rows_failed: run.rows_failed,
+                    duration_ms: run.duration_ms,
+                    tool_version: run.tool_version,
+                    api_base_url: run.api_base_url,
+                    token_fingerprint: run.token_fingerprint,
+                    table_name: run.table_name,
+                    table_kind: row.get("table_kind"),
+                    table_registered_at: row.get("table_registered_at"),
+                    archive_url: run.archive_url,
+                    labels: run.labels,
+                    note: run.note,
+                }
+            })
+            .collect())
+    }
+}
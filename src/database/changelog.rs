@@ -0,0 +1,107 @@
+//! Versioned repository history (`repository_history`)
+//!
+//! Runs with `--track-changes` record the [`TRACKED_FIELDS`] of every stored
+//! repository as slowly changing dimension (type 2) rows: while the fields
+//! stay the same the current version stays open (`valid_to IS NULL`); when
+//! one changes, the current version is closed at the new snapshot's
+//! `fetched_at` and a new version starts there. Counts such as stars are not
+//! tracked, so versions mark events like renames, archiving, or a license
+//! change.
+
+use crate::{DatabaseManager, Result};
+
+/// Snapshot columns whose changes open a new version
+pub const TRACKED_FIELDS: &[&str] = &[
+    "full_name",
+    "description",
+    "language",
+    "default_branch",
+    "visibility",
+    "private",
+    "fork",
+    "archived",
+    "disabled",
+    "owner_login",
+    "license_key",
+    "topics",
+    "has_issues",
+    "has_wiki",
+    "has_pages",
+];
+
+/// Outcome of [`DatabaseManager::track_changes`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChangeLogUpdate {
+    /// Versions opened: first sightings plus changed repositories
+    pub opened: u64,
+    /// Versions closed because a tracked field changed
+    pub closed: u64,
+}
+
+impl DatabaseManager {
+    /// Record the tracked fields of a repository snapshot in `repository_history`
+    ///
+    /// Fields missing from the snapshot's column set are ignored when
+    /// comparing, so switching `--columns` does not open versions by itself.
+    /// A snapshot older than a repository's current version is skipped, which
+    /// makes merging the same or an older snapshot again a no-op.
+    pub async fn track_changes(&self, table_name: &str) -> Result<ChangeLogUpdate> {
+        Self::validate_table_name(table_name)?;
+        let history = self.shared_table("repository_history");
+        let current = format!(
+            r#"
+            WITH current AS (
+                SELECT DISTINCT ON (t.github_id)
+                       t.github_id, t.full_name, COALESCE(t.fetched_at, NOW()) AS fetched_at,
+                       (SELECT COALESCE(jsonb_object_agg(key, value), '{{}}'::jsonb)
+                        FROM jsonb_each(to_jsonb(t)) WHERE key = ANY($1)) AS state
+                FROM {} t
+                ORDER BY t.github_id, t.fetched_at DESC
+            )
+            "#,
+            self.qualified_table(table_name)?
+        );
+        let close_sql = format!(
+            r#"
+            {current}
+            UPDATE {history} h SET valid_to = c.fetched_at
+            FROM current c
+            WHERE h.github_id = c.github_id
+              AND h.valid_to IS NULL
+              AND h.valid_from < c.fetched_at
+              AND EXISTS (
+                  SELECT 1 FROM jsonb_each(c.state) e
+                  WHERE h.state ? e.key AND h.state -> e.key IS DISTINCT FROM e.value
+              )
+            "#
+        );
+        let open_sql = format!(
+            r#"
+            {current}
+            INSERT INTO {history} (github_id, full_name, state, valid_from, table_name)
+            SELECT c.github_id, c.full_name, c.state, c.fetched_at, $2
+            FROM current c
+            WHERE NOT EXISTS (
+                SELECT 1 FROM {history} h
+                WHERE h.github_id = c.github_id AND (h.valid_to IS NULL OR h.valid_from >= c.fetched_at)
+            )
+            "#
+        );
+        let fields: Vec<String> = TRACKED_FIELDS.iter().map(|field| field.to_string()).collect();
+
+        let mut tx = self.pool.begin().await?;
+        let closed = sqlx::query(&close_sql)
+            .bind(&fields)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        let opened = sqlx::query(&open_sql)
+            .bind(&fields)
+            .bind(table_name)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        tx.commit().await?;
+        Ok(ChangeLogUpdate { opened, closed })
+    }
+}
@@ -0,0 +1,391 @@
+//! Columns of repository snapshot tables
+//!
+//! Every column a snapshot table can hold is listed once in
+//! [`REPOSITORY_COLUMNS`], with its DDL and how it is bound from a
+//! [`Repository`]. `create_repository_table` and the upsert statement are
+//! generated from the [`ColumnSet`] selected with `--columns`, so a
+//! `minimal` table only carries the name, stars, and language, and the
+//! `get_table_stats` summary is generated from the columns a table has.
+
+use std::str::FromStr;
+
+use sqlx::postgres::PgArguments;
+use sqlx::query::QueryScalar;
+use sqlx::types::Json;
+use sqlx::Postgres;
+
+use crate::{AppError, Repository, Result};
+
+/// Upsert statement a column binds its value into
+pub(crate) type UpsertQuery<'q> = QueryScalar<'q, Postgres, bool, PgArguments>;
+
+type BindFn = for<'q> fn(UpsertQuery<'q>, &'q Repository) -> UpsertQuery<'q>;
+
+/// Smallest preset that includes a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Tier {
+    /// Part of every table
+    Required,
+    Minimal,
+    Core,
+    Extended,
+}
+
+/// One column of a repository snapshot table
+pub struct RepositoryColumn {
+    /// Column name
+    pub name: &'static str,
+    /// `Repository` field the value comes from, as a serde path (`owner.login`)
+    pub field: Option<&'static str>,
+    /// Type and constraints used in `CREATE TABLE`
+    pub definition: &'static str,
+    tier: Tier,
+    /// Binds the value on insert; `None` for columns filled by enrichments or defaults
+    bind: Option<BindFn>,
+    /// Whether a re-fetched repository overwrites the stored value
+    pub(crate) update: bool,
+}
+
+impl RepositoryColumn {
+    /// Whether the upsert statement writes this column
+    pub fn is_inserted(&self) -> bool {
+        self.bind.is_some()
+    }
+
+    /// Column type without constraints (`BIGINT`, `VARCHAR(100)`, ...)
+    pub fn sql_type(&self) -> &'static str {
+        self.definition.split_whitespace().next().unwrap_or(self.definition)
+    }
+
+    /// Type name `information_schema.columns.udt_name` reports for this column
+    pub fn udt_name(&self) -> &'static str {
+        match self.sql_type() {
+            "BIGINT" => "int8",
+            "SERIAL" => "int4",
+            "BOOLEAN" => "bool",
+            "DOUBLE" => "float8",
+            "JSONB" => "jsonb",
+            "TEXT" => "text",
+            "TEXT[]" => "_text",
+            "TIMESTAMPTZ" => "timestamptz",
+            sql_type if sql_type.starts_with("VARCHAR") => "varchar",
+            sql_type => sql_type,
+        }
+    }
+
+    /// Look up a column of [`REPOSITORY_COLUMNS`] by name
+    pub fn find(name: &str) -> Option<&'static RepositoryColumn> {
+        REPOSITORY_COLUMNS.iter().find(|column| column.name == name)
+    }
+
+    pub(crate) fn bind<'q>(&self, query: UpsertQuery<'q>, repo: &'q Repository) -> UpsertQuery<'q> {
+        match self.bind {
+            Some(bind) => bind(query, repo),
+            None => query,
+        }
+    }
+}
+
+const fn column(name: &'static str, field: &'static str, definition: &'static str, tier: Tier, bind: BindFn) -> RepositoryColumn {
+    RepositoryColumn { name, field: Some(field), definition, tier, bind: Some(bind), update: true }
+}
+
+const fn fixed(name: &'static str, field: &'static str, definition: &'static str, tier: Tier, bind: BindFn) -> RepositoryColumn {
+    RepositoryColumn { name, field: Some(field), definition, tier, bind: Some(bind), update: false }
+}
+
+const fn unbound(name: &'static str, definition: &'static str, tier: Tier) -> RepositoryColumn {
+    RepositoryColumn { name, field: None, definition, tier, bind: None, update: false }
+}
+
+/// Every column a snapshot table can hold, in table order
+pub static REPOSITORY_COLUMNS: &[RepositoryColumn] = &[
+    unbound("id", "SERIAL PRIMARY KEY", Tier::Required),
+    fixed("github_id", "id", "BIGINT UNIQUE NOT NULL", Tier::Required, |q, r| q.bind(r.id)),
+    column("full_name", "full_name", "VARCHAR(255) NOT NULL", Tier::Required, |q, r| q.bind(&r.full_name)),
+    column("name", "name", "VARCHAR(255) NOT NULL", Tier::Minimal, |q, r| q.bind(&r.name)),
+    column("description", "description", "TEXT", Tier::Core, |q, r| q.bind(&r.description)),
+    column("html_url", "html_url", "VARCHAR(500) NOT NULL", Tier::Core, |q, r| q.bind(&r.html_url)),
+    column("clone_url", "clone_url", "VARCHAR(500) NOT NULL", Tier::Extended, |q, r| q.bind(&r.clone_url)),
+    column("ssh_url", "ssh_url", "VARCHAR(500) NOT NULL", Tier::Extended, |q, r| q.bind(&r.ssh_url)),
+    column("size_kb", "size", "BIGINT NOT NULL DEFAULT 0", Tier::Core, |q, r| q.bind(r.size)),
+    column("stargazers_count", "stargazers_count", "BIGINT NOT NULL DEFAULT 0", Tier::Minimal, |q, r| q.bind(r.stargazers_count)),
+    column("watchers_count", "watchers_count", "BIGINT NOT NULL DEFAULT 0", Tier::Extended, |q, r| q.bind(r.watchers_count)),
+    column("forks_count", "forks_count", "BIGINT NOT NULL DEFAULT 0", Tier::Core, |q, r| q.bind(r.forks_count)),
+    column("open_issues_count", "open_issues_count", "BIGINT NOT NULL DEFAULT 0", Tier::Core, |q, r| q.bind(r.open_issues_count)),
+    column("language", "language", "VARCHAR(100)", Tier::Minimal, |q, r| q.bind(&r.language)),
+    column("default_branch", "default_branch", "VARCHAR(100) NOT NULL", Tier::Core, |q, r| q.bind(&r.default_branch)),
+    column("visibility", "visibility", "VARCHAR(20) NOT NULL", Tier::Core, |q, r| q.bind(&r.visibility)),
+    column("private", "private", "BOOLEAN NOT NULL DEFAULT FALSE", Tier::Core, |q, r| q.bind(r.private)),
+    column("fork", "fork", "BOOLEAN NOT NULL DEFAULT FALSE", Tier::Core, |q, r| q.bind(r.fork)),
+    column("archived", "archived", "BOOLEAN NOT NULL DEFAULT FALSE", Tier::Core, |q, r| q.bind(r.archived)),
+    column("disabled", "disabled", "BOOLEAN NOT NULL DEFAULT FALSE", Tier::Extended, |q, r| q.bind(r.disabled)),
+    fixed("created_at", "created_at", "TIMESTAMPTZ NOT NULL", Tier::Core, |q, r| q.bind(r.created_at)),
+    column("updated_at", "updated_at", "TIMESTAMPTZ NOT NULL", Tier::Core, |q, r| q.bind(r.updated_at)),
+    column("pushed_at", "pushed_at", "TIMESTAMPTZ", Tier::Core, |q, r| q.bind(r.pushed_at)),
+    fixed("owner_id", "owner.id", "BIGINT NOT NULL", Tier::Extended, |q, r| q.bind(r.owner.id)),
+    column("owner_login", "owner.login", "VARCHAR(255) NOT NULL", Tier::Core, |q, r| q.bind(&r.owner.login)),
+    column("owner_type", "owner.type", "VARCHAR(50) NOT NULL", Tier::Extended, |q, r| q.bind(&r.owner.owner_type)),
+    column("owner_avatar_url", "owner.avatar_url", "VARCHAR(500) NOT NULL", Tier::Extended, |q, r| q.bind(&r.owner.avatar_url)),
+    column("owner_html_url", "owner.html_url", "VARCHAR(500) NOT NULL", Tier::Extended, |q, r| q.bind(&r.owner.html_url)),
+    column("owner_site_admin", "owner.site_admin", "BOOLEAN NOT NULL DEFAULT FALSE", Tier::Extended, |q, r| q.bind(r.owner.site_admin)),
+    column("license_key", "license.key", "VARCHAR(100)", Tier::Core, |q, r| q.bind(r.license.as_ref().map(|l| &l.key))),
+    column("license_name", "license.name", "VARCHAR(255)", Tier::Extended, |q, r| q.bind(r.license.as_ref().map(|l| &l.name))),
+    column("license_spdx_id", "license.spdx_id", "VARCHAR(100)", Tier::Core, |q, r| q.bind(r.license.as_ref().and_then(|l| l.spdx_id.as_ref()))),
+    column("license_url", "license.url", "VARCHAR(500)", Tier::Extended, |q, r| q.bind(r.license.as_ref().and_then(|l| l.url.as_ref()))),
+    column("topics", "topics", "TEXT[] DEFAULT '{}'", Tier::Core, |q, r| q.bind(&r.topics)),
+    column("has_issues", "has_issues", "BOOLEAN NOT NULL DEFAULT FALSE", Tier::Extended, |q, r| q.bind(r.has_issues)),
+    column("has_projects", "has_projects", "BOOLEAN NOT NULL DEFAULT FALSE", Tier::Extended, |q, r| q.bind(r.has_projects)),
+    column("has_wiki", "has_wiki", "BOOLEAN NOT NULL DEFAULT FALSE", Tier::Extended, |q, r| q.bind(r.has_wiki)),
+    column("has_pages", "has_pages", "BOOLEAN NOT NULL DEFAULT FALSE", Tier::Extended, |q, r| q.bind(r.has_pages)),
+    column("has_downloads", "has_downloads", "BOOLEAN NOT NULL DEFAULT FALSE", Tier::Extended, |q, r| q.bind(r.has_downloads)),
+    column("score", "score", "DOUBLE PRECISION", Tier::Extended, |q, r| q.bind(r.score)),
+    column("text_matches", "text_matches", "JSONB", Tier::Extended, |q, r| {
+        q.bind((!r.text_matches.is_empty()).then_some(Json(&r.text_matches)))
+    }),
+    column("matched_language_filter", "matched_language_filter", "VARCHAR(100)", Tier::Extended, |q, r| {
+        q.bind(&r.matched_language_filter)
+    }),
+    unbound("head_sha", "VARCHAR(40)", Tier::Extended),
+    unbound("commit_count", "BIGINT", Tier::Extended),
+    unbound("subscribers_count", "BIGINT", Tier::Extended),
+    unbound("network_count", "BIGINT", Tier::Extended),
+    unbound("details_fetched_at", "TIMESTAMPTZ", Tier::Extended),
+    unbound("fetched_at", "TIMESTAMPTZ DEFAULT NOW()", Tier::Required),
+];
+
+/// Aggregate `get_table_stats` computes over one column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Aggregate {
+    CountDistinct,
+    Avg,
+    Median,
+    Min,
+    Max,
+    /// Share of rows where a boolean column is true
+    TrueRatio,
+}
+
+/// One value of the `get_table_stats` summary row
+struct TableStat {
+    alias: &'static str,
+    column: &'static str,
+    aggregate: Aggregate,
+}
+
+const fn stat(alias: &'static str, column: &'static str, aggregate: Aggregate) -> TableStat {
+    TableStat { alias, column, aggregate }
+}
+
+/// Summary values of `get_table_stats`, by the column each is computed from
+static TABLE_STATS: &[TableStat] = &[
+    stat("unique_languages", "language", Aggregate::CountDistinct),
+    stat("unique_owners", "owner_login", Aggregate::CountDistinct),
+    stat("avg_stars", "stargazers_count", Aggregate::Avg),
+    stat("max_stars", "stargazers_count", Aggregate::Max),
+    stat("median_stars", "stargazers_count", Aggregate::Median),
+    stat("fork_ratio", "fork", Aggregate::TrueRatio),
+    stat("archived_ratio", "archived", Aggregate::TrueRatio),
+    stat("oldest_repo", "created_at", Aggregate::Min),
+    stat("newest_repo", "created_at", Aggregate::Max),
+];
+
+impl TableStat {
+    /// Aggregate expression, or a typed placeholder when the table lacks the column
+    fn expression(&self, present: bool) -> String {
+        let column = self.column;
+        match (self.aggregate, present) {
+            (Aggregate::CountDistinct, true) => format!("COUNT(DISTINCT {})", column),
+            (Aggregate::Avg, true) => format!("AVG({})::FLOAT8", column),
+            (Aggregate::Median, true) => format!("PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY {})::FLOAT8", column),
+            (Aggregate::Min, true) => format!("MIN({})", column),
+            (Aggregate::Max, true) => format!("MAX({})", column),
+            (Aggregate::TrueRatio, true) => format!("AVG(CASE WHEN {} THEN 1.0 ELSE 0.0 END)::FLOAT8", column),
+            (Aggregate::CountDistinct, false) => "0::BIGINT".to_string(),
+            (Aggregate::Avg | Aggregate::Median | Aggregate::TrueRatio, false) => "NULL::FLOAT8".to_string(),
+            (Aggregate::Min | Aggregate::Max, false) => format!(
+                "NULL::{}",
+                RepositoryColumn::find(column).map_or("TEXT", RepositoryColumn::sql_type)
+            ),
+        }
+    }
+}
+
+/// Summary query of `get_table_stats` for a table holding the columns `present`
+pub(crate) fn table_stats_sql(table: &str, present: &[&str]) -> String {
+    let values: Vec<String> = TABLE_STATS
+        .iter()
+        .map(|stat| format!("{} AS {}", stat.expression(present.contains(&stat.column)), stat.alias))
+        .collect();
+    format!("SELECT COUNT(*) AS total_repositories, {} FROM {}", values.join(", "), table)
+}
+
+/// Which columns `create_repository_table` and `insert_repositories` use
+///
+/// `id`, `github_id`, `full_name`, and `fetched_at` are part of every set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ColumnSet {
+    /// Name, stars, and language
+    Minimal,
+    /// The columns `stats`, `search-local`, and incremental re-crawls read
+    Core,
+    /// Every column (owner and license details, flags, search score, enrichments)
+    #[default]
+    Extended,
+    /// Exactly these columns, by name
+    Custom(Vec<String>),
+}
+
+impl ColumnSet {
+    /// Columns in this set, in table order
+    pub fn columns(&self) -> Vec<&'static RepositoryColumn> {
+        REPOSITORY_COLUMNS
+            .iter()
+            .filter(|column| match self {
+                Self::Minimal => column.tier <= Tier::Minimal,
+                Self::Core => column.tier <= Tier::Core,
+                Self::Extended => true,
+                Self::Custom(names) => column.tier == Tier::Required || names.iter().any(|name| name == column.name),
+            })
+            .collect()
+    }
+
+    /// Whether the set includes the column `name`
+    pub fn contains(&self, name: &str) -> bool {
+        self.columns().iter().any(|column| column.name == name)
+    }
+
+    /// Reject custom sets that name unknown columns
+    pub fn validate(&self) -> Result<()> {
+        if let Self::Custom(names) = self {
+            if let Some(unknown) = names.iter().find(|name| !REPOSITORY_COLUMNS.iter().any(|column| column.name == *name)) {
+                return Err(AppError::configuration(format!(
+                    "Unknown column '{}' in --columns (expected minimal, core, extended, or column names such as {})",
+                    unknown,
+                    REPOSITORY_COLUMNS.iter().map(|column| column.name).collect::<Vec<_>>().join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses `minimal`, `core`, `extended`, or a comma-separated list of column names
+impl FromStr for ColumnSet {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let set = match value.trim() {
+            "minimal" => Self::Minimal,
+            "core" => Self::Core,
+            "extended" => Self::Extended,
+            list => Self::Custom(
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            ),
+        };
+        set.validate()?;
+        Ok(set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(set: &ColumnSet) -> Vec<&'static str> {
+        set.columns().iter().map(|column| column.name).collect()
+    }
+
+    #[test]
+    fn test_presets_nest() {
+        assert_eq!(
+            names(&ColumnSet::Minimal),
+            vec!["id", "github_id", "full_name", "name", "stargazers_count", "language", "fetched_at"]
+        );
+        let core = names(&ColumnSet::Core);
+        assert!(core.contains(&"owner_login") && core.contains(&"updated_at"));
+        assert!(!core.contains(&"owner_avatar_url"));
+        assert_eq!(ColumnSet::Extended.columns().len(), REPOSITORY_COLUMNS.len());
+        assert!(names(&ColumnSet::Minimal).iter().all(|name| core.contains(name)));
+    }
+
+    /// Every serialized `Repository` field has exactly one column, and every column field exists
+    #[test]
+    fn test_columns_match_repository_fields() {
+        let mut repo = crate::models::test_repository(1, "octocat/hello");
+        repo.license = Some(crate::RepositoryLicense {
+            key: "mit".to_string(),
+            name: "MIT License".to_string(),
+            spdx_id: None,
+            url: None,
+        });
+        repo.text_matches.push(crate::TextMatch {
+            object_url: None,
+            object_type: None,
+            property: "name".to_string(),
+            fragment: "hello".to_string(),
+            matches: Vec::new(),
+        });
+        repo.matched_language_filter = Some("rust".to_string());
+
+        fn flatten(prefix: &str, value: &serde_json::Value, fields: &mut Vec<String>) {
+            match value {
+                serde_json::Value::Object(map) => {
+                    for (key, value) in map {
+                        let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                        flatten(&path, value, fields);
+                    }
+                }
+                _ => fields.push(prefix.to_string()),
+            }
+        }
+        let mut fields = Vec::new();
+        flatten("", &serde_json::to_value(&repo).unwrap(), &mut fields);
+        fields.sort();
+
+        let mut mapped: Vec<String> = REPOSITORY_COLUMNS
+            .iter()
+            .filter_map(|column| column.field.map(str::to_string))
+            .collect();
+        mapped.sort();
+        assert_eq!(fields, mapped);
+        assert!(REPOSITORY_COLUMNS.iter().all(|column| column.field.is_some() == column.is_inserted()));
+    }
+
+    #[test]
+    fn test_udt_names() {
+        for column in REPOSITORY_COLUMNS {
+            let udt_name = column.udt_name();
+            assert!(udt_name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'), "{}", column.name);
+        }
+        assert_eq!(RepositoryColumn::find("owner_login").unwrap().udt_name(), "varchar");
+        assert_eq!(RepositoryColumn::find("topics").unwrap().udt_name(), "_text");
+    }
+
+    #[test]
+    fn test_table_stats_sql_fills_missing_columns() {
+        let sql = table_stats_sql("\"repos\"", &["language", "stargazers_count"]);
+        assert!(sql.starts_with("SELECT COUNT(*) AS total_repositories, COUNT(DISTINCT language) AS unique_languages"));
+        assert!(sql.contains("0::BIGINT AS unique_owners"));
+        assert!(sql.contains("MAX(stargazers_count) AS max_stars"));
+        assert!(sql.contains("NULL::FLOAT8 AS fork_ratio"));
+        assert!(sql.contains("NULL::TIMESTAMPTZ AS newest_repo"));
+        assert!(sql.ends_with("FROM \"repos\""));
+    }
+
+    #[test]
+    fn test_parse_column_list() {
+        assert_eq!("core".parse::<ColumnSet>().unwrap(), ColumnSet::Core);
+
+        let set: ColumnSet = "stargazers_count, topics".parse().unwrap();
+        assert_eq!(names(&set), vec!["id", "github_id", "full_name", "stargazers_count", "topics", "fetched_at"]);
+        assert!(set.contains("topics") && !set.contains("language"));
+
+        let error = "stars".parse::<ColumnSet>().unwrap_err();
+        assert!(error.to_string().contains("Unknown column 'stars'"));
+    }
+}
@@ -0,0 +1,94 @@
+//! Where a list of tracked repositories landed in a snapshot
+//!
+//! `compare` answers "does my project show up for this search, and how
+//! high": each `owner/name` of a list file is looked up in the snapshot,
+//! case-insensitively, with its rank in the order the search returned it
+//! and its star count.
+
+use std::path::Path;
+
+use sqlx::FromRow;
+
+use crate::{AppError, DatabaseManager, Result};
+
+/// A tracked repository and where it appears in a snapshot
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct TrackedRepository {
+    /// Name as given in the list
+    pub full_name: String,
+    /// 1-based position in the search results; `None` when absent
+    pub rank: Option<i64>,
+    pub stargazers_count: Option<i64>,
+}
+
+/// `owner/name` lines of a list file; blank lines and `#` comments are skipped
+pub fn read_tracked_repositories(path: &Path) -> Result<Vec<String>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| AppError::configuration(format!("Cannot read {}: {}", path.display(), e)))?;
+    let mut names: Vec<String> = Vec::new();
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let valid = line
+            .split_once('/')
+            .is_some_and(|(owner, name)| !owner.is_empty() && !name.is_empty() && !name.contains('/'));
+        if !valid {
+            return Err(AppError::configuration(format!(
+                "Invalid repository '{}' in {} (expected owner/name)",
+                line,
+                path.display()
+            )));
+        }
+        if !names.iter().any(|name| name.eq_ignore_ascii_case(line)) {
+            names.push(line.to_string());
+        }
+    }
+    if names.is_empty() {
+        return Err(AppError::configuration(format!("{} lists no repositories", path.display())));
+    }
+    Ok(names)
+}
+
+impl DatabaseManager {
+    /// Rank and stars of each of `full_names` in a snapshot, in list order
+    ///
+    /// The rank is the position in which the search returned the repository,
+    /// which is the order rows were first stored.
+    pub async fn compare_repositories(&self, table_name: &str, full_names: &[String]) -> Result<Vec<TrackedRepository>> {
+        let sql = format!(
+            r#"
+            WITH ranked AS (
+                SELECT full_name, stargazers_count, ROW_NUMBER() OVER (ORDER BY id) AS rank
+                FROM {}
+            )
+            SELECT tracked.full_name, ranked.rank, ranked.stargazers_count
+            FROM unnest($1::TEXT[]) WITH ORDINALITY AS tracked(full_name, position)
+            LEFT JOIN ranked ON lower(ranked.full_name) = lower(tracked.full_name)
+            ORDER BY tracked.position
+            "#,
+            self.qualified_table(table_name)?
+        );
+        Ok(sqlx::query_as(&sql).bind(full_names).fetch_all(&self.read_pool).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_tracked_repositories() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("repos.txt");
+
+        std::fs::write(&path, "# mine\nserde-rs/serde\n\n  tokio-rs/tokio  \nSerde-rs/Serde\n").unwrap();
+        assert_eq!(read_tracked_repositories(&path).unwrap(), vec!["serde-rs/serde", "tokio-rs/tokio"]);
+
+        std::fs::write(&path, "serde-rs/serde\nhttps://github.com/tokio-rs/tokio\n").unwrap();
+        assert!(read_tracked_repositories(&path).is_err());
+        std::fs::write(&path, "# nothing yet\n").unwrap();
+        assert!(read_tracked_repositories(&path).is_err());
+        assert!(read_tracked_repositories(&dir.path().join("missing.txt")).is_err());
+    }
+}
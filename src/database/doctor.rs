@@ -0,0 +1,257 @@
+//! Privilege checks run by `doctor`
+//!
+//! The ingest role (`DATABASE_URL`) creates snapshot tables and writes the
+//! shared tables, so it needs `CREATE` on the schema and read/write access
+//! to the shared tables, but none of the cluster-wide role attributes. The
+//! read-only role (`DATABASE_URL_READ`) only needs `SELECT`; any write
+//! privilege it holds is reported unless it connects to a replica, which
+//! cannot write anyway.
+
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::{DatabaseManager, Result};
+
+/// Tables created by the embedded migrations, including the migration registry
+const SHARED_TABLES: &[&str] = &[
+    "_sqlx_migrations",
+    "query_history",
+    "repo_stars_timeline",
+    "repo_ci",
+    "repo_security",
+    "repo_scorecards",
+    "search_jobs",
+    "rate_limit_log",
+    "quarantined_items",
+    "snapshot_tables",
+    "saved_queries",
+    "repositories_latest",
+    "repository_history",
+    "duplicates",
+    "owner_stats",
+    "run_metrics",
+    "star_counts",
+];
+
+/// Table privileges the ingest role needs on every shared table
+const INGEST_PRIVILEGES: &[&str] = &["SELECT", "INSERT", "UPDATE", "DELETE"];
+
+/// Table privileges a read-only role must not hold
+const WRITE_PRIVILEGES: &[&str] = &["INSERT", "UPDATE", "DELETE", "TRUNCATE"];
+
+/// Role attributes neither role needs, as (`pg_roles` column, keyword)
+const EXCESS_ATTRIBUTES: &[(&str, &str)] = &[
+    ("rolsuper", "SUPERUSER"),
+    ("rolcreaterole", "CREATEROLE"),
+    ("rolcreatedb", "CREATEDB"),
+    ("rolreplication", "REPLICATION"),
+    ("rolbypassrls", "BYPASSRLS"),
+];
+
+/// Predefined roles that reach outside the database
+const EXCESS_MEMBERSHIPS: &[&str] = &["pg_read_server_files", "pg_write_server_files", "pg_execute_server_program"];
+
+/// Outcome of one `doctor` check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    /// Works, but grants more than needed
+    Warning,
+    /// Runs will fail, or the role is not read-only
+    Failed,
+}
+
+/// One line of the `doctor` report
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DoctorCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl DoctorCheck {
+    fn new(name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self { name: name.to_string(), status, detail: detail.into() }
+    }
+}
+
+impl DatabaseManager {
+    /// Check that the ingest role can do its work and holds nothing more
+    pub async fn check_ingest_privileges(&self) -> Result<Vec<DoctorCheck>> {
+        let (user, version): (String, String) =
+            sqlx::query_as("SELECT current_user::TEXT, current_setting('server_version')")
+                .fetch_one(&self.pool)
+                .await?;
+        let mut checks = vec![DoctorCheck::new(
+            "ingest connection",
+            CheckStatus::Ok,
+            format!("connected as {} to PostgreSQL {}", user, version),
+        )];
+
+        let excess = excess_privileges(&self.pool).await?;
+        checks.push(if excess.is_empty() {
+            DoctorCheck::new("ingest role attributes", CheckStatus::Ok, "no superuser or cluster-wide privileges")
+        } else {
+            DoctorCheck::new(
+                "ingest role attributes",
+                CheckStatus::Warning,
+                format!("{} has {}, which ingest does not need", user, excess.join(", ")),
+            )
+        });
+
+        let (usage, create): (bool, bool) = sqlx::query_as(
+            "SELECT has_schema_privilege($1, 'USAGE'), has_schema_privilege($1, 'CREATE')",
+        )
+        .bind(&self.schema)
+        .fetch_one(&self.pool)
+        .await?;
+        checks.push(if usage && create {
+            DoctorCheck::new("ingest schema", CheckStatus::Ok, format!("can create snapshot tables in {}", self.schema))
+        } else {
+            DoctorCheck::new(
+                "ingest schema",
+                CheckStatus::Failed,
+                format!("{} needs USAGE and CREATE on schema {}", user, self.schema),
+            )
+        });
+
+        let tables: Vec<String> = SHARED_TABLES.iter().map(|table| table.to_string()).collect();
+        let missing: Vec<String> = self
+            .table_privileges(&self.pool, &tables, INGEST_PRIVILEGES)
+            .await?
+            .into_iter()
+            .filter(|(_, _, granted)| !granted)
+            .map(|(table, privilege, _)| format!("{} on {}", privilege, table))
+            .collect();
+        checks.push(if missing.is_empty() {
+            DoctorCheck::new("ingest shared tables", CheckStatus::Ok, "can read and write every shared table")
+        } else {
+            DoctorCheck::new("ingest shared tables", CheckStatus::Failed, format!("missing {}", missing.join(", ")))
+        });
+
+        Ok(checks)
+    }
+
+    /// Check that the `DATABASE_URL_READ` role can read every table and write none
+    pub async fn check_read_privileges(&self) -> Result<Vec<DoctorCheck>> {
+        let (user, replica): (String, bool) = sqlx::query_as("SELECT current_user::TEXT, pg_is_in_recovery()")
+            .fetch_one(&self.read_pool)
+            .await?;
+        let mut checks = vec![DoctorCheck::new(
+            "read connection",
+            CheckStatus::Ok,
+            format!("connected as {}{}", user, if replica { " to a replica" } else { "" }),
+        )];
+
+        let mut tables: Vec<String> = SHARED_TABLES.iter().map(|table| table.to_string()).collect();
+        let snapshots: Vec<String> =
+            sqlx::query_scalar(&format!("SELECT table_name FROM {} ORDER BY table_name", self.shared_table("snapshot_tables")))
+                .fetch_all(&self.pool)
+                .await?;
+        tables.extend(snapshots);
+
+        if replica {
+            checks.push(DoctorCheck::new("read-only", CheckStatus::Ok, "replicas in recovery accept no writes"));
+        } else {
+            let mut writes = excess_privileges(&self.read_pool).await?;
+            let create: bool = sqlx::query_scalar("SELECT has_schema_privilege($1, 'CREATE')")
+                .bind(&self.schema)
+                .fetch_one(&self.read_pool)
+                .await?;
+            if create {
+                writes.push(format!("CREATE on schema {}", self.schema));
+            }
+            writes.extend(
+                self.table_privileges(&self.read_pool, &tables, WRITE_PRIVILEGES)
+                    .await?
+                    .into_iter()
+                    .filter(|(_, _, granted)| *granted)
+                    .map(|(table, privilege, _)| format!("{} on {}", privilege, table)),
+            );
+            checks.push(if writes.is_empty() {
+                DoctorCheck::new("read-only", CheckStatus::Ok, "holds no write privileges")
+            } else {
+                let shown = writes.iter().take(5).cloned().collect::<Vec<_>>().join(", ");
+                let more = writes.len().saturating_sub(5);
+                DoctorCheck::new(
+                    "read-only",
+                    CheckStatus::Failed,
+                    format!("{} can write: {}{}", user, shown, if more > 0 { format!(" and {} more", more) } else { String::new() }),
+                )
+            });
+        }
+
+        let unreadable: Vec<String> = self
+            .table_privileges(&self.read_pool, &tables, &["SELECT"])
+            .await?
+            .into_iter()
+            .filter(|(_, _, granted)| !granted)
+            .map(|(table, _, _)| table)
+            .collect();
+        checks.push(if unreadable.is_empty() {
+            DoctorCheck::new("read access", CheckStatus::Ok, format!("can read all {} tables", tables.len()))
+        } else {
+            DoctorCheck::new(
+                "read access",
+                CheckStatus::Failed,
+                format!(
+                    "cannot read {} of {} tables (first: {}); grant SELECT and ALTER DEFAULT PRIVILEGES for new snapshots",
+                    unreadable.len(),
+                    tables.len(),
+                    unreadable[0]
+                ),
+            )
+        });
+
+        Ok(checks)
+    }
+
+    /// Whether the role of `pool` holds each privilege on each existing table, as (table, privilege, granted)
+    async fn table_privileges(&self, pool: &PgPool, tables: &[String], privileges: &[&str]) -> Result<Vec<(String, String, bool)>> {
+        let sql = r#"
+            SELECT t.name, p.privilege, has_table_privilege(format('%I.%I', $1::TEXT, t.name), p.privilege)
+            FROM unnest($2::TEXT[]) WITH ORDINALITY AS t(name, position)
+            CROSS JOIN unnest($3::TEXT[]) WITH ORDINALITY AS p(privilege, position)
+            WHERE to_regclass(format('%I.%I', $1::TEXT, t.name)) IS NOT NULL
+            ORDER BY t.position, p.position
+        "#;
+        Ok(sqlx::query_as(sql)
+            .bind(&self.schema)
+            .bind(tables)
+            .bind(privileges)
+            .fetch_all(pool)
+            .await?)
+    }
+}
+
+/// Role attributes and predefined role memberships of the role of `pool` from the excess lists
+async fn excess_privileges(pool: &PgPool) -> Result<Vec<String>> {
+    let columns: Vec<String> = EXCESS_ATTRIBUTES.iter().map(|(column, _)| format!("r.{}", column)).collect();
+    let sql = format!("SELECT ARRAY[{}] FROM pg_roles r WHERE r.rolname = current_user", columns.join(", "));
+    let attributes: Vec<bool> = sqlx::query_scalar(&sql).fetch_one(pool).await?;
+    let memberships: Vec<bool> =
+        sqlx::query_scalar("SELECT ARRAY(SELECT pg_has_role(current_user, role::NAME, 'MEMBER') FROM unnest($1::TEXT[]) AS role)")
+            .bind(EXCESS_MEMBERSHIPS)
+            .fetch_one(pool)
+            .await?;
+
+    let superuser = attributes.first().copied().unwrap_or(false);
+    let mut excess: Vec<String> = EXCESS_ATTRIBUTES
+        .iter()
+        .zip(&attributes)
+        .filter(|(_, held)| **held)
+        .map(|((_, keyword), _)| keyword.to_string())
+        .collect();
+    // Superusers are members of every role; listing them adds nothing
+    if !superuser {
+        excess.extend(
+            EXCESS_MEMBERSHIPS
+                .iter()
+                .zip(&memberships)
+                .filter(|(_, held)| **held)
+                .map(|(role, _)| role.to_string()),
+        );
+    }
+    Ok(excess)
+}
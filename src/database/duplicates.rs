@@ -0,0 +1,248 @@
+//! Likely mirrors and unmarked forks within a snapshot
+//!
+//! Mirrors and copies pushed without GitHub's fork relationship show up as
+//! separate repositories: the same name under another owner, the same
+//! description, and about the same size. `duplicates` groups a snapshot by
+//! name, treats the oldest repository of each group as the original, and
+//! writes every later look-alike to the shared `duplicates` table so
+//! cleaning queries can anti-join against it.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use sqlx::FromRow;
+
+use crate::{AppError, DatabaseManager, Repository, RepositoryFilter, Result};
+
+/// Columns a snapshot needs for duplicate detection
+const DUPLICATE_COLUMNS: &[&str] = &["name", "description", "size_kb", "fork", "created_at"];
+
+/// How alike two repositories must be to count as duplicates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DuplicateOptions {
+    /// Smallest share of description words the two have in common (0.0 - 1.0)
+    pub min_similarity: f64,
+    /// Largest size difference, as a share of the larger size (0.0 - 1.0)
+    pub size_tolerance: f64,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        Self { min_similarity: 0.9, size_tolerance: 0.05 }
+    }
+}
+
+/// A repository that looks like a copy of an older one
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct Duplicate {
+    pub github_id: i64,
+    pub full_name: String,
+    /// Oldest repository of the same name the duplicate matches
+    pub original_github_id: i64,
+    pub original_full_name: String,
+    /// Jaccard similarity of the two descriptions' words
+    pub description_similarity: f64,
+    /// Size difference as a share of the larger size
+    pub size_difference: f64,
+}
+
+/// Repositories of `repositories` that duplicate an older one of the same name
+///
+/// Repositories already marked as forks are left out; GitHub reports those.
+/// Each duplicate points at the oldest matching repository that is not
+/// itself a duplicate, so a chain of copies reports one original.
+pub fn find_duplicates(repositories: &[Repository], options: &DuplicateOptions) -> Vec<Duplicate> {
+    let mut groups: BTreeMap<String, Vec<&Repository>> = BTreeMap::new();
+    for repo in repositories {
+        groups.entry(repo.name.to_lowercase()).or_default().push(repo);
+    }
+
+    let mut duplicates = Vec::new();
+    for mut group in groups.into_values().filter(|group| group.len() > 1) {
+        group.sort_by_key(|repo| (repo.created_at, repo.id));
+        let mut originals: Vec<&Repository> = Vec::new();
+        for repo in group {
+            let duplicate = (!repo.fork)
+                .then(|| {
+                    originals.iter().find_map(|original| {
+                        if owner(&original.full_name).eq_ignore_ascii_case(owner(&repo.full_name)) {
+                            return None;
+                        }
+                        let similarity = description_similarity(original.description.as_deref(), repo.description.as_deref());
+                        let difference = size_difference(original.size, repo.size);
+                        (similarity >= options.min_similarity && difference <= options.size_tolerance).then(|| Duplicate {
+                            github_id: repo.id,
+                            full_name: repo.full_name.clone(),
+                            original_github_id: original.id,
+                            original_full_name: original.full_name.clone(),
+                            description_similarity: similarity,
+                            size_difference: difference,
+                        })
+                    })
+                })
+                .flatten();
+            match duplicate {
+                Some(duplicate) => duplicates.push(duplicate),
+                None => originals.push(repo),
+            }
+        }
+    }
+    duplicates.sort_by(|a, b| a.original_full_name.cmp(&b.original_full_name).then(a.github_id.cmp(&b.github_id)));
+    duplicates
+}
+
+/// Jaccard similarity of the lowercase words of two descriptions
+///
+/// Two missing or empty descriptions are identical; one is not like any other.
+pub fn description_similarity(a: Option<&str>, b: Option<&str>) -> f64 {
+    let words = |text: Option<&str>| -> BTreeSet<String> {
+        text.unwrap_or_default()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    a.intersection(&b).count() as f64 / a.union(&b).count() as f64
+}
+
+/// Difference of two sizes as a share of the larger one
+fn size_difference(a: i64, b: i64) -> f64 {
+    match a.max(b) {
+        0 => 0.0,
+        larger => (a - b).abs() as f64 / larger as f64,
+    }
+}
+
+/// Owner part of a `full_name`, which every snapshot stores
+fn owner(full_name: &str) -> &str {
+    full_name.split_once('/').map_or("", |(owner, _)| owner)
+}
+
+impl DatabaseManager {
+    /// Find the duplicates in a snapshot and store them in `duplicates`
+    ///
+    /// Replaces the snapshot's earlier results, so re-running with other
+    /// thresholds leaves only the latest findings.
+    pub async fn detect_duplicates(&self, table_name: &str, options: &DuplicateOptions) -> Result<Vec<Duplicate>> {
+        let columns = self.table_columns(table_name).await?;
+        if let Some(missing) = DUPLICATE_COLUMNS
+            .iter()
+            .find(|name| !columns.iter().any(|column| column.name == **name))
+        {
+            return Err(AppError::configuration(format!(
+                "duplicates needs the {} column, which {} does not have",
+                missing, table_name
+            )));
+        }
+
+        let repositories: Vec<Repository> = self
+            .fetch_repositories(table_name, &RepositoryFilter::default())
+            .await?
+            .into_iter()
+            .map(|stored| stored.repository)
+            .collect();
+        let duplicates = find_duplicates(&repositories, options);
+
+        let table = self.shared_table("duplicates");
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(&format!("DELETE FROM {} WHERE table_name = $1", table))
+            .bind(table_name)
+            .execute(&mut *tx)
+            .await?;
+        let sql = format!(
+            r#"
+            INSERT INTO {} (table_name, github_id, full_name, original_github_id, original_full_name,
+                            description_similarity, size_difference)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            table
+        );
+        for duplicate in &duplicates {
+            sqlx::query(&sql)
+                .bind(table_name)
+                .bind(duplicate.github_id)
+                .bind(&duplicate.full_name)
+                .bind(duplicate.original_github_id)
+                .bind(&duplicate.original_full_name)
+                .bind(duplicate.description_similarity)
+                .bind(duplicate.size_difference)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(duplicates)
+    }
+
+    /// Duplicates last stored for a snapshot, grouped by original
+    pub async fn get_duplicates(&self, table_name: &str) -> Result<Vec<Duplicate>> {
+        let sql = format!(
+            r#"
+            SELECT github_id, full_name, original_github_id, original_full_name,
+                   description_similarity, size_difference
+            FROM {}
+            WHERE table_name = $1
+            ORDER BY original_full_name, github_id
+            "#,
+            self.shared_table("duplicates")
+        );
+        Ok(sqlx::query_as(&sql).bind(table_name).fetch_all(&self.read_pool).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repository(id: i64, full_name: &str, description: Option<&str>, size: i64, created: &str) -> Repository {
+        let mut repo = crate::models::test_repository(id, full_name);
+        repo.size = size;
+        repo.created_at = format!("{}T00:00:00Z", created).parse().unwrap();
+        repo.description = description.map(str::to_string);
+        repo
+    }
+
+    #[test]
+    fn test_description_similarity() {
+        assert_eq!(description_similarity(Some("A fast JSON parser"), Some("a FAST json-parser!")), 1.0);
+        assert_eq!(description_similarity(None, Some("")), 1.0);
+        assert_eq!(description_similarity(None, Some("parser")), 0.0);
+        assert_eq!(description_similarity(Some("fast json parser"), Some("fast yaml parser")), 0.5);
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let original = repository(1, "serde-rs/json", Some("Strongly typed JSON library"), 1000, "2016-01-01");
+        let repos = vec![
+            repository(3, "mirror/json", Some("Strongly typed JSON library for Rust"), 1010, "2020-01-01"),
+            repository(2, "copy/JSON", Some("Strongly typed JSON library"), 1020, "2018-01-01"),
+            original.clone(),
+            // Too different in size, and in description
+            repository(4, "big/json", Some("Strongly typed JSON library"), 5000, "2019-01-01"),
+            repository(5, "other/json", Some("JSON for Modern C++"), 1000, "2017-01-01"),
+            repository(6, "serde-rs/yaml", Some("Strongly typed JSON library"), 1000, "2021-01-01"),
+        ];
+        let mut marked_fork = repository(7, "fork/json", Some("Strongly typed JSON library"), 1000, "2022-01-01");
+        marked_fork.fork = true;
+
+        let mut all = repos.clone();
+        all.push(marked_fork);
+        let duplicates = find_duplicates(&all, &DuplicateOptions::default());
+        assert_eq!(
+            duplicates.iter().map(|d| (d.full_name.as_str(), d.original_full_name.as_str())).collect::<Vec<_>>(),
+            vec![("copy/JSON", "serde-rs/json")]
+        );
+        assert_eq!(duplicates[0].description_similarity, 1.0);
+        assert!(duplicates[0].size_difference < 0.02);
+
+        let loose = DuplicateOptions { min_similarity: 0.5, size_tolerance: 0.05 };
+        let duplicates = find_duplicates(&repos, &loose);
+        assert_eq!(
+            duplicates.iter().map(|d| (d.github_id, d.original_github_id)).collect::<Vec<_>>(),
+            vec![(2, 1), (3, 1)]
+        );
+    }
+}
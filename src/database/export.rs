@@ -0,0 +1,56 @@
+//! Reading snapshot tables back out for export to other systems
+
+use sqlx::FromRow;
+
+use crate::{AppError, DatabaseManager, Result};
+
+/// A column of a snapshot table as reported by `information_schema`
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct ColumnInfo {
+    pub name: String,
+    /// PostgreSQL type name (`int8`, `varchar`, `timestamptz`, `_text` for `TEXT[]`, ...)
+    pub udt_name: String,
+    pub nullable: bool,
+}
+
+impl DatabaseManager {
+    /// Columns of a snapshot table in definition order
+    pub async fn table_columns(&self, table_name: &str) -> Result<Vec<ColumnInfo>> {
+        Self::validate_table_name(table_name)?;
+        let sql = r#"
+            SELECT column_name AS name, udt_name::TEXT AS udt_name, is_nullable = 'YES' AS nullable
+            FROM information_schema.columns
+            WHERE table_schema = $1 AND table_name = $2
+            ORDER BY ordinal_position
+        "#;
+
+        let columns: Vec<ColumnInfo> = sqlx::query_as(sql)
+            .bind(&self.schema)
+            .bind(table_name)
+            .fetch_all(&self.read_pool)
+            .await?;
+        if columns.is_empty() {
+            return Err(AppError::configuration(format!("Table {} does not exist", table_name)));
+        }
+        Ok(columns)
+    }
+
+    /// Next batch of rows as JSON objects, keyed by the serial `id` column
+    ///
+    /// Pass the last returned `id` as `after_id` to continue; an empty batch
+    /// means the table has been read completely.
+    pub async fn export_rows_json(&self, table_name: &str, after_id: i32, limit: i64) -> Result<Vec<(i32, String)>> {
+        Self::validate_table_name(table_name)?;
+        let sql = format!(
+            "SELECT t.id, row_to_json(t)::TEXT FROM {} t WHERE t.id > $1 ORDER BY t.id LIMIT $2",
+            self.qualified_table(table_name)?
+        );
+
+        let rows = sqlx::query_as(&sql)
+            .bind(after_id)
+            .bind(limit)
+            .fetch_all(&self.read_pool)
+            .await?;
+        Ok(rows)
+    }
+}
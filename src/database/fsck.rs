@@ -0,0 +1,234 @@
+//! Snapshot integrity checks run by `fsck`
+//!
+//! Cross-checks the metadata tables against the snapshot tables themselves:
+//! recorded result counts against row counts, the `snapshot_tables` registry
+//! against the tables in the schema, and stored rows against the rules
+//! `Repository::validate` applies before inserting. Metadata problems can be
+//! repaired; invalid rows are only reported.
+
+use serde::Serialize;
+use sqlx::Row;
+
+use crate::{DatabaseManager, Result};
+
+/// Row rules checked on snapshot columns, as (column, rule, SQL condition a violating row meets)
+const ROW_RULES: &[(&str, &str, &str)] = &[
+    ("full_name", "full_name is empty", "full_name = ''"),
+    ("name", "name is empty", "name = ''"),
+    ("html_url", "html_url is not a GitHub URL", "html_url NOT LIKE 'https://github.com/%'"),
+    ("clone_url", "clone_url is not a GitHub clone URL", "clone_url NOT LIKE 'https://github.com/%.git'"),
+    ("ssh_url", "ssh_url is not a GitHub SSH URL", "ssh_url NOT LIKE 'git@github.com:%.git'"),
+    ("default_branch", "default_branch is empty", "default_branch = ''"),
+    ("visibility", "visibility is not public, private, or internal", "visibility NOT IN ('public', 'private', 'internal')"),
+    ("owner_login", "owner_login is empty", "owner_login = ''"),
+    ("size_kb", "size_kb is negative", "size_kb < 0"),
+    ("stargazers_count", "stargazers_count is negative", "stargazers_count < 0"),
+    ("watchers_count", "watchers_count is negative", "watchers_count < 0"),
+    ("forks_count", "forks_count is negative", "forks_count < 0"),
+    ("open_issues_count", "open_issues_count is negative", "open_issues_count < 0"),
+];
+
+/// A problem found by [`DatabaseManager::fsck`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "issue", rename_all = "kebab-case")]
+pub enum FsckIssue {
+    /// `query_history.result_count` of the only run that wrote the table differs from its rows
+    RowCountMismatch { table_name: String, query_id: uuid::Uuid, recorded: i64, actual: i64 },
+    /// A snapshot table without a `snapshot_tables` entry
+    Unregistered { table_name: String, kind: String },
+    /// A `snapshot_tables` entry whose table no longer exists
+    MissingTable { table_name: String },
+    /// Rows breaking a validation rule, with the `full_name` of one of them
+    InvalidRows { table_name: String, rule: String, rows: i64, example: String },
+}
+
+impl FsckIssue {
+    /// Whether [`DatabaseManager::repair_fsck_issue`] can fix it
+    pub fn repairable(&self) -> bool {
+        !matches!(self, FsckIssue::InvalidRows { .. })
+    }
+
+    /// One-line description
+    pub fn message(&self) -> String {
+        match self {
+            FsckIssue::RowCountMismatch { table_name, recorded, actual, .. } => format!(
+                "{}: query_history records {} results, the table has {} rows",
+                table_name, recorded, actual
+            ),
+            FsckIssue::Unregistered { table_name, kind } => {
+                format!("{}: {} snapshot missing from snapshot_tables", table_name, kind)
+            }
+            FsckIssue::MissingTable { table_name } => {
+                format!("{}: registered in snapshot_tables but the table does not exist", table_name)
+            }
+            FsckIssue::InvalidRows { table_name, rule, rows, example } => {
+                format!("{}: {} rows where {} (e.g. {})", table_name, rows, rule, example)
+            }
+        }
+    }
+}
+
+impl DatabaseManager {
+    /// Check every snapshot table and registry entry
+    pub async fn fsck(&self) -> Result<Vec<FsckIssue>> {
+        let mut issues = Vec::new();
+
+        let tables_sql = r#"
+            SELECT table_name::TEXT AS table_name,
+                   CASE WHEN starts_with(table_name, $2) THEN 'repositories' ELSE 'topics' END AS kind
+            FROM information_schema.tables
+            WHERE table_schema = $1 AND table_type = 'BASE TABLE'
+              AND (starts_with(table_name, $2) OR starts_with(table_name, $3))
+            ORDER BY table_name
+        "#;
+        let tables: Vec<(String, String)> = sqlx::query_as(tables_sql)
+            .bind(&self.schema)
+            .bind(self.repository_table_prefix())
+            .bind(format!("{}topics_", self.table_prefix))
+            .fetch_all(&self.pool)
+            .await?;
+        let registered: Vec<String> = sqlx::query_scalar(&format!(
+            "SELECT table_name FROM {} ORDER BY table_name",
+            self.shared_table("snapshot_tables")
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        for (table_name, kind) in &tables {
+            if !registered.contains(table_name) {
+                issues.push(FsckIssue::Unregistered { table_name: table_name.clone(), kind: kind.clone() });
+            }
+        }
+        for table_name in &registered {
+            if !tables.iter().any(|(name, _)| name == table_name) {
+                issues.push(FsckIssue::MissingTable { table_name: table_name.clone() });
+            }
+        }
+
+        // Tables several runs wrote to (--append-to, --output-table) hold more than any one run's results
+        let runs_sql = format!(
+            r#"
+            SELECT h.table_name, h.id, h.result_count
+            FROM {} h
+            WHERE h.success
+              AND (SELECT COUNT(*) FROM {} o WHERE o.table_name = h.table_name AND o.success) = 1
+            "#,
+            self.shared_table("query_history"),
+            self.shared_table("query_history")
+        );
+        let runs: Vec<(String, uuid::Uuid, i64)> = sqlx::query_as(&runs_sql).fetch_all(&self.pool).await?;
+        for (table_name, query_id, recorded) in runs {
+            if !tables.iter().any(|(name, _)| *name == table_name) {
+                continue;
+            }
+            let actual: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", self.qualified_table(&table_name)?))
+                .fetch_one(&self.pool)
+                .await?;
+            if actual != recorded {
+                issues.push(FsckIssue::RowCountMismatch { table_name, query_id, recorded, actual });
+            }
+        }
+
+        for (table_name, kind) in &tables {
+            if kind == "repositories" {
+                issues.extend(self.invalid_rows(table_name).await?);
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Rows of a repository snapshot breaking the rules for the columns it has
+    async fn invalid_rows(&self, table_name: &str) -> Result<Vec<FsckIssue>> {
+        let columns = self.table_columns(table_name).await?;
+        let rules: Vec<&(&str, &str, &str)> = ROW_RULES
+            .iter()
+            .filter(|(column, _, _)| columns.iter().any(|info| info.name == *column))
+            .collect();
+        let has_full_name = columns.iter().any(|info| info.name == "full_name");
+        if rules.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let aggregates: Vec<String> = rules
+            .iter()
+            .enumerate()
+            .map(|(index, (_, _, condition))| {
+                let example = if has_full_name { "MIN(full_name)" } else { "MIN(github_id::TEXT)" };
+                format!(
+                    "COUNT(*) FILTER (WHERE {condition}) AS rows_{index}, {example} FILTER (WHERE {condition}) AS example_{index}"
+                )
+            })
+            .collect();
+        let sql = format!("SELECT {} FROM {}", aggregates.join(", "), self.qualified_table(table_name)?);
+        let row = sqlx::query(&sql).fetch_one(&self.pool).await?;
+
+        Ok(rules
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (_, rule, _))| {
+                let rows: i64 = row.get(format!("rows_{}", index).as_str());
+                (rows > 0).then(|| FsckIssue::InvalidRows {
+                    table_name: table_name.to_string(),
+                    rule: rule.to_string(),
+                    rows,
+                    example: row.get::<Option<String>, _>(format!("example_{}", index).as_str()).unwrap_or_default(),
+                })
+            })
+            .collect())
+    }
+
+    /// Fix the metadata behind an issue; returns whether anything changed
+    ///
+    /// Mismatched result counts are set to the table's row count, unregistered
+    /// tables are registered with the latest run that wrote them, and entries
+    /// of missing tables are removed. Invalid rows are left alone.
+    pub async fn repair_fsck_issue(&self, issue: &FsckIssue) -> Result<bool> {
+        let result = match issue {
+            FsckIssue::RowCountMismatch { query_id, actual, .. } => {
+                let sql = format!("UPDATE {} SET result_count = $2 WHERE id = $1", self.shared_table("query_history"));
+                sqlx::query(&sql).bind(query_id).bind(actual).execute(&self.pool).await?
+            }
+            FsckIssue::Unregistered { table_name, kind } => {
+                let sql = format!(
+                    r#"
+                    INSERT INTO {} (table_name, kind, query_id, created_at)
+                    SELECT $1, $2, latest.id, COALESCE(latest.executed_at, NOW())
+                    FROM (SELECT NULL) AS one
+                    LEFT JOIN LATERAL (
+                        SELECT id, executed_at FROM {} WHERE table_name = $1 ORDER BY executed_at DESC LIMIT 1
+                    ) latest ON true
+                    ON CONFLICT (table_name) DO NOTHING
+                    "#,
+                    self.shared_table("snapshot_tables"),
+                    self.shared_table("query_history")
+                );
+                sqlx::query(&sql).bind(table_name).bind(kind).execute(&self.pool).await?
+            }
+            FsckIssue::MissingTable { table_name } => {
+                let sql = format!("DELETE FROM {} WHERE table_name = $1", self.shared_table("snapshot_tables"));
+                sqlx::query(&sql).bind(table_name).execute(&self.pool).await?
+            }
+            FsckIssue::InvalidRows { .. } => return Ok(false),
+        };
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fsck_issue_message() {
+        let issue = FsckIssue::InvalidRows {
+            table_name: "repos_1".to_string(),
+            rule: "stargazers_count is negative".to_string(),
+            rows: 2,
+            example: "user/repo".to_string(),
+        };
+        assert_eq!(issue.message(), "repos_1: 2 rows where stargazers_count is negative (e.g. user/repo)");
+        assert!(!issue.repairable());
+        assert!(FsckIssue::MissingTable { table_name: "repos_1".to_string() }.repairable());
+    }
+}
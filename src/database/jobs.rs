@@ -0,0 +1,279 @@
+//! Persistent queue of searches requested through `serve`
+//!
+//! Jobs move from `queued` to `running` when a worker claims them, then to
+//! `done`, or back to `queued` with a delay until their attempts run out and
+//! they end up `failed`. Claiming uses `FOR UPDATE SKIP LOCKED`, so several
+//! workers can share the queue without running a job twice.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::{AppError, DatabaseManager, Result};
+
+/// State of a queued search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    /// Waiting for a worker (possibly until `run_after` for a retry)
+    Queued,
+    /// Claimed by a worker
+    Running,
+    /// Gave up after `max_attempts`
+    Failed,
+    /// Completed successfully
+    Done,
+}
+
+impl JobStatus {
+    /// Value stored in `search_jobs.status`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Failed => "failed",
+            JobStatus::Done => "done",
+        }
+    }
+}
+
+impl fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for JobStatus {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "queued" => Ok(JobStatus::Queued),
+            "running" => Ok(JobStatus::Running),
+            "failed" => Ok(JobStatus::Failed),
+            "done" => Ok(JobStatus::Done),
+            _ => Err(AppError::internal(format!("Unknown job status '{}'", value))),
+        }
+    }
+}
+
+/// A row of `search_jobs`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchJob {
+    pub id: uuid::Uuid,
+    pub search_query: String,
+    /// Results per page; the server's default when `None`
+    pub per_page: Option<i32>,
+    /// Consecutive pages to fetch; the server's default when `None`
+    pub pages: Option<i32>,
+    pub status: JobStatus,
+    /// Times a worker has claimed the job
+    pub attempts: i32,
+    pub max_attempts: i32,
+    /// Error of the latest failed attempt
+    pub last_error: Option<String>,
+    /// query_history row written by the latest attempt
+    pub query_id: Option<uuid::Uuid>,
+    /// Run report of the latest attempt
+    pub report: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    /// Earliest time a worker may claim the job
+    pub run_after: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl SearchJob {
+    fn from_row(row: &PgRow) -> Result<Self> {
+        Ok(Self {
+            id: row.get("id"),
+            search_query: row.get("search_query"),
+            per_page: row.get("per_page"),
+            pages: row.get("pages"),
+            status: row.get::<String, _>("status").parse()?,
+            attempts: row.get("attempts"),
+            max_attempts: row.get("max_attempts"),
+            last_error: row.get("last_error"),
+            query_id: row.get("query_id"),
+            report: row.get("report"),
+            created_at: row.get("created_at"),
+            run_after: row.get("run_after"),
+            started_at: row.get("started_at"),
+            finished_at: row.get("finished_at"),
+        })
+    }
+}
+
+impl DatabaseManager {
+    /// Add a search to the queue
+    pub async fn enqueue_job(
+        &self,
+        search_query: &str,
+        per_page: Option<u32>,
+        pages: Option<u32>,
+        max_attempts: u32,
+    ) -> Result<SearchJob> {
+        let sql = format!(
+            r#"
+            INSERT INTO {} (id, search_query, per_page, pages, max_attempts)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+            self.shared_table("search_jobs")
+        );
+
+        let row = sqlx::query(&sql)
+            .bind(uuid::Uuid::new_v4())
+            .bind(search_query)
+            .bind(per_page.map(|value| value as i32))
+            .bind(pages.map(|value| value as i32))
+            .bind(max_attempts.max(1) as i32)
+            .fetch_one(&self.pool)
+            .await?;
+        SearchJob::from_row(&row)
+    }
+
+    /// Mark the oldest runnable job as running and return it
+    pub async fn claim_next_job(&self) -> Result<Option<SearchJob>> {
+        let table = self.shared_table("search_jobs");
+        let sql = format!(
+            r#"
+            UPDATE {table}
+            SET status = 'running', attempts = attempts + 1, started_at = NOW(), finished_at = NULL
+            WHERE id = (
+                SELECT id FROM {table}
+                WHERE status = 'queued' AND run_after <= NOW()
+                ORDER BY run_after, created_at
+                FOR UPDATE SKIP LOCKED
+                LIMIT 1
+            )
+            RETURNING *
+            "#
+        );
+
+        let row = sqlx::query(&sql).fetch_optional(&self.pool).await?;
+        row.as_ref().map(SearchJob::from_row).transpose()
+    }
+
+    /// Mark a running job as done
+    pub async fn complete_job(
+        &self,
+        id: uuid::Uuid,
+        query_id: Option<uuid::Uuid>,
+        report: &serde_json::Value,
+    ) -> Result<()> {
+        let sql = format!(
+            r#"
+            UPDATE {} SET status = 'done', query_id = $2, report = $3, last_error = NULL, finished_at = NOW()
+            WHERE id = $1
+            "#,
+            self.shared_table("search_jobs")
+        );
+
+        sqlx::query(&sql)
+            .bind(id)
+            .bind(query_id)
+            .bind(report)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Record a failed attempt, requeueing the job after `retry_delay` unless it has no attempts left
+    ///
+    /// Returns the job's new status.
+    pub async fn fail_job(
+        &self,
+        id: uuid::Uuid,
+        error: &str,
+        query_id: Option<uuid::Uuid>,
+        report: &serde_json::Value,
+        retry_delay: Duration,
+    ) -> Result<JobStatus> {
+        let sql = format!(
+            r#"
+            UPDATE {}
+            SET status = CASE WHEN attempts < max_attempts THEN 'queued' ELSE 'failed' END,
+                run_after = NOW() + make_interval(secs => $2),
+                last_error = $3, query_id = $4, report = $5, finished_at = NOW()
+            WHERE id = $1
+            RETURNING status
+            "#,
+            self.shared_table("search_jobs")
+        );
+
+        let status: String = sqlx::query_scalar(&sql)
+            .bind(id)
+            .bind(retry_delay.as_secs_f64())
+            .bind(error)
+            .bind(query_id)
+            .bind(report)
+            .fetch_one(&self.pool)
+            .await?;
+        status.parse()
+    }
+
+    /// Return jobs left running by a stopped server to the queue
+    ///
+    /// Only safe while no other server is working on the same queue.
+    pub async fn requeue_running_jobs(&self) -> Result<u64> {
+        let sql = format!(
+            "UPDATE {} SET status = 'queued', run_after = NOW() WHERE status = 'running'",
+            self.shared_table("search_jobs")
+        );
+        let result = sqlx::query(&sql).execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+
+    /// A job by ID
+    pub async fn get_job(&self, id: uuid::Uuid) -> Result<Option<SearchJob>> {
+        let sql = format!("SELECT * FROM {} WHERE id = $1", self.shared_table("search_jobs"));
+        let row = sqlx::query(&sql).bind(id).fetch_optional(&self.pool).await?;
+        row.as_ref().map(SearchJob::from_row).transpose()
+    }
+
+    /// Most recently created jobs, optionally only those in `status`
+    pub async fn list_jobs(&self, status: Option<JobStatus>, limit: i64) -> Result<Vec<SearchJob>> {
+        let sql = format!(
+            r#"
+            SELECT * FROM {}
+            WHERE $1::TEXT IS NULL OR status = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+            self.shared_table("search_jobs")
+        );
+
+        let rows = sqlx::query(&sql)
+            .bind(status.map(|status| status.as_str()))
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(SearchJob::from_row).collect()
+    }
+
+    /// Remove a job from the queue
+    pub async fn delete_job(&self, id: uuid::Uuid) -> Result<bool> {
+        let sql = format!("DELETE FROM {} WHERE id = $1", self.shared_table("search_jobs"));
+        let result = sqlx::query(&sql).bind(id).execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_status_round_trip() {
+        for status in [JobStatus::Queued, JobStatus::Running, JobStatus::Failed, JobStatus::Done] {
+            assert_eq!(status.as_str().parse::<JobStatus>().unwrap(), status);
+        }
+        assert!("cancelled".parse::<JobStatus>().is_err());
+        assert_eq!(serde_json::to_string(&JobStatus::Done).unwrap(), "\"done\"");
+    }
+}
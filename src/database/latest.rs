@@ -0,0 +1,77 @@
+//! The `repositories_latest` table
+//!
+//! Runs with `--update-latest` fold their snapshot into `repositories_latest`,
+//! which keeps the most recent row per `github_id` across all repository
+//! snapshots, so consumers can read the current state of every repository
+//! seen so far without picking a snapshot table.
+
+use sqlx::PgConnection;
+
+use crate::{DatabaseManager, Result};
+
+impl DatabaseManager {
+    /// Merge the rows of a repository snapshot into `repositories_latest`
+    ///
+    /// A row replaces the stored one unless that was fetched later, so
+    /// merging an older snapshot never rolls a repository back. Returns the
+    /// number of repositories inserted or updated.
+    pub async fn update_latest(&self, table_name: &str) -> Result<u64> {
+        Self::validate_table_name(table_name)?;
+        let result = sqlx::query(&self.fold_latest_sql(table_name)?)
+            .bind(table_name)
+            .bind(None::<Vec<i64>>)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Rebuild the `repositories_latest` rows of `github_ids` from the registered repository snapshots
+    ///
+    /// Used after the snapshot those rows came from is dropped: each
+    /// repository falls back to its newest row in the snapshots that remain,
+    /// or stays absent if none has it.
+    pub(crate) async fn refold_latest(&self, conn: &mut PgConnection, github_ids: &[i64]) -> Result<u64> {
+        if github_ids.is_empty() {
+            return Ok(0);
+        }
+        let sql = format!(
+            "SELECT table_name FROM {} WHERE kind = 'repositories' AND to_regclass(quote_ident($1) || '.' || quote_ident(table_name)) IS NOT NULL ORDER BY table_name",
+            self.shared_table("snapshot_tables")
+        );
+        let tables: Vec<String> = sqlx::query_scalar(&sql).bind(&self.schema).fetch_all(&mut *conn).await?;
+
+        let mut folded = 0;
+        for table_name in tables {
+            let result = sqlx::query(&self.fold_latest_sql(&table_name)?)
+                .bind(&table_name)
+                .bind(github_ids)
+                .execute(&mut *conn)
+                .await?;
+            folded += result.rows_affected();
+        }
+        Ok(folded)
+    }
+
+    /// Upsert of a snapshot's rows into `repositories_latest`, binding the
+    /// table name as `$1` and an optional `github_id` filter as `$2`
+    fn fold_latest_sql(&self, table_name: &str) -> Result<String> {
+        Ok(format!(
+            r#"
+            INSERT INTO {latest} AS l (github_id, full_name, table_name, fetched_at, data)
+            SELECT DISTINCT ON (t.github_id)
+                   t.github_id, t.full_name, $1, COALESCE(t.fetched_at, NOW()), to_jsonb(t) - 'id'
+            FROM {table} t
+            WHERE $2::BIGINT[] IS NULL OR t.github_id = ANY($2)
+            ORDER BY t.github_id, t.fetched_at DESC
+            ON CONFLICT (github_id) DO UPDATE SET
+                full_name = EXCLUDED.full_name,
+                table_name = EXCLUDED.table_name,
+                fetched_at = EXCLUDED.fetched_at,
+                data = EXCLUDED.data
+            WHERE l.fetched_at <= EXCLUDED.fetched_at
+            "#,
+            latest = self.shared_table("repositories_latest"),
+            table = self.qualified_table(table_name)?
+        ))
+    }
+}
@@ -0,0 +1,84 @@
+//! Owner-level aggregates of a snapshot
+//!
+//! `analyze owners` groups a snapshot by owner into the shared
+//! `owner_stats` table, answering "who are the biggest players in this
+//! niche" with repository counts, total stars, each owner's dominant
+//! language, whether it is an organization, and how old its repositories are.
+
+use sqlx::FromRow;
+
+use crate::{AppError, DatabaseManager, Result};
+
+/// Columns a snapshot needs for owner aggregates; `owner_type` is optional
+const OWNER_COLUMNS: &[&str] = &["owner_login", "stargazers_count", "language", "created_at", "fetched_at"];
+
+/// Aggregates of one owner's repositories in a snapshot
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct OwnerStats {
+    pub owner_login: String,
+    /// `Organization` or `User`; `None` when the snapshot lacks `owner_type`
+    pub owner_type: Option<String>,
+    pub repositories: i64,
+    pub total_stars: i64,
+    /// Most common primary language of the owner's repositories
+    pub dominant_language: Option<String>,
+    /// Mean repository age in days when the snapshot was taken
+    pub avg_repo_age_days: f64,
+}
+
+impl DatabaseManager {
+    /// Aggregate a snapshot by owner into `owner_stats`
+    ///
+    /// Replaces the snapshot's earlier rows and returns how many owners were written.
+    pub async fn analyze_owners(&self, table_name: &str) -> Result<u64> {
+        let columns = self.table_columns(table_name).await?;
+        let has = |name: &str| columns.iter().any(|column| column.name == name);
+        if let Some(missing) = OWNER_COLUMNS.iter().find(|name| !has(name)) {
+            return Err(AppError::configuration(format!(
+                "analyze owners needs the {} column, which {} does not have",
+                missing, table_name
+            )));
+        }
+        let owner_type = if has("owner_type") { "MAX(owner_type)" } else { "NULL" };
+
+        let stats = self.shared_table("owner_stats");
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(&format!("DELETE FROM {} WHERE table_name = $1", stats))
+            .bind(table_name)
+            .execute(&mut *tx)
+            .await?;
+        let sql = format!(
+            r#"
+            INSERT INTO {stats} (table_name, owner_login, owner_type, repositories, total_stars,
+                                 dominant_language, avg_repo_age_days)
+            SELECT $1, owner_login, {owner_type}, COUNT(*), SUM(stargazers_count),
+                   MODE() WITHIN GROUP (ORDER BY language),
+                   AVG(EXTRACT(EPOCH FROM (COALESCE(fetched_at, NOW()) - created_at)) / 86400)::FLOAT8
+            FROM {table}
+            GROUP BY owner_login
+            "#,
+            stats = stats,
+            owner_type = owner_type,
+            table = self.qualified_table(table_name)?,
+        );
+        let written = sqlx::query(&sql).bind(table_name).execute(&mut *tx).await?.rows_affected();
+        tx.commit().await?;
+
+        Ok(written)
+    }
+
+    /// Owners of a snapshot by total stars, from its last `analyze owners`
+    pub async fn get_owner_stats(&self, table_name: &str, limit: i64) -> Result<Vec<OwnerStats>> {
+        let sql = format!(
+            r#"
+            SELECT owner_login, owner_type, repositories, total_stars, dominant_language, avg_repo_age_days
+            FROM {}
+            WHERE table_name = $1
+            ORDER BY total_stars DESC, repositories DESC, owner_login
+            LIMIT $2
+            "#,
+            self.shared_table("owner_stats")
+        );
+        Ok(sqlx::query_as(&sql).bind(table_name).bind(limit).fetch_all(&self.read_pool).await?)
+    }
+}
@@ -0,0 +1,62 @@
+//! Search items that could not be mapped to a repository
+//!
+//! `SearchResponse` sets aside items that fail to deserialize instead of
+//! failing the page. Runs write them to `quarantined_items` next to their
+//! `query_history` row, with the raw payload, so the mapping can be fixed
+//! and the items replayed.
+
+use sqlx::Row;
+
+use crate::{DatabaseManager, QuarantinedItem, Result};
+
+impl DatabaseManager {
+    /// Store quarantined items for the snapshot `table_name`
+    pub async fn insert_quarantined_items(
+        &self,
+        query_id: Option<uuid::Uuid>,
+        table_name: &str,
+        items: &[QuarantinedItem],
+    ) -> Result<u64> {
+        if items.is_empty() {
+            return Ok(0);
+        }
+
+        let sql = format!(
+            "INSERT INTO {} (query_id, table_name, github_id, error, payload) VALUES ($1, $2, $3, $4, $5)",
+            self.shared_table("quarantined_items")
+        );
+
+        let mut tx = self.pool.begin().await?;
+        for item in items {
+            sqlx::query(&sql)
+                .bind(query_id)
+                .bind(table_name)
+                .bind(item.github_id)
+                .bind(&item.error)
+                .bind(&item.payload)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(items.len() as u64)
+    }
+
+    /// Items quarantined by a run, in the order they were stored
+    pub async fn get_quarantined_items(&self, query_id: uuid::Uuid) -> Result<Vec<QuarantinedItem>> {
+        let sql = format!(
+            "SELECT github_id, error, payload FROM {} WHERE query_id = $1 ORDER BY id",
+            self.shared_table("quarantined_items")
+        );
+
+        let rows = sqlx::query(&sql).bind(query_id).fetch_all(&self.pool).await?;
+        Ok(rows
+            .iter()
+            .map(|row| QuarantinedItem {
+                github_id: row.get("github_id"),
+                error: row.get("error"),
+                payload: row.get("payload"),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,80 @@
+//! Per-response rate limit log
+//!
+//! `GitHubClient` records the rate limit headers of every response; runs
+//! write them to `rate_limit_log` next to their `query_history` row, so the
+//! quota spent by each crawl and enrichment pass can be inspected afterwards.
+
+use sqlx::Row;
+
+use crate::{DatabaseManager, RateLimitSample, Result};
+
+impl DatabaseManager {
+    /// Append rate limit samples, optionally attributed to a `query_history` row
+    pub async fn insert_rate_limit_log(
+        &self,
+        query_id: Option<uuid::Uuid>,
+        samples: &[RateLimitSample],
+    ) -> Result<u64> {
+        if samples.is_empty() {
+            return Ok(0);
+        }
+
+        let sql = format!(
+            r#"
+            INSERT INTO {} (query_id, observed_at, endpoint, status, resource, quota_limit, remaining, used, reset_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+            self.shared_table("rate_limit_log")
+        );
+
+        let mut tx = self.pool.begin().await?;
+        for sample in samples {
+            sqlx::query(&sql)
+                .bind(query_id)
+                .bind(sample.observed_at)
+                .bind(&sample.endpoint)
+                .bind(sample.status as i16)
+                .bind(&sample.resource)
+                .bind(sample.limit.map(|value| value as i32))
+                .bind(sample.remaining.map(|value| value as i32))
+                .bind(sample.used.map(|value| value as i32))
+                .bind(sample.reset_at)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(samples.len() as u64)
+    }
+
+    /// Rate limit samples recorded for a run, oldest first
+    pub async fn get_rate_limit_log(&self, query_id: uuid::Uuid) -> Result<Vec<RateLimitSample>> {
+        let sql = format!(
+            r#"
+            SELECT observed_at, endpoint, status, resource, quota_limit, remaining, used, reset_at
+            FROM {}
+            WHERE query_id = $1
+            ORDER BY observed_at, id
+            "#,
+            self.shared_table("rate_limit_log")
+        );
+
+        let rows = sqlx::query(&sql).bind(query_id).fetch_all(&self.read_pool).await?;
+        let count = |row: &sqlx::postgres::PgRow, column: &str| {
+            row.get::<Option<i32>, _>(column).map(|value| value as u32)
+        };
+        Ok(rows
+            .iter()
+            .map(|row| RateLimitSample {
+                observed_at: row.get("observed_at"),
+                endpoint: row.get("endpoint"),
+                status: row.get::<i16, _>("status") as u16,
+                resource: row.get("resource"),
+                limit: count(row, "quota_limit"),
+                remaining: count(row, "remaining"),
+                used: count(row, "used"),
+                reset_at: row.get("reset_at"),
+            })
+            .collect())
+    }
+}
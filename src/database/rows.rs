@@ -0,0 +1,318 @@
+//! Reading stored repositories back from snapshot tables
+//!
+//! [`Repository`] implements `sqlx::FromRow` for snapshot rows of any
+//! [`ColumnSet`](crate::ColumnSet): columns a table does not have are left
+//! at their defaults (empty strings, zero, `false`, `None`, and the Unix
+//! epoch for dates). [`DatabaseManager::fetch_repositories`] returns the
+//! rows as [`StoredRepository`] values with their row metadata, a page at a
+//! time when a [`RepositoryFilter`] sets `limit` and `offset`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::postgres::PgRow;
+use sqlx::types::Json;
+use sqlx::{Decode, FromRow, Postgres, Row, Type};
+
+use crate::{AppError, DatabaseManager, Repository, RepositoryLicense, RepositoryOwner, Result, TextMatch};
+
+/// A repository as stored in a snapshot table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StoredRepository {
+    /// Row id within the snapshot table
+    pub row_id: i32,
+    /// When the row was written
+    pub fetched_at: Option<DateTime<Utc>>,
+    /// `query_history` id of the run that wrote the snapshot, if registered
+    pub snapshot_id: Option<uuid::Uuid>,
+    pub repository: Repository,
+}
+
+/// Which rows [`DatabaseManager::fetch_repositories`] returns
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepositoryFilter {
+    /// Primary language, compared case-insensitively
+    pub language: Option<String>,
+    /// Owner login, compared case-insensitively
+    pub owner: Option<String>,
+    /// Fewest stars a repository may have
+    pub min_stars: Option<i64>,
+    /// Most rows to return; all rows when `None`
+    pub limit: Option<i64>,
+    /// Rows to skip before the first one returned
+    pub offset: Option<i64>,
+    /// Order of the returned rows
+    pub order_by: RepositoryOrder,
+}
+
+impl RepositoryFilter {
+    /// Check that `limit` and `offset` are not negative
+    pub fn validate(&self) -> Result<()> {
+        if self.limit.is_some_and(|limit| limit < 0) {
+            return Err(AppError::validation("limit", "must not be negative"));
+        }
+        if self.offset.is_some_and(|offset| offset < 0) {
+            return Err(AppError::validation("offset", "must not be negative"));
+        }
+        Ok(())
+    }
+}
+
+/// Sort order of [`DatabaseManager::fetch_repositories`]
+///
+/// Every order ends with the row id, so pages taken with `offset` neither
+/// skip nor repeat rows that tie.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RepositoryOrder {
+    /// Insertion order
+    #[default]
+    Inserted,
+    /// Most stars first
+    Stars,
+    /// Most forks first
+    Forks,
+    /// Most recently updated first
+    Updated,
+    /// Most recently created first
+    Created,
+    /// Alphabetically by full name
+    Name,
+}
+
+impl RepositoryOrder {
+    /// All supported orders
+    pub const ALL: &'static [RepositoryOrder] = &[
+        RepositoryOrder::Inserted,
+        RepositoryOrder::Stars,
+        RepositoryOrder::Forks,
+        RepositoryOrder::Updated,
+        RepositoryOrder::Created,
+        RepositoryOrder::Name,
+    ];
+
+    /// Name used in query strings
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RepositoryOrder::Inserted => "inserted",
+            RepositoryOrder::Stars => "stars",
+            RepositoryOrder::Forks => "forks",
+            RepositoryOrder::Updated => "updated",
+            RepositoryOrder::Created => "created",
+            RepositoryOrder::Name => "name",
+        }
+    }
+
+    /// `ORDER BY` expression
+    fn sql(&self) -> &'static str {
+        match self {
+            RepositoryOrder::Inserted => "t.id",
+            RepositoryOrder::Stars => "t.stargazers_count DESC, t.id",
+            RepositoryOrder::Forks => "t.forks_count DESC, t.id",
+            RepositoryOrder::Updated => "t.updated_at DESC, t.id",
+            RepositoryOrder::Created => "t.created_at DESC, t.id",
+            RepositoryOrder::Name => "t.full_name, t.id",
+        }
+    }
+}
+
+impl fmt::Display for RepositoryOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RepositoryOrder {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        RepositoryOrder::ALL
+            .iter()
+            .copied()
+            .find(|order| order.as_str() == value)
+            .ok_or_else(|| {
+                AppError::validation(
+                    "order_by",
+                    format!("unknown order '{}' (supported: inserted, stars, forks, updated, created, name)", value),
+                )
+            })
+    }
+}
+
+/// Value of `column`, or `None` when the table does not have it
+fn optional<'r, T>(row: &'r PgRow, column: &str) -> sqlx::Result<Option<T>>
+where
+    T: Decode<'r, Postgres> + Type<Postgres>,
+{
+    match row.try_get::<Option<T>, _>(column) {
+        Ok(value) => Ok(value),
+        Err(sqlx::Error::ColumnNotFound(_)) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Value of `column`, or the type's default when the table does not have it
+fn or_default<'r, T>(row: &'r PgRow, column: &str) -> sqlx::Result<T>
+where
+    T: Decode<'r, Postgres> + Type<Postgres> + Default,
+{
+    Ok(optional(row, column)?.unwrap_or_default())
+}
+
+impl<'r> FromRow<'r, PgRow> for Repository {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        let license = optional::<String>(row, "license_key")?.map(|key| -> sqlx::Result<_> {
+            Ok(RepositoryLicense {
+                key,
+                name: or_default(row, "license_name")?,
+                spdx_id: optional(row, "license_spdx_id")?,
+                url: optional(row, "license_url")?,
+            })
+        });
+        let date = |column| -> sqlx::Result<DateTime<Utc>> {
+            Ok(optional(row, column)?.unwrap_or(DateTime::UNIX_EPOCH))
+        };
+
+        Ok(Repository {
+            id: row.try_get("github_id")?,
+            full_name: row.try_get("full_name")?,
+            name: or_default(row, "name")?,
+            description: optional(row, "description")?,
+            html_url: or_default(row, "html_url")?,
+            clone_url: or_default(row, "clone_url")?,
+            ssh_url: or_default(row, "ssh_url")?,
+            size: or_default(row, "size_kb")?,
+            stargazers_count: or_default(row, "stargazers_count")?,
+            watchers_count: or_default(row, "watchers_count")?,
+            forks_count: or_default(row, "forks_count")?,
+            open_issues_count: or_default(row, "open_issues_count")?,
+            language: optional(row, "language")?,
+            default_branch: or_default(row, "default_branch")?,
+            visibility: or_default(row, "visibility")?,
+            private: or_default(row, "private")?,
+            fork: or_default(row, "fork")?,
+            archived: or_default(row, "archived")?,
+            disabled: or_default(row, "disabled")?,
+            created_at: date("created_at")?,
+            updated_at: date("updated_at")?,
+            pushed_at: optional(row, "pushed_at")?,
+            owner: RepositoryOwner {
+                id: or_default(row, "owner_id")?,
+                login: or_default(row, "owner_login")?,
+                owner_type: or_default(row, "owner_type")?,
+                avatar_url: or_default(row, "owner_avatar_url")?,
+                html_url: or_default(row, "owner_html_url")?,
+                site_admin: or_default(row, "owner_site_admin")?,
+            },
+            license: license.transpose()?,
+            topics: or_default(row, "topics")?,
+            has_issues: or_default(row, "has_issues")?,
+            has_projects: or_default(row, "has_projects")?,
+            has_wiki: or_default(row, "has_wiki")?,
+            has_pages: or_default(row, "has_pages")?,
+            has_downloads: or_default(row, "has_downloads")?,
+            score: optional(row, "score")?,
+            text_matches: optional::<Json<Vec<TextMatch>>>(row, "text_matches")?
+                .map(|matches| matches.0)
+                .unwrap_or_default(),
+            matched_language_filter: optional(row, "matched_language_filter")?,
+        })
+    }
+}
+
+impl<'r> FromRow<'r, PgRow> for StoredRepository {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        Ok(StoredRepository {
+            row_id: row.try_get("id")?,
+            fetched_at: optional(row, "fetched_at")?,
+            snapshot_id: optional(row, "snapshot_id")?,
+            repository: Repository::from_row(row)?,
+        })
+    }
+}
+
+impl DatabaseManager {
+    /// Repositories stored in a snapshot table, in `filter.order_by` order
+    ///
+    /// Only the filters and order that are set refer to their columns, so any
+    /// column set can be read; filtering or sorting on a column the table does
+    /// not have fails with a database error. Values are always bound as
+    /// parameters.
+    pub async fn fetch_repositories(&self, table_name: &str, filter: &RepositoryFilter) -> Result<Vec<StoredRepository>> {
+        Self::validate_table_name(table_name)?;
+        filter.validate()?;
+        let mut conditions = vec!["TRUE".to_string()];
+        let mut bound = 1;
+        let mut placeholder = || {
+            bound += 1;
+            format!("${}", bound)
+        };
+        if filter.language.is_some() {
+            conditions.push(format!("lower(t.language) = lower({})", placeholder()));
+        }
+        if filter.owner.is_some() {
+            conditions.push(format!("lower(t.owner_login) = lower({})", placeholder()));
+        }
+        if filter.min_stars.is_some() {
+            conditions.push(format!("t.stargazers_count >= {}", placeholder()));
+        }
+        let sql = format!(
+            r#"
+            SELECT t.*, s.query_id AS snapshot_id
+            FROM {} t
+            LEFT JOIN {} s ON s.table_name = $1
+            WHERE {}
+            ORDER BY {}
+            LIMIT {} OFFSET {}
+            "#,
+            self.qualified_table(table_name)?,
+            self.shared_table("snapshot_tables"),
+            conditions.join(" AND "),
+            filter.order_by.sql(),
+            placeholder(),
+            placeholder()
+        );
+
+        let query = sqlx::query_as::<_, StoredRepository>(&sql).bind(table_name);
+        let query = match &filter.language {
+            Some(language) => query.bind(language),
+            None => query,
+        };
+        let query = match &filter.owner {
+            Some(owner) => query.bind(owner),
+            None => query,
+        };
+        let query = match filter.min_stars {
+            Some(min_stars) => query.bind(min_stars),
+            None => query,
+        };
+        let rows = query
+            .bind(filter.limit)
+            .bind(filter.offset)
+            .fetch_all(&self.read_pool)
+            .await?;
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository_order_round_trip() {
+        for order in RepositoryOrder::ALL {
+            assert_eq!(order.as_str().parse::<RepositoryOrder>().unwrap(), *order);
+            assert!(order.sql().ends_with("t.id"));
+        }
+        assert!("stars; DROP TABLE query_history".parse::<RepositoryOrder>().is_err());
+    }
+
+    #[test]
+    fn test_filter_validate() {
+        assert!(RepositoryFilter::default().validate().is_ok());
+        assert!(RepositoryFilter { limit: Some(-1), ..RepositoryFilter::default() }.validate().is_err());
+        assert!(RepositoryFilter { offset: Some(-5), ..RepositoryFilter::default() }.validate().is_err());
+    }
+}
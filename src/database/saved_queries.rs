@@ -0,0 +1,92 @@
+//! Named search templates
+//!
+//! `save-query nightly-rust "language:rust stars:>{{min_stars}}"` stores a
+//! template in `saved_queries`; `run nightly-rust --param min_stars=500`
+//! fills in its placeholders (see [`crate::template`]) and runs the search,
+//! recording the template name in `query_history.saved_query`.
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use crate::{template_parameters, AppError, DatabaseManager, Result};
+
+/// Longest saved query name, matching the `saved_queries.name` column
+const MAX_NAME_LENGTH: usize = 100;
+
+/// A search template stored by `save-query`
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct SavedQuery {
+    pub name: String,
+    /// Search query with `{{parameter}}` placeholders
+    pub template: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SavedQuery {
+    /// Check that a name uses only lowercase letters, digits, `-`, and `_`
+    pub fn validate_name(name: &str) -> Result<()> {
+        let valid = !name.is_empty()
+            && name.len() <= MAX_NAME_LENGTH
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !valid {
+            return Err(AppError::validation(
+                "name",
+                "Saved query names may only contain lowercase letters, digits, '-' and '_' (up to 100 characters)",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl DatabaseManager {
+    /// Create or replace the saved query `name`
+    pub async fn save_query(&self, name: &str, template: &str) -> Result<SavedQuery> {
+        SavedQuery::validate_name(name)?;
+        template_parameters(template)?;
+
+        let sql = format!(
+            r#"
+            INSERT INTO {} (name, template) VALUES ($1, $2)
+            ON CONFLICT (name) DO UPDATE SET template = EXCLUDED.template, updated_at = NOW()
+            RETURNING name, template, created_at, updated_at
+            "#,
+            self.shared_table("saved_queries")
+        );
+        let saved = sqlx::query_as::<_, SavedQuery>(&sql)
+            .bind(name)
+            .bind(template)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(saved)
+    }
+
+    /// Look up a saved query by name
+    pub async fn get_saved_query(&self, name: &str) -> Result<Option<SavedQuery>> {
+        let sql = format!(
+            "SELECT name, template, created_at, updated_at FROM {} WHERE name = $1",
+            self.shared_table("saved_queries")
+        );
+        let saved = sqlx::query_as::<_, SavedQuery>(&sql)
+            .bind(name)
+            .fetch_optional(&self.read_pool)
+            .await?;
+        Ok(saved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(SavedQuery::validate_name("nightly-rust").is_ok());
+        assert!(SavedQuery::validate_name("weekly_go_2").is_ok());
+        assert!(SavedQuery::validate_name("").is_err());
+        assert!(SavedQuery::validate_name("Nightly Rust").is_err());
+        assert!(SavedQuery::validate_name(&"a".repeat(101)).is_err());
+    }
+}
@@ -0,0 +1,482 @@
+//! DDL of repository snapshot tables
+//!
+//! `create_repository_table` runs the statements generated here, and the
+//! `schema` subcommand prints them (or a JSON description) for the column
+//! set chosen with `--columns`, so ETL jobs and ORMs can model snapshot
+//! tables without connecting to one. `--append-to` checks an existing table
+//! against the same column set before writing to it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{AppError, DatabaseManager, DatabaseOptions, RepositoryColumn, Result};
+
+/// Output format of the `schema` subcommand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaFormat {
+    /// `CREATE TABLE` and `CREATE INDEX` statements
+    Sql,
+    /// Columns and indexes as a JSON document
+    Json,
+}
+
+impl SchemaFormat {
+    /// All supported formats
+    pub const ALL: &'static [SchemaFormat] = &[SchemaFormat::Sql, SchemaFormat::Json];
+
+    /// Name used on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchemaFormat::Sql => "sql",
+            SchemaFormat::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for SchemaFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SchemaFormat {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        SchemaFormat::ALL
+            .iter()
+            .copied()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| {
+                AppError::configuration(format!("Unknown schema format '{}' (supported: sql, json)", value))
+            })
+    }
+}
+
+/// Which indexes new snapshot tables get
+///
+/// Every index slows bulk inserts, so large loads that are only read back
+/// whole can choose fewer. The `github_id` uniqueness constraint is part of
+/// the table and always present.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IndexSet {
+    /// No secondary indexes
+    None,
+    /// `full_name` and `stargazers_count`, which lookups and rankings use
+    Minimal,
+    /// Every index for the columns the table has
+    #[default]
+    Full,
+}
+
+impl IndexSet {
+    /// All index sets
+    pub const ALL: &'static [IndexSet] = &[IndexSet::None, IndexSet::Minimal, IndexSet::Full];
+
+    /// Name used on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IndexSet::None => "none",
+            IndexSet::Minimal => "minimal",
+            IndexSet::Full => "full",
+        }
+    }
+}
+
+impl fmt::Display for IndexSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for IndexSet {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        IndexSet::ALL
+            .iter()
+            .copied()
+            .find(|indexes| indexes.as_str() == value)
+            .ok_or_else(|| {
+                AppError::configuration(format!("Unknown index set '{}' (supported: none, minimal, full)", value))
+            })
+    }
+}
+
+/// PostgreSQL storage options of new snapshot tables
+///
+/// For snapshots treated as disposable staging data: unlogged tables skip
+/// the write-ahead log, so they load faster but are emptied after a crash
+/// and are not copied to replicas.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableStorage {
+    /// `CREATE UNLOGGED TABLE`
+    pub unlogged: bool,
+    /// Storage parameters for `WITH (...)`, as (name, value)
+    pub parameters: Vec<(String, String)>,
+}
+
+impl TableStorage {
+    /// Parse a `--storage-param NAME=VALUE` autovacuum setting
+    ///
+    /// Only `autovacuum_*` and `toast.autovacuum_*` parameters are accepted,
+    /// with plain numbers or booleans as values, since both end up in DDL.
+    pub fn parse_parameter(parameter: &str) -> Result<(String, String)> {
+        let (name, value) = parameter
+            .split_once('=')
+            .map(|(name, value)| (name.trim(), value.trim()))
+            .ok_or_else(|| AppError::validation("storage-param", format!("'{}' is not NAME=VALUE", parameter)))?;
+        let setting = name.strip_prefix("toast.").unwrap_or(name);
+        let valid_name = setting.starts_with("autovacuum_")
+            && setting.chars().all(|c| c.is_ascii_lowercase() || c == '_');
+        if !valid_name {
+            return Err(AppError::validation(
+                "storage-param",
+                format!("'{}' is not an autovacuum_* or toast.autovacuum_* parameter", name),
+            ));
+        }
+        let valid_value = !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '.');
+        if !valid_value {
+            return Err(AppError::validation(
+                "storage-param",
+                format!("value of '{}' must be a number or boolean", name),
+            ));
+        }
+        Ok((name.to_string(), value.to_lowercase()))
+    }
+
+    /// The `WITH (...)` clause, empty without parameters
+    fn with_clause(&self) -> String {
+        if self.parameters.is_empty() {
+            return String::new();
+        }
+        let parameters: Vec<String> =
+            self.parameters.iter().map(|(name, value)| format!("{} = {}", name, value)).collect();
+        format!(" WITH ({})", parameters.join(", "))
+    }
+}
+
+/// Index suffixes kept by [`IndexSet::Minimal`]
+const MINIMAL_INDEXES: &[&str] = &["full_name", "stargazers"];
+
+/// Longest table name used verbatim in index names; with `idx_` and the
+/// longest suffix (`_owner_login`) that is PostgreSQL's 63-character limit
+const INDEX_TABLE_NAME_MAX: usize = 47;
+
+/// `idx_<table>_` prefix of a snapshot's index names
+///
+/// Names longer than [`INDEX_TABLE_NAME_MAX`] are cut and followed by a hash
+/// of the full name, so PostgreSQL never truncates an index name and two long
+/// tables sharing a prefix still get distinct indexes.
+pub(crate) fn index_name_prefix(table_name: &str) -> String {
+    if table_name.len() <= INDEX_TABLE_NAME_MAX {
+        return format!("idx_{}_", table_name);
+    }
+    let digest = Sha256::digest(table_name.as_bytes());
+    let hash: String = digest[..4].iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("idx_{}_{}_", &table_name[..INDEX_TABLE_NAME_MAX - 9], hash)
+}
+
+/// Definition of one repository snapshot table
+#[derive(Clone)]
+pub struct SnapshotSchema {
+    schema: String,
+    table_name: String,
+    columns: Vec<&'static RepositoryColumn>,
+    indexes: IndexSet,
+    storage: TableStorage,
+}
+
+/// One column as described by `schema --format json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaColumn {
+    pub name: &'static str,
+    /// Column type without constraints
+    #[serde(rename = "type")]
+    pub sql_type: &'static str,
+    pub nullable: bool,
+    /// Type and constraints as written in `CREATE TABLE`
+    pub definition: &'static str,
+    /// `Repository` field the value comes from; `None` for columns filled by enrichments or defaults
+    pub source_field: Option<&'static str>,
+}
+
+/// One index as described by `schema --format json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaIndex {
+    pub name: String,
+    pub columns: Vec<&'static str>,
+    pub definition: String,
+}
+
+impl SnapshotSchema {
+    /// Schema of `table_name` with the schema and column set of `options`
+    pub fn new(options: &DatabaseOptions, table_name: &str) -> Result<Self> {
+        DatabaseManager::validate_identifier("schema", &options.schema)?;
+        Ok(Self::from_parts(&options.schema, table_name, options.columns.columns(), options.indexes)?
+            .with_storage(options.storage.clone()))
+    }
+
+    pub(crate) fn from_parts(
+        schema: &str,
+        table_name: &str,
+        columns: Vec<&'static RepositoryColumn>,
+        indexes: IndexSet,
+    ) -> Result<Self> {
+        DatabaseManager::validate_identifier("table_name", table_name)?;
+        Ok(Self {
+            schema: schema.to_string(),
+            table_name: table_name.to_string(),
+            columns,
+            indexes,
+            storage: TableStorage::default(),
+        })
+    }
+
+    /// Create the table with these storage options
+    pub(crate) fn with_storage(mut self, storage: TableStorage) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Quoted, schema-qualified table name
+    pub fn qualified_table(&self) -> String {
+        format!("\"{}\".\"{}\"", self.schema, self.table_name)
+    }
+
+    /// `CREATE TABLE` statement
+    pub fn create_table_sql(&self) -> String {
+        let columns: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| format!("{} {}", column.name, column.definition))
+            .collect();
+        format!(
+            "CREATE {}TABLE IF NOT EXISTS {} (\n    {}\n){}",
+            if self.storage.unlogged { "UNLOGGED " } else { "" },
+            self.qualified_table(),
+            columns.join(",\n    "),
+            self.storage.with_clause()
+        )
+    }
+
+    /// Indexes of the index set for the columns the table has
+    pub fn indexes(&self) -> Vec<SchemaIndex> {
+        let suffix_of = |index: &SchemaIndex| index.name[index_name_prefix(&self.table_name).len()..].to_string();
+        match self.indexes {
+            IndexSet::None => Vec::new(),
+            IndexSet::Minimal => self
+                .all_indexes()
+                .into_iter()
+                .filter(|index| MINIMAL_INDEXES.contains(&suffix_of(index).as_str()))
+                .collect(),
+            IndexSet::Full => self.all_indexes(),
+        }
+    }
+
+    /// Every index for the columns the table has
+    fn all_indexes(&self) -> Vec<SchemaIndex> {
+        let table = self.qualified_table();
+        let index = |suffix: &str, columns: Vec<&'static str>, expression: &str| {
+            let name = format!("{}{}", index_name_prefix(&self.table_name), suffix);
+            SchemaIndex {
+                definition: format!("CREATE INDEX IF NOT EXISTS \"{}\" ON {}({})", name, table, expression),
+                name,
+                columns,
+            }
+        };
+        let has = |name: &str| self.columns.iter().any(|column| column.name == name);
+
+        let mut indexes = Vec::new();
+        for column in ["github_id", "full_name", "language"] {
+            if has(column) {
+                indexes.push(index(column, vec![column], column));
+            }
+        }
+        if has("stargazers_count") {
+            indexes.push(index("stargazers", vec!["stargazers_count"], "stargazers_count DESC"));
+        }
+        for column in ["created_at", "owner_login"] {
+            if has(column) {
+                indexes.push(index(column, vec![column], column));
+            }
+        }
+        if has("name") && has("description") {
+            indexes.push(SchemaIndex {
+                name: format!("{}search", index_name_prefix(&self.table_name)),
+                columns: vec!["name", "description"],
+                definition: DatabaseManager::search_index_sql(&self.table_name, &table),
+            });
+        }
+        indexes
+    }
+
+    /// Every statement that creates the table and its indexes, in order
+    pub fn statements(&self) -> Vec<String> {
+        let mut statements = vec![self.create_table_sql()];
+        statements.extend(self.indexes().into_iter().map(|index| index.definition));
+        statements
+    }
+
+    /// The statements as a SQL script
+    pub fn to_sql(&self) -> String {
+        self.statements()
+            .iter()
+            .map(|statement| format!("{};\n", statement))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Columns with their types, nullability, and source fields
+    pub fn columns(&self) -> Vec<SchemaColumn> {
+        self.columns
+            .iter()
+            .map(|column| SchemaColumn {
+                name: column.name,
+                sql_type: column.sql_type(),
+                nullable: !column.definition.contains("NOT NULL") && !column.definition.contains("PRIMARY KEY"),
+                definition: column.definition,
+                source_field: column.field,
+            })
+            .collect()
+    }
+
+    /// The table as a JSON document
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "schema": self.schema,
+            "table": self.table_name,
+            "columns": self.columns(),
+            "indexes": self.indexes(),
+        })
+    }
+}
+
+impl DatabaseManager {
+    /// Check that an existing table can take rows of the configured column set
+    ///
+    /// Used by `--append-to`: every configured column must exist with the
+    /// type `create_repository_table` would give it. Extra columns are fine.
+    pub async fn check_append_target(&self, table_name: &str) -> Result<()> {
+        let existing = self.table_columns(table_name).await?;
+        let mut missing = Vec::new();
+        for column in &self.columns {
+            match existing.iter().find(|info| info.name == column.name) {
+                None => missing.push(column.name),
+                Some(info) if info.udt_name != column.udt_name() => {
+                    return Err(AppError::configuration(format!(
+                        "Cannot append to {}: column {} is {}, expected {}",
+                        table_name,
+                        column.name,
+                        info.udt_name,
+                        column.sql_type()
+                    )));
+                }
+                Some(_) => {}
+            }
+        }
+        if !missing.is_empty() {
+            return Err(AppError::configuration(format!(
+                "Cannot append to {}: it lacks the columns {} (choose a smaller --columns set)",
+                table_name,
+                missing.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ColumnSet;
+
+    fn schema(columns: ColumnSet) -> SnapshotSchema {
+        let options = DatabaseOptions { columns, ..DatabaseOptions::default() };
+        SnapshotSchema::new(&options, "repos_20240101000000").unwrap()
+    }
+
+    #[test]
+    fn test_minimal_schema_sql() {
+        let sql = schema(ColumnSet::Minimal).to_sql();
+        assert!(sql.starts_with("CREATE TABLE IF NOT EXISTS \"public\".\"repos_20240101000000\" (\n    id SERIAL PRIMARY KEY,"));
+        assert!(sql.contains("stargazers_count BIGINT NOT NULL DEFAULT 0"));
+        assert!(!sql.contains("description"));
+        assert!(sql.contains("\"idx_repos_20240101000000_stargazers\" ON \"public\".\"repos_20240101000000\"(stargazers_count DESC);"));
+        assert!(!sql.contains("_search"));
+    }
+
+    #[test]
+    fn test_schema_json_describes_columns_and_indexes() {
+        let json = schema(ColumnSet::Extended).to_json();
+        let columns = json["columns"].as_array().unwrap();
+        assert_eq!(columns.len(), ColumnSet::Extended.columns().len());
+        let login = columns.iter().find(|column| column["name"] == "owner_login").unwrap();
+        assert_eq!(login["type"], "VARCHAR(255)");
+        assert_eq!(login["nullable"], false);
+        assert_eq!(login["source_field"], "owner.login");
+        let head_sha = columns.iter().find(|column| column["name"] == "head_sha").unwrap();
+        assert_eq!(head_sha["nullable"], true);
+        assert!(head_sha["source_field"].is_null());
+
+        let indexes = json["indexes"].as_array().unwrap();
+        let search = indexes.iter().find(|index| index["name"] == "idx_repos_20240101000000_search").unwrap();
+        assert!(search["definition"].as_str().unwrap().contains("USING GIN"));
+    }
+
+    #[test]
+    fn test_schema_storage_options() {
+        let storage = TableStorage {
+            unlogged: true,
+            parameters: vec![
+                ("fillfactor".to_string(), "70".to_string()),
+                TableStorage::parse_parameter("autovacuum_enabled=FALSE").unwrap(),
+            ],
+        };
+        let options = DatabaseOptions { storage, ..DatabaseOptions::default() };
+        let sql = SnapshotSchema::new(&options, "repos_1").unwrap().create_table_sql();
+        assert!(sql.starts_with("CREATE UNLOGGED TABLE IF NOT EXISTS \"public\".\"repos_1\" ("));
+        assert!(sql.ends_with("\n) WITH (fillfactor = 70, autovacuum_enabled = false)"));
+        assert!(!schema(ColumnSet::Minimal).create_table_sql().contains("WITH"));
+
+        assert_eq!(
+            TableStorage::parse_parameter("toast.autovacuum_vacuum_scale_factor = 0.05").unwrap(),
+            ("toast.autovacuum_vacuum_scale_factor".to_string(), "0.05".to_string())
+        );
+        assert!(TableStorage::parse_parameter("autovacuum_enabled").is_err());
+        assert!(TableStorage::parse_parameter("fillfactor=50").is_err());
+        assert!(TableStorage::parse_parameter("autovacuum_enabled=off); DROP TABLE x; --").is_err());
+    }
+
+    #[test]
+    fn test_schema_index_sets() {
+        let indexes = |indexes: IndexSet| {
+            let options = DatabaseOptions { indexes, ..DatabaseOptions::default() };
+            SnapshotSchema::new(&options, "repos_1")
+                .unwrap()
+                .indexes()
+                .into_iter()
+                .map(|index| index.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(indexes(IndexSet::Full).len(), 7);
+        assert_eq!(indexes(IndexSet::Minimal), vec!["idx_repos_1_full_name", "idx_repos_1_stargazers"]);
+        assert!(indexes(IndexSet::None).is_empty());
+
+        let options = DatabaseOptions { indexes: IndexSet::None, ..DatabaseOptions::default() };
+        assert_eq!(SnapshotSchema::new(&options, "repos_1").unwrap().statements().len(), 1);
+        assert_eq!("minimal".parse::<IndexSet>().unwrap(), IndexSet::Minimal);
+        assert!("some".parse::<IndexSet>().is_err());
+    }
+
+    #[test]
+    fn test_schema_rejects_invalid_names() {
+        let options = DatabaseOptions::default();
+        assert!(SnapshotSchema::new(&options, "repos; DROP TABLE query_history").is_err());
+        "yaml".parse::<SchemaFormat>().unwrap_err();
+        assert_eq!("json".parse::<SchemaFormat>().unwrap(), SchemaFormat::Json);
+    }
+}
@@ -0,0 +1,120 @@
+//! Offline full-text search over stored snapshots
+//!
+//! Repository names and descriptions are indexed with a GIN expression index
+//! on their `english` tsvector, so `search-local` can answer keyword queries
+//! from earlier crawls while the GitHub API is rate-limited.
+
+use sqlx::FromRow;
+
+use super::schema::index_name_prefix;
+use crate::{DatabaseManager, Result};
+
+/// Document indexed for full-text search; queries must repeat it exactly to use the index
+pub(crate) const SEARCH_DOCUMENT: &str =
+    "to_tsvector('english', coalesce(name, '') || ' ' || coalesce(description, ''))";
+
+/// A stored repository matching a local search
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct LocalSearchHit {
+    pub full_name: String,
+    pub description: Option<String>,
+    pub language: Option<String>,
+    pub stargazers_count: i64,
+    pub html_url: String,
+    /// `ts_rank` of the match; higher is more relevant
+    pub rank: f32,
+}
+
+impl DatabaseManager {
+    /// Statement creating the full-text index of a snapshot table
+    pub(crate) fn search_index_sql(table_name: &str, table: &str) -> String {
+        format!(
+            "CREATE INDEX IF NOT EXISTS \"{}search\" ON {} USING GIN ({})",
+            index_name_prefix(table_name), table, SEARCH_DOCUMENT
+        )
+    }
+
+    /// Add the full-text index to repository snapshots created before it was part of the schema
+    ///
+    /// Run by the `migrate` subcommand rather than by `search-local`, which
+    /// only reads. Snapshots without `name` and `description` columns are
+    /// skipped. Returns the tables that were indexed.
+    pub async fn ensure_search_indexes(&self) -> Result<Vec<String>> {
+        let sql = format!(
+            r#"
+            SELECT s.table_name
+            FROM {} s
+            WHERE s.kind = 'repositories'
+              AND (SELECT COUNT(*) FROM information_schema.columns c
+                   WHERE c.table_schema = $1 AND c.table_name = s.table_name
+                     AND c.column_name IN ('name', 'description')) = 2
+            ORDER BY s.table_name
+            "#,
+            self.shared_table("snapshot_tables")
+        );
+        let tables: Vec<String> = sqlx::query_scalar(&sql).bind(&self.schema).fetch_all(&self.pool).await?;
+
+        let mut indexed = Vec::new();
+        for table_name in tables {
+            let index = format!("{}search", index_name_prefix(&table_name));
+            let exists: bool = sqlx::query_scalar("SELECT to_regclass(quote_ident($1) || '.' || quote_ident($2)) IS NOT NULL")
+                .bind(&self.schema)
+                .bind(&index)
+                .fetch_one(&self.pool)
+                .await?;
+            if exists {
+                continue;
+            }
+            let table = self.qualified_table(&table_name)?;
+            sqlx::query(&Self::search_index_sql(&table_name, &table)).execute(&self.pool).await?;
+            indexed.push(table_name);
+        }
+        Ok(indexed)
+    }
+
+    /// Search stored names and descriptions with web-search syntax
+    ///
+    /// Supports quoted phrases, `or`, and `-term` exclusions, and returns the
+    /// best matches first, breaking ties by stars.
+    pub async fn search_local(&self, table_name: &str, query: &str, limit: i64) -> Result<Vec<LocalSearchHit>> {
+        Self::validate_table_name(table_name)?;
+        let sql = format!(
+            r#"
+            SELECT full_name, description, language, stargazers_count, html_url,
+                   ts_rank({document}, websearch_to_tsquery('english', $1)) AS rank
+            FROM {table}
+            WHERE {document} @@ websearch_to_tsquery('english', $1)
+            ORDER BY rank DESC, stargazers_count DESC
+            LIMIT $2
+            "#,
+            document = SEARCH_DOCUMENT,
+            table = self.qualified_table(table_name)?
+        );
+
+        let hits = sqlx::query_as(&sql)
+            .bind(query)
+            .bind(limit)
+            .fetch_all(&self.read_pool)
+            .await?;
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_index_sql_uses_search_document() {
+        let sql = DatabaseManager::search_index_sql("repos_20240101000000", "\"public\".\"repos_20240101000000\"");
+        assert!(sql.contains("\"idx_repos_20240101000000_search\""));
+        assert!(sql.contains(&format!("USING GIN ({})", SEARCH_DOCUMENT)));
+
+        // Long names sharing a prefix get distinct names within the 63-character limit
+        let first = format!("repos_{}_a", "x".repeat(55));
+        let second = format!("repos_{}_b", "x".repeat(55));
+        let name = |table: &str| format!("{}search", index_name_prefix(table));
+        assert!(name(&first).len() <= 63);
+        assert_ne!(name(&first), name(&second));
+    }
+}
@@ -0,0 +1,56 @@
+//! Time series for Grafana
+//!
+//! With `--timeseries` a run appends one row to `run_metrics` and one row
+//! per stored repository to `star_counts`, stamped with the run's
+//! `executed_at`. Unlike snapshot tables they are never dropped with a
+//! snapshot, so dashboards built on the `grafana_*` views keep their history.
+
+use chrono::{DateTime, Utc};
+
+use crate::{DatabaseManager, QueryMetadata, Result};
+
+impl DatabaseManager {
+    /// Append a run's outcome and request counts to `run_metrics`
+    pub async fn record_run_metrics(&self, metadata: &QueryMetadata) -> Result<()> {
+        let sql = format!(
+            r#"
+            INSERT INTO {} (query_id, time, search_query, success, result_count, duration_ms,
+                            pages_fetched, api_requests, retries, rate_limit_sleep_ms)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (query_id) DO NOTHING
+            "#,
+            self.shared_table("run_metrics")
+        );
+        let metrics = metadata.http_metrics.as_ref();
+        sqlx::query(&sql)
+            .bind(metadata.id)
+            .bind(metadata.executed_at)
+            .bind(&metadata.search_query)
+            .bind(metadata.success)
+            .bind(metadata.result_count)
+            .bind(metadata.duration_ms)
+            .bind(metadata.pages_fetched)
+            .bind(metrics.map(|m| m.requests as i64))
+            .bind(metrics.map(|m| m.retries as i64))
+            .bind(metrics.map(|m| m.rate_limit_sleep_ms as i64))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Append the star count of every repository in a snapshot to `star_counts`
+    ///
+    /// Returns the rows written; repositories already recorded at `time` are skipped.
+    pub async fn record_star_counts(&self, table_name: &str, time: DateTime<Utc>) -> Result<u64> {
+        let sql = format!(
+            r#"
+            INSERT INTO {} (github_id, time, full_name, stargazers_count)
+            SELECT github_id, $1, full_name, stargazers_count FROM {}
+            ON CONFLICT (github_id, time) DO NOTHING
+            "#,
+            self.shared_table("star_counts"),
+            self.qualified_table(table_name)?
+        );
+        Ok(sqlx::query(&sql).bind(time).execute(&self.pool).await?.rows_affected())
+    }
+}
@@ -0,0 +1,54 @@
+//! Topic co-occurrence counts of a snapshot
+//!
+//! Counts come straight from the `topics` array column: one `unnest` for
+//! how many repositories carry each topic, and a self-join of the unnested
+//! topics for how many carry each pair.
+
+use crate::{AppError, DatabaseManager, Result, TopicGraph};
+
+impl DatabaseManager {
+    /// Topics and topic pairs carried by at least `min_weight` repositories
+    pub async fn topic_graph(&self, table_name: &str, min_weight: i64) -> Result<TopicGraph> {
+        let columns = self.table_columns(table_name).await?;
+        if !columns.iter().any(|column| column.name == "topics") {
+            return Err(AppError::configuration(format!(
+                "analyze topics needs the topics column, which {} does not have",
+                table_name
+            )));
+        }
+        let table = self.qualified_table(table_name)?;
+
+        let nodes_sql = format!(
+            r#"
+            SELECT topic, COUNT(DISTINCT t.id)
+            FROM {} t, unnest(t.topics) AS topic
+            GROUP BY topic
+            HAVING COUNT(DISTINCT t.id) >= $1
+            ORDER BY 2 DESC, topic
+            "#,
+            table
+        );
+        let nodes: Vec<(String, i64)> = sqlx::query_as(&nodes_sql)
+            .bind(min_weight)
+            .fetch_all(&self.read_pool)
+            .await?;
+
+        let edges_sql = format!(
+            r#"
+            SELECT a, b, COUNT(DISTINCT t.id)
+            FROM {} t, unnest(t.topics) AS a, unnest(t.topics) AS b
+            WHERE a < b
+            GROUP BY a, b
+            HAVING COUNT(DISTINCT t.id) >= $1
+            ORDER BY 3 DESC, a, b
+            "#,
+            table
+        );
+        let edges: Vec<(String, String, i64)> = sqlx::query_as(&edges_sql)
+            .bind(min_weight)
+            .fetch_all(&self.read_pool)
+            .await?;
+
+        Ok(TopicGraph { nodes, edges })
+    }
+}
@@ -0,0 +1,175 @@
+//! Trend analysis across snapshots of the same query
+//!
+//! Every successful repository run records the previous successful run of
+//! the same search in `query_history.previous_query_id`, so the snapshots of
+//! a query form a chain that `trends` walks and diffs pairwise.
+
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+
+use crate::{DatabaseManager, QueryMetadata, Result};
+
+/// Changes between two snapshots of the same query
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    /// Rows in the older snapshot
+    pub previous_count: i64,
+    /// Rows in the newer snapshot
+    pub current_count: i64,
+    /// Repositories only in the newer snapshot
+    pub new_entrants: i64,
+    /// Repositories only in the older snapshot
+    pub dropped: i64,
+    /// Net star change of repositories present in both snapshots
+    pub star_growth: i64,
+}
+
+/// Diff between two consecutive snapshots that still exist
+#[derive(Debug, Clone)]
+pub struct TrendInterval {
+    pub from: QueryMetadata,
+    pub to: QueryMetadata,
+    pub diff: SnapshotDiff,
+}
+
+impl DatabaseManager {
+    /// Latest successful repository snapshot of the same query, other than `metadata` itself
+    ///
+    /// Queries rendered from a template match on the template, so
+    /// `created:>{{today-30d}}` links up although its dates move.
+    pub async fn find_previous_snapshot(&self, metadata: &QueryMetadata) -> Result<Option<uuid::Uuid>> {
+        let sql = format!(
+            r#"
+            SELECT id FROM {}
+            WHERE COALESCE(query_template, search_query) = $1 AND success = true AND id <> $2
+            AND starts_with(table_name, $3)
+            ORDER BY executed_at DESC
+            LIMIT 1
+            "#,
+            self.shared_table("query_history")
+        );
+
+        let id = sqlx::query_scalar(&sql)
+            .bind(metadata.query_template.as_deref().unwrap_or(&metadata.search_query))
+            .bind(metadata.id)
+            .bind(self.repository_table_prefix())
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(id)
+    }
+
+    /// Start of the latest successful run of a saved query, or of a query template
+    pub async fn last_successful_run(
+        &self,
+        saved_query: Option<&str>,
+        template: &str,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let sql = format!(
+            r#"
+            SELECT MAX(executed_at) FROM {}
+            WHERE success = true
+            AND CASE WHEN $1::TEXT IS NULL THEN COALESCE(query_template, search_query) = $2 ELSE saved_query = $1 END
+            "#,
+            self.shared_table("query_history")
+        );
+
+        let last_run = sqlx::query_scalar(&sql)
+            .bind(saved_query)
+            .bind(template)
+            .fetch_one(&self.read_pool)
+            .await?;
+        Ok(last_run)
+    }
+
+    /// Snapshots linked from `query_id` through `previous_query_id`, oldest first
+    pub async fn get_snapshot_chain(&self, query_id: uuid::Uuid) -> Result<Vec<QueryMetadata>> {
+        let table = self.shared_table("query_history");
+        let sql = format!(
+            r#"
+            WITH RECURSIVE chain AS (
+                SELECT * FROM {table} WHERE id = $1
+                UNION ALL
+                SELECT q.* FROM {table} q JOIN chain c ON q.id = c.previous_query_id
+            )
+            SELECT * FROM chain ORDER BY executed_at
+            "#,
+            table = table
+        );
+
+        let rows = sqlx::query(&sql).bind(query_id).fetch_all(&self.read_pool).await?;
+        Ok(rows.iter().map(Self::query_metadata_from_row).collect())
+    }
+
+    /// Successful repository snapshots of a search query, oldest first
+    pub async fn get_query_snapshots(&self, search_query: &str) -> Result<Vec<QueryMetadata>> {
+        let sql = format!(
+            r#"
+            SELECT * FROM {}
+            WHERE search_query = $1 AND success = true AND starts_with(table_name, $2)
+            ORDER BY executed_at
+            "#,
+            self.shared_table("query_history")
+        );
+
+        let rows = sqlx::query(&sql)
+            .bind(search_query)
+            .bind(self.repository_table_prefix())
+            .fetch_all(&self.read_pool)
+            .await?;
+        Ok(rows.iter().map(Self::query_metadata_from_row).collect())
+    }
+
+    /// Compare two repository snapshots by `github_id`
+    pub async fn compare_snapshots(&self, previous_table: &str, current_table: &str) -> Result<SnapshotDiff> {
+        Self::validate_table_name(previous_table)?;
+        Self::validate_table_name(current_table)?;
+        let previous = self.qualified_table(previous_table)?;
+        let current = self.qualified_table(current_table)?;
+
+        let sql = format!(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM {previous}) AS previous_count,
+                (SELECT COUNT(*) FROM {current}) AS current_count,
+                (SELECT COUNT(*) FROM {current} c
+                    WHERE NOT EXISTS (SELECT 1 FROM {previous} p WHERE p.github_id = c.github_id)) AS new_entrants,
+                (SELECT COUNT(*) FROM {previous} p
+                    WHERE NOT EXISTS (SELECT 1 FROM {current} c WHERE c.github_id = p.github_id)) AS dropped,
+                (SELECT COALESCE(SUM(c.stargazers_count - p.stargazers_count), 0)::BIGINT
+                    FROM {current} c JOIN {previous} p ON p.github_id = c.github_id) AS star_growth
+            "#,
+            previous = previous,
+            current = current
+        );
+
+        let row = sqlx::query(&sql).fetch_one(&self.read_pool).await?;
+        Ok(SnapshotDiff {
+            previous_count: row.get("previous_count"),
+            current_count: row.get("current_count"),
+            new_entrants: row.get("new_entrants"),
+            dropped: row.get("dropped"),
+            star_growth: row.get("star_growth"),
+        })
+    }
+
+    /// Diff consecutive snapshots, skipping any whose table has been dropped
+    pub async fn get_trends(&self, snapshots: &[QueryMetadata]) -> Result<Vec<TrendInterval>> {
+        let mut existing = Vec::with_capacity(snapshots.len());
+        for snapshot in snapshots {
+            if self.table_exists(&snapshot.table_name).await? {
+                existing.push(snapshot);
+            }
+        }
+
+        let mut intervals = Vec::with_capacity(existing.len().saturating_sub(1));
+        for pair in existing.windows(2) {
+            let diff = self.compare_snapshots(&pair[0].table_name, &pair[1].table_name).await?;
+            intervals.push(TrendInterval {
+                from: pair[0].clone(),
+                to: pair[1].clone(),
+                diff,
+            });
+        }
+        Ok(intervals)
+    }
+}
@@ -0,0 +1,402 @@
+//! # Enrichment Module
+//!
+//! Optional post-insert passes that call additional GitHub endpoints for
+//! each stored repository and write the extra data back to the database.
+//!
+//! Enrichments are selected with `--enrich <kind>` and run after the
+//! search results have been stored, so a failing enrichment never loses
+//! the primary snapshot.
+
+use futures::stream::{self, StreamExt};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{
+    AppError, CategoryRules, Classification, DatabaseManager, GitHubApi, GitHubClient, Repository, RepositoryCi, RepositorySecurity, Result,
+    ScorecardClient, SeverityCounts,
+};
+
+/// GitHub stops listing stargazers after 400 pages of 100
+const MAX_STARGAZER_PAGES: u32 = 400;
+
+/// Upper bound on Dependabot alert pages fetched per repository
+const MAX_ALERT_PAGES: u32 = 10;
+
+/// Repository detail lookups `--enrich details` keeps in flight
+pub const DEFAULT_DETAILS_CONCURRENCY: usize = 4;
+
+/// Available enrichment passes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnrichmentKind {
+    /// Fetch the complete topic list for each repository
+    Topics,
+    /// Page through stargazers to record `starred_at` timestamps
+    StarHistory,
+    /// Detect GitHub Actions workflows and the latest run status
+    Ci,
+    /// Roll up security advisories and Dependabot alerts by severity
+    Security,
+    /// Look up the OSSF Scorecard for each repository
+    Scorecard,
+    /// Capture the default branch head SHA and commit count
+    Commits,
+    /// Backfill `subscribers_count` and `network_count` from `/repos/{owner}/{repo}`
+    Details,
+    /// Classify into an ecosystem (`web-framework`, `cli`, ...) with [`CategoryRules`]
+    Category,
+}
+
+impl EnrichmentKind {
+    /// All supported enrichment kinds, in execution order
+    pub const ALL: &'static [EnrichmentKind] = &[
+        EnrichmentKind::Topics,
+        EnrichmentKind::StarHistory,
+        EnrichmentKind::Ci,
+        EnrichmentKind::Security,
+        EnrichmentKind::Scorecard,
+        EnrichmentKind::Commits,
+        EnrichmentKind::Details,
+        EnrichmentKind::Category,
+    ];
+
+    /// Name used on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EnrichmentKind::Topics => "topics",
+            EnrichmentKind::StarHistory => "star-history",
+            EnrichmentKind::Ci => "ci",
+            EnrichmentKind::Security => "security",
+            EnrichmentKind::Scorecard => "scorecard",
+            EnrichmentKind::Commits => "commits",
+            EnrichmentKind::Details => "details",
+            EnrichmentKind::Category => "category",
+        }
+    }
+
+    /// Snapshot table columns the pass writes to (`details` and `category` add their own)
+    pub fn snapshot_columns(&self) -> &'static [&'static str] {
+        match self {
+            EnrichmentKind::Topics => &["topics"],
+            EnrichmentKind::Commits => &["head_sha", "commit_count"],
+            _ => &[],
+        }
+    }
+}
+
+impl fmt::Display for EnrichmentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EnrichmentKind {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        EnrichmentKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.as_str() == value)
+            .ok_or_else(|| {
+                let supported: Vec<&str> = EnrichmentKind::ALL.iter().map(|k| k.as_str()).collect();
+                AppError::configuration(format!(
+                    "Unknown enrichment '{}' (supported: {})",
+                    value,
+                    supported.join(", ")
+                ))
+            })
+    }
+}
+
+/// Outcome of an enrichment run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnrichmentSummary {
+    /// Number of repositories successfully enriched
+    pub enriched: i64,
+    /// Number of repositories with nothing to record (e.g., no scorecard)
+    pub skipped: i64,
+    /// Number of repositories whose enrichment failed
+    pub failed: i64,
+}
+
+/// Result of enriching a single repository
+enum Enriched {
+    Stored,
+    Skipped,
+}
+
+/// Run the requested enrichment passes over stored repositories
+///
+/// `category` classifies with `rules`. Per-repository failures are counted and skipped. Authentication, rate
+/// limit, and open circuit breaker errors abort the pass since every
+/// following call would fail the same way.
+pub async fn enrich_repositories(
+    client: &GitHubClient,
+    db: &DatabaseManager,
+    table_name: &str,
+    repositories: &[Repository],
+    kinds: &[EnrichmentKind],
+    rules: &CategoryRules,
+) -> Result<EnrichmentSummary> {
+    let mut summary = EnrichmentSummary::default();
+
+    let scorecard_client = if kinds.contains(&EnrichmentKind::Scorecard) {
+        Some(ScorecardClient::new_with_config(client.http_config())?)
+    } else {
+        None
+    };
+
+    for kind in kinds {
+        if *kind == EnrichmentKind::Details {
+            let details = enrich_details(client, db, table_name, DEFAULT_DETAILS_CONCURRENCY).await?;
+            summary.enriched += details.enriched;
+            summary.skipped += details.skipped;
+            summary.failed += details.failed;
+            continue;
+        }
+        if *kind == EnrichmentKind::Category {
+            db.ensure_category_column(table_name).await?;
+        }
+
+        for repo in repositories {
+            let outcome = match (kind, &scorecard_client) {
+                (EnrichmentKind::Topics, _) => enrich_topics(client, db, table_name, repo).await,
+                (EnrichmentKind::StarHistory, _) => enrich_star_history(client, db, repo).await,
+                (EnrichmentKind::Ci, _) => enrich_ci(client, db, repo).await,
+                (EnrichmentKind::Security, _) => enrich_security(client, db, repo).await,
+                (EnrichmentKind::Scorecard, Some(scorecards)) => {
+                    enrich_scorecard(scorecards, db, repo).await
+                }
+                (EnrichmentKind::Scorecard, None) => Ok(Enriched::Skipped),
+                (EnrichmentKind::Commits, _) => enrich_commits(client, db, table_name, repo).await,
+                (EnrichmentKind::Details, _) => unreachable!("details are enriched per table"),
+                (EnrichmentKind::Category, _) => enrich_category(client, db, table_name, rules, repo).await,
+            };
+
+            match outcome {
+                Ok(Enriched::Stored) => summary.enriched += 1,
+                Ok(Enriched::Skipped) => summary.skipped += 1,
+                Err(error @ (AppError::Authentication { .. } | AppError::RateLimit { .. } | AppError::CircuitOpen { .. })) => {
+                    return Err(error);
+                }
+                Err(_) => summary.failed += 1,
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Replace the stored topic list with the full list from the topics endpoint
+async fn enrich_topics(
+    client: &GitHubClient,
+    db: &DatabaseManager,
+    table_name: &str,
+    repo: &Repository,
+) -> Result<Enriched> {
+    let topics = client.get_repository_topics(&repo.full_name).await?;
+    db.update_repository_topics(table_name, repo.id, &topics).await?;
+    Ok(Enriched::Stored)
+}
+
+/// Record every stargazer's `starred_at` timestamp in repo_stars_timeline
+async fn enrich_star_history(
+    client: &GitHubClient,
+    db: &DatabaseManager,
+    repo: &Repository,
+) -> Result<Enriched> {
+    let per_page = 100;
+
+    for page in 1..=MAX_STARGAZER_PAGES {
+        let stargazers = client.get_stargazers(&repo.full_name, per_page, page).await?;
+        db.insert_stargazers(repo.id, &repo.full_name, &stargazers).await?;
+
+        if stargazers.len() < per_page as usize {
+            break;
+        }
+    }
+
+    Ok(Enriched::Stored)
+}
+
+/// Record workflow presence and the latest default-branch run in repo_ci
+async fn enrich_ci(client: &GitHubClient, db: &DatabaseManager, repo: &Repository) -> Result<Enriched> {
+    let workflows = client.get_workflows(&repo.full_name).await?;
+    let latest_run = if workflows.is_empty() {
+        None
+    } else {
+        client
+            .get_latest_workflow_run(&repo.full_name, &repo.default_branch)
+            .await?
+    };
+
+    let ci = RepositoryCi {
+        workflows,
+        latest_run,
+    };
+    db.upsert_repository_ci(repo.id, &repo.full_name, &ci).await?;
+    Ok(Enriched::Stored)
+}
+
+/// Record advisory and Dependabot alert severity rollups in repo_security
+async fn enrich_security(
+    client: &GitHubClient,
+    db: &DatabaseManager,
+    repo: &Repository,
+) -> Result<Enriched> {
+    let mut security = RepositorySecurity::default();
+
+    for advisory in client.get_security_advisories(&repo.full_name).await? {
+        security.advisories.record(advisory.severity.as_deref());
+    }
+
+    let mut alert_counts = SeverityCounts::default();
+    let mut alerts_available = false;
+    for page in 1..=MAX_ALERT_PAGES {
+        let Some(alerts) = client.get_dependabot_alerts(&repo.full_name, page).await? else {
+            break;
+        };
+        alerts_available = true;
+
+        for alert in &alerts {
+            alert_counts.record(alert.security_advisory.severity.as_deref());
+        }
+        if alerts.len() < 100 {
+            break;
+        }
+    }
+    if alerts_available {
+        security.dependabot_alerts = Some(alert_counts);
+    }
+
+    db.upsert_repository_security(repo.id, &repo.full_name, &security).await?;
+    Ok(Enriched::Stored)
+}
+
+/// Record the OSSF Scorecard result in repo_scorecards, skipping unscored repos
+async fn enrich_scorecard(
+    scorecards: &ScorecardClient,
+    db: &DatabaseManager,
+    repo: &Repository,
+) -> Result<Enriched> {
+    match scorecards.get_scorecard(&repo.full_name).await? {
+        Some(scorecard) => {
+            db.upsert_repository_scorecard(repo.id, &repo.full_name, &scorecard).await?;
+            Ok(Enriched::Stored)
+        }
+        None => Ok(Enriched::Skipped),
+    }
+}
+
+/// Record the default branch head SHA and commit count on the snapshot row
+async fn enrich_commits(
+    client: &GitHubClient,
+    db: &DatabaseManager,
+    table_name: &str,
+    repo: &Repository,
+) -> Result<Enriched> {
+    match client.get_branch_head(&repo.full_name, &repo.default_branch).await? {
+        Some(head) => {
+            db.update_repository_head(table_name, repo.id, &head.sha, head.commit_count)
+                .await?;
+            Ok(Enriched::Stored)
+        }
+        None => Ok(Enriched::Skipped),
+    }
+}
+
+/// Record the first matching rule's category on the snapshot row
+///
+/// The root directory is only listed when a rule that looks at files
+/// decides the outcome; unclassified repositories are skipped.
+async fn enrich_category(
+    client: &dyn GitHubApi,
+    db: &DatabaseManager,
+    table_name: &str,
+    rules: &CategoryRules,
+    repo: &Repository,
+) -> Result<Enriched> {
+    let files;
+    let mut classification = rules.classify(repo, None);
+    if classification == Classification::NeedsFiles {
+        files = client.get_root_files(&repo.full_name).await?.unwrap_or_default();
+        classification = rules.classify(repo, Some(&files));
+    }
+    match classification {
+        Classification::Category(category) => {
+            db.update_repository_category(table_name, repo.id, Some(category)).await?;
+            Ok(Enriched::Stored)
+        }
+        _ => Ok(Enriched::Skipped),
+    }
+}
+
+/// Fetch `/repos/{owner}/{repo}` for every row of a snapshot still missing its details
+///
+/// Up to `concurrency` lookups run at once. Each row is marked as soon as
+/// its lookup finishes, so an interrupted backfill resumes where it stopped
+/// when run again. Repositories that have since disappeared are marked and
+/// counted as skipped.
+pub async fn enrich_details(
+    client: &dyn GitHubApi,
+    db: &DatabaseManager,
+    table_name: &str,
+    concurrency: usize,
+) -> Result<EnrichmentSummary> {
+    db.ensure_details_columns(table_name).await?;
+    let pending = db.repositories_missing_details(table_name).await?;
+
+    let mut lookups = stream::iter(pending)
+        .map(|(github_id, full_name)| async move {
+            let details = client.get_repository_details(&full_name).await?;
+            db.update_repository_details(table_name, github_id, details.as_ref()).await?;
+            Ok(if details.is_some() { Enriched::Stored } else { Enriched::Skipped })
+        })
+        .buffer_unordered(concurrency.max(1));
+
+    let mut summary = EnrichmentSummary::default();
+    while let Some(outcome) = lookups.next().await {
+        match outcome {
+            Ok(Enriched::Stored) => summary.enriched += 1,
+            Ok(Enriched::Skipped) => summary.skipped += 1,
+            Err(error @ (AppError::Authentication { .. } | AppError::RateLimit { .. } | AppError::CircuitOpen { .. })) => {
+                return Err(error);
+            }
+            Err(_) => summary.failed += 1,
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enrichment_kind_round_trip() {
+        for kind in EnrichmentKind::ALL {
+            assert_eq!(kind.as_str().parse::<EnrichmentKind>().unwrap(), *kind);
+        }
+    }
+
+    #[test]
+    fn test_severity_counts_record() {
+        let mut counts = SeverityCounts::default();
+        for severity in [Some("critical"), Some("HIGH"), Some("moderate"), Some("low"), Some("unknown"), None] {
+            counts.record(severity);
+        }
+
+        assert_eq!(counts.critical, 1);
+        assert_eq!(counts.high, 1);
+        assert_eq!(counts.medium, 1);
+        assert_eq!(counts.low, 1);
+        assert_eq!(counts.total(), 4);
+    }
+
+    #[test]
+    fn test_enrichment_kind_unknown() {
+        let error = "stars".parse::<EnrichmentKind>().unwrap_err();
+        assert!(error.to_string().contains("Unknown enrichment 'stars'"));
+    }
+}
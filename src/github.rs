@@ -1,18 +1,166 @@
-use crate::{AppError, Result, SearchResponse};
+use crate::{
+    ApiRecording, AppError, CircuitBreaker, FaultInjector, Redacted, RecordedResponse, RecordingMode, DependabotAlert, HttpMetrics, Middleware, MiddlewareAction,
+    RetryOnRateLimit, HttpConfig, RepositoryDetails, ResponseCache, Result, SearchResponse, SearchType, SecurityAdvisory,
+    Stargazer, TopicSearchResponse, Workflow, WorkflowRun,
+};
 
+mod api;
+mod builder;
 #[cfg(test)]
 mod tests;
-use reqwest::{Client, StatusCode};
+
+pub use api::GitHubApi;
+pub use builder::{GitHubClientBuilder, DEFAULT_GITHUB_API_URL};
+use reqwest::header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH};
+use reqwest::{Client, ResponseBuilderExt, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::time::sleep;
+use tokio::time::{sleep, Instant};
+
+/// Sort field used for repository searches
+pub const REPOSITORY_SEARCH_SORT: &str = "updated";
+
+/// Sort direction used for repository searches
+pub const REPOSITORY_SEARCH_ORDER: &str = "desc";
 
 /// GitHub API client with authentication and rate limiting
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct GitHubClient {
     client: Client,
     token: String,
     base_url: String,
+    http_config: HttpConfig,
+    usage: Arc<Mutex<ApiUsage>>,
+    rate_limit_log: Arc<Mutex<Vec<RateLimitSample>>>,
+    rate_limit: RateLimitConfig,
+    /// Earliest start of the next paced request per rate limit resource
+    pacing_slots: Arc<Mutex<BTreeMap<String, Instant>>>,
+    circuit_breaker: CircuitBreaker,
+    /// Hooks run around every request, starting with [`BearerAuth`]
+    middleware: Vec<Arc<dyn Middleware>>,
+    /// Ask searches for matched fragments with the text-match media type
+    text_match: bool,
+    /// Search responses reused within their TTL
+    cache: Option<ResponseCache>,
+    /// Responses written to or answered from disk (`--record`, `--replay`)
+    recording: Option<ApiRecording>,
+    /// Synthetic failures for hardening tests (`--chaos`)
+    faults: Option<FaultInjector>,
+}
+
+/// Leaves out the token and connection internals
+impl fmt::Debug for GitHubClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GitHubClient")
+            .field("base_url", &Redacted(&self.base_url))
+            .field("anonymous", &self.is_anonymous())
+            .field("http_config", &Redacted(&self.http_config))
+            .field("rate_limit", &self.rate_limit)
+            .field("circuit_breaker", self.circuit_breaker.config())
+            .field("text_match", &self.text_match)
+            .field("cache", &self.cache)
+            .field("recording", &self.recording)
+            .field("faults", &self.faults)
+            .field("middleware", &self.middleware)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Requests sent by a client and the rate limit quota GitHub reported back
+///
+/// Clones of a client share the same counters.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ApiUsage {
+    /// Total HTTP requests sent
+    pub requests: u64,
+    /// Quota per rate limit resource (`core`, `search`, ...)
+    pub rate_limits: BTreeMap<String, ResourceUsage>,
+    /// Times the circuit breaker opened after consecutive failures
+    pub circuit_breaker_trips: u64,
+    /// Requests refused without contacting GitHub while the breaker was open
+    pub circuit_breaker_rejections: u64,
+    /// Requests sent again because a middleware asked for a retry, e.g. after a 403 or 429
+    pub retries: u64,
+    /// Time slept before those retries
+    pub retry_sleep_ms: u64,
+    /// Response body bytes as transferred, compressed where GitHub compressed them
+    pub bytes_downloaded: u64,
+    /// Response body bytes after decompression
+    pub bytes_decompressed: u64,
+    /// Search pages served from the response cache instead of GitHub
+    pub cache_hits: u64,
+    /// Time until the response headers arrived, per request
+    #[serde(skip)]
+    pub latencies_ms: Vec<u64>,
+}
+
+impl ApiUsage {
+    /// Summary recorded in `query_history.http_metrics`
+    pub fn http_metrics(&self) -> HttpMetrics {
+        let mut latencies = self.latencies_ms.clone();
+        latencies.sort_unstable();
+        let paced_ms: u64 = self.rate_limits.values().map(|resource| resource.paced_ms).sum();
+        HttpMetrics {
+            requests: self.requests,
+            bytes_downloaded: self.bytes_downloaded,
+            bytes_decompressed: self.bytes_decompressed,
+            retries: self.retries,
+            rate_limit_sleep_ms: self.retry_sleep_ms + paced_ms,
+            latency_p50_ms: percentile(&latencies, 50),
+            latency_p95_ms: percentile(&latencies, 95),
+            cache_hits: self.cache_hits,
+            request_id: None,
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], percent: usize) -> Option<u64> {
+    let rank = (sorted.len() * percent).div_ceil(100);
+    sorted.get(rank.saturating_sub(1)).copied()
+}
+
+/// Requests and last observed quota for one rate limit resource
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ResourceUsage {
+    /// Requests that counted against this resource
+    pub requests: u64,
+    /// Quota limit from the most recent response
+    pub limit: Option<u32>,
+    /// Remaining quota from the most recent response
+    pub remaining: Option<u32>,
+    /// When the quota window resets
+    pub reset_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Time spent waiting before requests because of [`PacingPolicy`]
+    pub paced_ms: u64,
+}
+
+/// Rate limit headers of a single response, as stored in `rate_limit_log`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RateLimitSample {
+    /// When the response was received
+    pub observed_at: chrono::DateTime<chrono::Utc>,
+    /// Request path, without the query string
+    pub endpoint: String,
+    /// HTTP status of the response
+    pub status: u16,
+    /// Rate limit resource the request counted against
+    pub resource: String,
+    /// `x-ratelimit-limit`
+    pub limit: Option<u32>,
+    /// `x-ratelimit-remaining`
+    pub remaining: Option<u32>,
+    /// `x-ratelimit-used`
+    pub used: Option<u32>,
+    /// `x-ratelimit-reset`
+    pub reset_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 /// Rate limiting configuration
@@ -26,6 +174,8 @@ pub struct RateLimitConfig {
     pub max_backoff_ms: u64,
     /// Backoff multiplier for exponential backoff
     pub backoff_multiplier: f64,
+    /// Proactive pacing before the quota runs out; `None` only reacts to 403s
+    pub pacing: Option<PacingPolicy>,
 }
 
 impl Default for RateLimitConfig {
@@ -35,10 +185,46 @@ impl Default for RateLimitConfig {
             initial_backoff_ms: 1000,
             max_backoff_ms: 60000,
             backoff_multiplier: 2.0,
+            pacing: Some(PacingPolicy::default()),
         }
     }
 }
 
+/// Spreads the remaining quota over the time left until it resets
+///
+/// Once the remaining requests per second until the reset fall below
+/// `min_rate`, requests against that resource are spaced evenly across the
+/// rest of the window instead of running into a 403.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PacingPolicy {
+    /// Remaining requests per second below which pacing starts
+    pub min_rate: f64,
+    /// Longest pause inserted before a single request
+    pub max_delay_ms: u64,
+}
+
+impl Default for PacingPolicy {
+    fn default() -> Self {
+        Self {
+            min_rate: 0.25,
+            max_delay_ms: 60000,
+        }
+    }
+}
+
+impl PacingPolicy {
+    /// Spacing between requests for the given quota, or `None` while above `min_rate`
+    ///
+    /// An exhausted quota waits for the whole window (capped at `max_delay_ms`).
+    pub fn interval(&self, remaining: u32, until_reset: Duration) -> Option<Duration> {
+        if until_reset.is_zero() || remaining as f64 / until_reset.as_secs_f64() >= self.min_rate {
+            return None;
+        }
+        let interval = until_reset.div_f64(remaining.max(1) as f64);
+        Some(interval.min(Duration::from_millis(self.max_delay_ms)))
+    }
+}
+
 impl GitHubClient {
     /// Create a new GitHub client with authentication token
     /// 
@@ -48,28 +234,77 @@ impl GitHubClient {
     /// # Returns
     /// * `Result<GitHubClient>` - Configured client or error
     pub fn new(token: String) -> Result<Self> {
-        if token.is_empty() {
-            return Err(AppError::authentication("GitHub token cannot be empty"));
-        }
+        Self::builder().token(token).build()
+    }
 
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .user_agent("github-pg-query/0.1.0")
-            .build()
-            .map_err(|e| AppError::configuration(format!("Failed to create HTTP client: {}", e)))?;
+    /// Start configuring a client; see [`GitHubClientBuilder`]
+    pub fn builder() -> GitHubClientBuilder {
+        GitHubClientBuilder::default()
+    }
 
-        Ok(Self {
-            client,
-            token,
-            base_url: "https://api.github.com".to_string(),
-        })
+    /// Create a new GitHub client with custom HTTP settings
+    /// 
+    /// # Arguments
+    /// * `token` - GitHub personal access token or API token
+    /// * `http_config` - Timeouts and connection pool settings
+    pub fn new_with_config(token: String, http_config: HttpConfig) -> Result<Self> {
+        Self::builder().token(token).http_config(http_config).build()
+    }
+
+    /// Circuit breaker guarding this client's requests
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.circuit_breaker
+    }
+
+    /// Rate limit handling applied by this client
+    pub fn rate_limit(&self) -> &RateLimitConfig {
+        &self.rate_limit
+    }
+
+    /// HTTP settings this client was built with
+    pub fn http_config(&self) -> &HttpConfig {
+        &self.http_config
+    }
+
+    /// Snapshot of the requests sent and rate limit quota observed so far
+    pub fn api_usage(&self) -> ApiUsage {
+        self.usage.lock().expect("API usage lock poisoned").clone()
+    }
+
+    /// Summary recorded in `query_history.http_metrics`, tagged with the run's request ID
+    pub fn http_metrics(&self) -> HttpMetrics {
+        HttpMetrics { request_id: self.http_config.request_id.clone(), ..self.api_usage().http_metrics() }
+    }
+
+    /// Take the rate limit headers recorded since the last call, oldest first
+    ///
+    /// Every response carrying `x-ratelimit-remaining` is recorded; clones of
+    /// a client share the same log.
+    pub fn take_rate_limit_log(&self) -> Vec<RateLimitSample> {
+        std::mem::take(&mut *self.rate_limit_log.lock().expect("rate limit log lock poisoned"))
+    }
+
+    /// API base URL requests are sent to
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Short SHA-256 fingerprint of the token for audit records
+    ///
+    /// Identifies which credential ran a query without storing the token.
+    pub fn token_fingerprint(&self) -> String {
+        let digest = Sha256::digest(self.token.as_bytes());
+        digest[..8].iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Whether requests are sent without a token
+    pub fn is_anonymous(&self) -> bool {
+        self.token.is_empty()
     }
 
     /// Create a new GitHub client with custom base URL (for testing)
     pub fn with_base_url(token: String, base_url: String) -> Result<Self> {
-        let mut client = Self::new(token)?;
-        client.base_url = base_url;
-        Ok(client)
+        Self::builder().token(token).base_url(base_url).build()
     }
 
     /// Search repositories using GitHub API with rate limiting and retry logic
@@ -98,7 +333,7 @@ impl GitHubClient {
         per_page: Option<u32>,
         page: Option<u32>,
     ) -> Result<SearchResponse> {
-        self.search_repositories_with_config(query, per_page, page, &RateLimitConfig::default())
+        self.search_repositories_with_config(query, per_page, page, &self.rate_limit)
             .await
     }
 
@@ -110,70 +345,700 @@ impl GitHubClient {
         page: Option<u32>,
         config: &RateLimitConfig,
     ) -> Result<SearchResponse> {
+        self.search(
+            "repositories",
+            query,
+            per_page,
+            page,
+            &[("sort", REPOSITORY_SEARCH_SORT), ("order", REPOSITORY_SEARCH_ORDER)],
+            config,
+        )
+        .await
+    }
+
+    /// Search topics using GitHub API with rate limiting and retry logic
+    /// 
+    /// # Arguments
+    /// * `query` - GitHub topic search query string (e.g., "rust is:curated")
+    /// * `per_page` - Number of results per page (1-100, default: 30)
+    /// * `page` - Page number to retrieve (default: 1)
+    pub async fn search_topics(
+        &self,
+        query: &str,
+        per_page: Option<u32>,
+        page: Option<u32>,
+    ) -> Result<TopicSearchResponse> {
+        self.search("topics", query, per_page, page, &[], &self.rate_limit)
+            .await
+    }
+
+    /// Check a query against GitHub by fetching a single result
+    ///
+    /// Returns the number of matches. A query GitHub rejects fails with
+    /// [`AppError::InvalidQuery`] carrying GitHub's validation message.
+    pub async fn preflight_query(&self, search_type: SearchType, query: &str) -> Result<i64> {
+        match search_type {
+            SearchType::Repositories => Ok(self.search_repositories(query, Some(1), Some(1)).await?.total_count),
+            SearchType::Topics => Ok(self.search_topics(query, Some(1), Some(1)).await?.total_count),
+        }
+    }
+
+    /// Execute a request against one of the `/search/*` endpoints
+    #[tracing::instrument(name = "github.search", skip_all, fields(endpoint, page, per_page))]
+    async fn search<T: DeserializeOwned + Send + 'static>(
+        &self,
+        endpoint: &str,
+        query: &str,
+        per_page: Option<u32>,
+        page: Option<u32>,
+        extra_params: &[(&str, &str)],
+        config: &RateLimitConfig,
+    ) -> Result<T> {
         if query.is_empty() {
             return Err(AppError::invalid_query(query, "Query cannot be empty"));
         }
 
         let per_page = per_page.unwrap_or(30).clamp(1, 100);
         let page = page.unwrap_or(1).max(1);
+        let span = tracing::Span::current();
+        span.record("endpoint", endpoint);
+        span.record("page", page);
+        span.record("per_page", per_page);
 
-        let url = format!("{}/search/repositories", self.base_url);
-        
-        let mut attempt = 0;
-        let mut backoff_ms = config.initial_backoff_ms;
+        let url = format!("{}/search/{}", self.base_url, endpoint);
+        let accept = if self.text_match {
+            "application/vnd.github.text-match+json"
+        } else {
+            "application/vnd.github.v3+json"
+        };
 
-        loop {
-            let response = self
-                .client
-                .get(&url)
-                .header("Authorization", format!("Bearer {}", self.token))
-                .header("Accept", "application/vnd.github.v3+json")
-                .header("X-GitHub-Api-Version", "2022-11-28")
-                .query(&[
-                    ("q", query),
-                    ("per_page", &per_page.to_string()),
-                    ("page", &page.to_string()),
-                    ("sort", "updated"),
-                    ("order", "desc"),
-                ])
-                .send()
-                .await?;
-
-            match response.status() {
-                StatusCode::OK => {
-                    let search_response: SearchResponse = response.json().await?;
-                    return Ok(search_response);
-                }
-                StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS => {
-                    if attempt >= config.max_retries {
-                        let reset_time = self.extract_rate_limit_reset(&response).await;
-                        return Err(AppError::rate_limit(reset_time));
+        let cache_key = self.cache.as_ref().map(|_| {
+            let (per_page, page, fingerprint) = (per_page.to_string(), page.to_string(), self.token_fingerprint());
+            let mut parts = vec![url.as_str(), query, per_page.as_str(), page.as_str(), accept, fingerprint.as_str()];
+            for (name, value) in extra_params {
+                parts.extend([*name, *value]);
+            }
+            ResponseCache::key(&parts)
+        });
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+            if let Some(body) = cache.get(key).await {
+                match serde_json::from_slice(&body) {
+                    Ok(cached) => {
+                        self.usage.lock().expect("API usage lock poisoned").cache_hits += 1;
+                        tracing::debug!(endpoint, page, "search page served from the response cache");
+                        return Ok(cached);
                     }
+                    Err(error) => tracing::warn!(%error, "ignoring unreadable response cache entry"),
+                }
+            }
+        }
 
-                    // Exponential backoff with jitter
-                    let jitter = fastrand::u64(0..=backoff_ms / 4);
-                    let delay = Duration::from_millis(backoff_ms + jitter);
-                    sleep(delay).await;
+        self.pace("search", config.pacing).await;
+        let request = self
+            .client
+            .get(&url)
+            .header("Accept", accept)
+            .header("X-GitHub-Api-Version", "2022-11-28")
+            .query(&[
+                ("q", query),
+                ("per_page", &per_page.to_string()),
+                ("page", &page.to_string()),
+            ])
+            .query(extra_params);
+        let retry = RetryOnRateLimit::new(config.clone());
+        let response = self.send_with(request, &[&retry]).await?;
 
-                    backoff_ms = ((backoff_ms as f64 * config.backoff_multiplier) as u64)
-                        .min(config.max_backoff_ms);
-                    attempt += 1;
+        match response.status() {
+            StatusCode::OK => match (&self.cache, &cache_key) {
+                (Some(cache), Some(key)) => {
+                    let body = response.bytes().await?;
+                    let value = serde_json::from_slice(&body)?;
+                    // Partial results are worth asking for again
+                    let complete: CompletenessProbe = serde_json::from_slice(&body).unwrap_or_default();
+                    if !complete.incomplete_results {
+                        cache.put(key, &body).await;
+                    }
+                    Ok(value)
                 }
-                StatusCode::UNAUTHORIZED => {
-                    return Err(AppError::authentication("Invalid or expired GitHub token"));
+                _ => decode_json(response).await,
+            },
+            StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS => {
+                let reset_time = self.extract_rate_limit_reset(&response).await;
+                Err(AppError::rate_limit(reset_time))
+            }
+            StatusCode::UNAUTHORIZED => {
+                Err(AppError::authentication("Invalid or expired GitHub token"))
+            }
+            StatusCode::UNPROCESSABLE_ENTITY => {
+                let error_body = response.text().await.unwrap_or_default();
+                let reason = self.extract_validation_error(&error_body);
+                Err(AppError::invalid_query(query, reason))
+            }
+            status => {
+                let error_body = response.text().await.unwrap_or_default();
+                let message = format!("HTTP {}: {}", status, error_body);
+                Err(AppError::github_api(message))
+            }
+        }
+    }
+
+    /// Names of the files and directories in the root of a repository
+    ///
+    /// Returns `Ok(None)` for empty, deleted, or inaccessible repositories.
+    pub async fn get_root_files(&self, full_name: &str) -> Result<Option<Vec<String>>> {
+        let path = format!("/repos/{}/contents", full_name);
+        let entries: Option<Vec<ContentEntry>> = self
+            .fetch_json(&path, "application/vnd.github+json", &[], true)
+            .await?;
+        Ok(entries.map(|entries| entries.into_iter().map(|entry| entry.name).collect()))
+    }
+
+    /// Fetch the complete topic list for a repository
+    /// 
+    /// Search results may carry a truncated topic list; this calls
+    /// `/repos/{owner}/{repo}/topics` to retrieve all of them.
+    /// 
+    /// # Arguments
+    /// * `full_name` - Repository full name in `owner/repo` form
+    pub async fn get_repository_topics(&self, full_name: &str) -> Result<Vec<String>> {
+        let path = format!("/repos/{}/topics", full_name);
+        let topics: RepositoryTopicsResponse = self
+            .get_json(&path, "application/vnd.github+json", &[])
+            .await?;
+        Ok(topics.names)
+    }
+
+    /// Fetch the fields of a repository that search results omit
+    /// 
+    /// Returns `Ok(None)` when the repository has been deleted or made
+    /// private since it was stored.
+    /// 
+    /// # Arguments
+    /// * `full_name` - Repository full name in `owner/repo` form
+    pub async fn get_repository_details(&self, full_name: &str) -> Result<Option<RepositoryDetails>> {
+        let path = format!("/repos/{}", full_name);
+        self.fetch_json(&path, "application/vnd.github+json", &[], true).await
+    }
+
+    /// Fetch one page of stargazers including their `starred_at` timestamps
+    /// 
+    /// # Arguments
+    /// * `full_name` - Repository full name in `owner/repo` form
+    /// * `per_page` - Number of stargazers per page (1-100)
+    /// * `page` - Page number to retrieve (starts from 1)
+    pub async fn get_stargazers(
+        &self,
+        full_name: &str,
+        per_page: u32,
+        page: u32,
+    ) -> Result<Vec<Stargazer>> {
+        let path = format!("/repos/{}/stargazers", full_name);
+        self.get_json(
+            &path,
+            "application/vnd.github.star+json",
+            &[
+                ("per_page", &per_page.clamp(1, 100).to_string()),
+                ("page", &page.max(1).to_string()),
+            ],
+        )
+        .await
+    }
+
+    /// List the GitHub Actions workflows defined in a repository
+    pub async fn get_workflows(&self, full_name: &str) -> Result<Vec<Workflow>> {
+        let path = format!("/repos/{}/actions/workflows", full_name);
+        let response: WorkflowsResponse = self
+            .get_json(&path, "application/vnd.github+json", &[("per_page", "100")])
+            .await?;
+        Ok(response.workflows)
+    }
+
+    /// Fetch the most recent workflow run on a branch, if any
+    pub async fn get_latest_workflow_run(
+        &self,
+        full_name: &str,
+        branch: &str,
+    ) -> Result<Option<WorkflowRun>> {
+        let path = format!("/repos/{}/actions/runs", full_name);
+        let response: WorkflowRunsResponse = self
+            .get_json(
+                &path,
+                "application/vnd.github+json",
+                &[("branch", branch), ("per_page", "1")],
+            )
+            .await?;
+        Ok(response.workflow_runs.into_iter().next())
+    }
+
+    /// Fetch published security advisories for a repository
+    pub async fn get_security_advisories(&self, full_name: &str) -> Result<Vec<SecurityAdvisory>> {
+        let path = format!("/repos/{}/security-advisories", full_name);
+        self.get_json(&path, "application/vnd.github+json", &[("per_page", "100")])
+            .await
+    }
+
+    /// Fetch one page of open Dependabot alerts
+    /// 
+    /// Returns `Ok(None)` when the token is not allowed to read alerts for
+    /// the repository (or alerts are disabled), rather than failing.
+    pub async fn get_dependabot_alerts(
+        &self,
+        full_name: &str,
+        page: u32,
+    ) -> Result<Option<Vec<DependabotAlert>>> {
+        let path = format!("/repos/{}/dependabot/alerts", full_name);
+        self.fetch_json(
+            &path,
+            "application/vnd.github+json",
+            &[("state", "open"), ("per_page", "100"), ("page", &page.max(1).to_string())],
+            true,
+        )
+        .await
+    }
+
+    /// Fetch the head commit SHA and approximate commit count of a branch
+    /// 
+    /// Requests `/commits?per_page=1` so the `rel="last"` page number in the
+    /// Link header equals the number of commits reachable from the branch.
+    /// Returns `Ok(None)` for empty repositories.
+    #[tracing::instrument(name = "github.branch_head", skip(self))]
+    pub async fn get_branch_head(&self, full_name: &str, branch: &str) -> Result<Option<BranchHead>> {
+        let url = format!("{}/repos/{}/commits", self.base_url, full_name);
+
+        self.pace("core", self.rate_limit.pacing).await;
+        let response = self
+            .send(
+                self.client
+                    .get(&url)
+                    .header("Accept", "application/vnd.github+json")
+                    .header("X-GitHub-Api-Version", "2022-11-28")
+                    .query(&[("sha", branch), ("per_page", "1")]),
+            )
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let last_page = response
+                    .headers()
+                    .get("link")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(parse_last_page);
+                let commits: Vec<CommitRef> = response.json().await?;
+
+                Ok(commits.into_iter().next().map(|commit| BranchHead {
+                    sha: commit.sha,
+                    commit_count: last_page.unwrap_or(1),
+                }))
+            }
+            // GitHub answers 409 Conflict for repositories without commits
+            StatusCode::CONFLICT => Ok(None),
+            StatusCode::UNAUTHORIZED => {
+                Err(AppError::authentication("Invalid or expired GitHub token"))
+            }
+            StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS => {
+                let reset_time = self.extract_rate_limit_reset(&response).await;
+                Err(AppError::rate_limit(reset_time))
+            }
+            status => {
+                let error_body = response.text().await.unwrap_or_default();
+                Err(AppError::github_api(format!(
+                    "Commit lookup for {} failed: HTTP {}: {}",
+                    full_name, status, error_body
+                )))
+            }
+        }
+    }
+
+    /// Issue an authenticated GET against a REST endpoint and decode the JSON body
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        accept: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T> {
+        self.fetch_json(path, accept, params, false)
+            .await?
+            .ok_or_else(|| AppError::github_api(format!("Request to {} returned no data", path)))
+    }
+
+    /// Issue an authenticated GET, optionally mapping "not permitted" responses to `None`
+    /// 
+    /// With `tolerate_missing`, 404 responses and 403 responses that are not
+    /// caused by an exhausted quota yield `Ok(None)`.
+    #[tracing::instrument(name = "github.request", skip(self, accept, params, tolerate_missing))]
+    async fn fetch_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        accept: &str,
+        params: &[(&str, &str)],
+        tolerate_missing: bool,
+    ) -> Result<Option<T>> {
+        let url = format!("{}{}", self.base_url, path);
+
+        self.pace("core", self.rate_limit.pacing).await;
+        let response = self
+            .send(
+                self.client
+                    .get(&url)
+                    .header("Accept", accept)
+                    .header("X-GitHub-Api-Version", "2022-11-28")
+                    .query(params),
+            )
+            .await?;
+
+        let quota_exhausted = response
+            .headers()
+            .get("x-ratelimit-remaining")
+            .and_then(|v| v.to_str().ok())
+            == Some("0");
+
+        match response.status() {
+            StatusCode::OK => Ok(Some(response.json().await?)),
+            StatusCode::UNAUTHORIZED => {
+                Err(AppError::authentication("Invalid or expired GitHub token"))
+            }
+            StatusCode::TOO_MANY_REQUESTS => {
+                let reset_time = self.extract_rate_limit_reset(&response).await;
+                Err(AppError::rate_limit(reset_time))
+            }
+            StatusCode::FORBIDDEN if quota_exhausted || !tolerate_missing => {
+                let reset_time = self.extract_rate_limit_reset(&response).await;
+                Err(AppError::rate_limit(reset_time))
+            }
+            StatusCode::FORBIDDEN | StatusCode::NOT_FOUND if tolerate_missing => Ok(None),
+            status => {
+                let error_body = response.text().await.unwrap_or_default();
+                Err(AppError::github_api(format!(
+                    "Request to {} failed: HTTP {}: {}",
+                    path, status, error_body
+                )))
+            }
+        }
+    }
+
+    /// Send a request through the client's middleware
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        self.send_with(request, &[]).await
+    }
+
+    /// Send a request through the client's middleware followed by `extra`
+    ///
+    /// Every attempt passes the circuit breaker and has its rate limit
+    /// headers recorded. 5xx responses, timeouts, and connection failures
+    /// count towards opening the breaker; any other response closes it.
+    async fn send_with(
+        &self,
+        request: reqwest::RequestBuilder,
+        extra: &[&dyn Middleware],
+    ) -> Result<reqwest::Response> {
+        let chain: Vec<&dyn Middleware> = self
+            .middleware
+            .iter()
+            .map(|middleware| middleware.as_ref())
+            .chain(extra.iter().copied())
+            .collect();
+
+        let mut request = request.build()?;
+        for middleware in &chain {
+            middleware.on_request(&mut request).await?;
+        }
+
+        let mut attempt = 0;
+        loop {
+            let attempt_request = request
+                .try_clone()
+                .ok_or_else(|| AppError::internal("GitHub request body cannot be resent"))?;
+            let response = self.execute(attempt_request).await?;
+
+            let mut action = MiddlewareAction::Continue;
+            for middleware in &chain {
+                action = middleware.on_response(&request, &response, attempt).await?;
+                if action != MiddlewareAction::Continue {
+                    break;
                 }
-                StatusCode::UNPROCESSABLE_ENTITY => {
-                    let error_body = response.text().await.unwrap_or_default();
-                    let reason = self.extract_validation_error(&error_body);
-                    return Err(AppError::invalid_query(query, reason));
+            }
+
+            match action {
+                MiddlewareAction::Continue => return Ok(response),
+                MiddlewareAction::Retry(delay) => {
+                    {
+                        let mut usage = self.usage.lock().expect("API usage lock poisoned");
+                        usage.retries += 1;
+                        usage.retry_sleep_ms += delay.as_millis() as u64;
+                    }
+                    sleep(delay).await;
+                    attempt += 1;
                 }
-                status => {
-                    let error_body = response.text().await.unwrap_or_default();
-                    let message = format!("HTTP {}: {}", status, error_body);
-                    return Err(AppError::github_api(message));
+            }
+        }
+    }
+
+    /// Send one attempt through the circuit breaker and record its rate limit headers
+    ///
+    /// Responses are requested gzip or deflate compressed and decoded here,
+    /// so both sizes end up in [`ApiUsage`].
+    async fn execute(&self, mut request: reqwest::Request) -> Result<reqwest::Response> {
+        if let Some(recording) = self.recording.as_ref().filter(|recording| recording.mode() == RecordingMode::Replay) {
+            return self.replay(recording, &request).await;
+        }
+        let recorded_request = (request.method().to_string(), request_path(request.url()));
+        request
+            .headers_mut()
+            .entry(ACCEPT_ENCODING)
+            .or_insert(HeaderValue::from_static("gzip, deflate"));
+        if let Err(error) = self.circuit_breaker.check() {
+            self.usage.lock().expect("API usage lock poisoned").circuit_breaker_rejections += 1;
+            return Err(error);
+        }
+
+        let injected = match &self.faults {
+            Some(faults) => faults.before_request(request.url()).await?,
+            None => None,
+        };
+        let started = Instant::now();
+        let outcome = match injected {
+            Some(response) => Ok(response),
+            None => self.client.execute(request).await,
+        };
+        let latency_ms = started.elapsed().as_millis() as u64;
+        let failed = match &outcome {
+            Ok(response) => {
+                self.record_usage(response, latency_ms);
+                response.status().is_server_error()
+            }
+            Err(error) => error.is_timeout() || error.is_connect(),
+        };
+
+        if failed {
+            if self.circuit_breaker.record_failure() {
+                self.usage.lock().expect("API usage lock poisoned").circuit_breaker_trips += 1;
+                tracing::warn!(
+                    cool_down_secs = self.circuit_breaker.config().cool_down.as_secs(),
+                    "GitHub API circuit breaker opened"
+                );
+            }
+        } else if outcome.is_ok() {
+            self.circuit_breaker.record_success();
+        }
+
+        let mut response = self.decompress(outcome?).await?;
+        if let Some(faults) = &self.faults {
+            response = faults.after_response(response).await?;
+        }
+        match &self.recording {
+            Some(recording) => self.record(recording, recorded_request, response).await,
+            None => Ok(response),
+        }
+    }
+
+    /// Store a decoded response for `--replay` and hand back an identical one
+    async fn record(
+        &self,
+        recording: &ApiRecording,
+        (method, path): (String, String),
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response> {
+        let status = response.status();
+        let version = response.version();
+        let url = response.url().clone();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        recording
+            .save(&RecordedResponse {
+                method,
+                path,
+                status: status.as_u16(),
+                headers: headers
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+                    .collect(),
+                body: String::from_utf8_lossy(&body).into_owned(),
+                recorded_at: chrono::Utc::now(),
+            })
+            .await?;
+
+        let mut builder = http::Response::builder().status(status).version(version).url(url);
+        if let Some(builder_headers) = builder.headers_mut() {
+            *builder_headers = headers;
+        }
+        let response = builder
+            .body(body)
+            .map_err(|error| AppError::internal(format!("Failed to rebuild recorded response: {}", error)))?;
+        Ok(reqwest::Response::from(response))
+    }
+
+    /// Answer `request` with its next recorded response instead of sending it
+    async fn replay(&self, recording: &ApiRecording, request: &reqwest::Request) -> Result<reqwest::Response> {
+        let recorded = recording
+            .load(request.method().as_str(), &request_path(request.url()))
+            .await?;
+        let mut builder = http::Response::builder()
+            .status(recorded.status)
+            .url(request.url().clone());
+        for (name, value) in &recorded.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let response = builder
+            .body(recorded.body.into_bytes())
+            .map_err(|error| AppError::configuration(format!("Invalid recorded response: {}", error)))?;
+        let response = reqwest::Response::from(response);
+        self.record_usage(&response, 0);
+        let size = response.content_length().unwrap_or(0);
+        self.record_bytes(size, size);
+        Ok(response)
+    }
+
+    /// Decode a gzip or deflate response body as it arrives and count its transferred and decoded bytes
+    ///
+    /// The body is never buffered: each network chunk is counted and decoded
+    /// on its way to the reader, so a large page is held in memory once and
+    /// the `streaming-json` decoder still parses it incrementally. Bytes are
+    /// counted as they are read rather than taken from `Content-Length`,
+    /// which chunked responses do not send.
+    async fn decompress(&self, mut response: reqwest::Response) -> Result<reqwest::Response> {
+        let encoding = response
+            .headers()
+            .get(CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_ascii_lowercase());
+        let decoder = match encoding.as_deref() {
+            Some("gzip") => BodyDecoder::Gzip(flate2::write::MultiGzDecoder::new(Vec::new())),
+            Some("deflate") => BodyDecoder::Deflate(Vec::new()),
+            _ => BodyDecoder::Identity,
+        };
+
+        let status = response.status();
+        let version = response.version();
+        let url = response.url().clone();
+        let mut headers = std::mem::take(response.headers_mut());
+        if !matches!(decoder, BodyDecoder::Identity) {
+            headers.remove(CONTENT_ENCODING);
+            headers.remove(CONTENT_LENGTH);
+        }
+
+        let usage = Arc::clone(&self.usage);
+        let path = url.path().to_string();
+        let chunks = futures::stream::try_unfold(Some((response, decoder)), move |state| {
+            let usage = Arc::clone(&usage);
+            let path = path.clone();
+            async move {
+                let Some((mut response, mut decoder)) = state else {
+                    return Ok::<_, std::io::Error>(None);
+                };
+                let decode_error = |error: std::io::Error| {
+                    std::io::Error::other(format!("Failed to decompress response from {}: {}", path, error))
+                };
+                loop {
+                    let (transferred, decoded, done) = match response.chunk().await.map_err(std::io::Error::other)? {
+                        Some(chunk) => (chunk.len(), decoder.feed(&chunk).map_err(decode_error)?, false),
+                        None => (0, decoder.finish().map_err(decode_error)?, true),
+                    };
+                    {
+                        let mut usage = usage.lock().expect("API usage lock poisoned");
+                        usage.bytes_downloaded += transferred as u64;
+                        usage.bytes_decompressed += decoded.len() as u64;
+                    }
+                    if done {
+                        return Ok((!decoded.is_empty()).then(|| (decoded, None)));
+                    }
+                    if !decoded.is_empty() {
+                        return Ok(Some((decoded, Some((response, decoder)))));
+                    }
                 }
             }
+        });
+
+        let mut builder = http::Response::builder().status(status).version(version).url(url);
+        if let Some(builder_headers) = builder.headers_mut() {
+            *builder_headers = headers;
         }
+        let response = builder
+            .body(reqwest::Body::wrap_stream(chunks))
+            .map_err(|error| AppError::internal(format!("Failed to wrap response body: {}", error)))?;
+        Ok(reqwest::Response::from(response))
+    }
+
+    fn record_bytes(&self, transferred: u64, decoded: u64) {
+        let mut usage = self.usage.lock().expect("API usage lock poisoned");
+        usage.bytes_downloaded += transferred;
+        usage.bytes_decompressed += decoded;
+    }
+
+    /// Wait for the next pacing slot of `resource` when its quota is running low
+    ///
+    /// Slots are shared by clones of the client, so concurrent callers are
+    /// spaced out rather than all waiting the same interval.
+    async fn pace(&self, resource: &str, policy: Option<PacingPolicy>) {
+        let Some(policy) = policy else {
+            return;
+        };
+
+        let wait = {
+            let mut usage = self.usage.lock().expect("API usage lock poisoned");
+            let Some(entry) = usage.rate_limits.get_mut(resource) else {
+                return;
+            };
+            let (Some(remaining), Some(reset_at)) = (entry.remaining, entry.reset_at) else {
+                return;
+            };
+            let until_reset = (reset_at - chrono::Utc::now()).to_std().unwrap_or_default();
+            let Some(interval) = policy.interval(remaining, until_reset) else {
+                return;
+            };
+
+            let now = Instant::now();
+            let mut slots = self.pacing_slots.lock().expect("pacing lock poisoned");
+            let mut start = slots.get(resource).map_or(now, |slot| (*slot).max(now));
+            if remaining == 0 {
+                start = start.max(now + interval);
+            }
+            slots.insert(resource.to_string(), start + interval);
+
+            let wait = start - now;
+            entry.paced_ms += wait.as_millis() as u64;
+            wait
+        };
+
+        if !wait.is_zero() {
+            tracing::debug!(resource, wait_ms = wait.as_millis() as u64, "pacing GitHub requests");
+            sleep(wait).await;
+        }
+    }
+
+    /// Count a request and its timing, and remember the quota reported in its rate limit headers
+    fn record_usage(&self, response: &reqwest::Response, latency_ms: u64) {
+        let headers = response.headers();
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let number = |name: &str| header(name).and_then(|v| v.parse::<i64>().ok());
+        let count = |name: &str| number(name).and_then(|v| u32::try_from(v).ok());
+
+        let mut usage = self.usage.lock().expect("API usage lock poisoned");
+        usage.requests += 1;
+        usage.latencies_ms.push(latency_ms);
+
+        let Some(remaining) = number("x-ratelimit-remaining") else {
+            return;
+        };
+        let resource = header("x-ratelimit-resource").unwrap_or("core").to_string();
+        let reset_at = number("x-ratelimit-reset")
+            .and_then(|reset| chrono::DateTime::from_timestamp(reset, 0));
+
+        let entry = usage.rate_limits.entry(resource.clone()).or_default();
+        entry.requests += 1;
+        entry.remaining = u32::try_from(remaining).ok();
+        entry.limit = count("x-ratelimit-limit");
+        entry.reset_at = reset_at;
+
+        self.rate_limit_log.lock().expect("rate limit log lock poisoned").push(RateLimitSample {
+            observed_at: chrono::Utc::now(),
+            endpoint: response.url().path().to_string(),
+            status: response.status().as_u16(),
+            resource,
+            limit: entry.limit,
+            remaining: entry.remaining,
+            used: count("x-ratelimit-used"),
+            reset_at,
+        });
     }
 
     /// Extract rate limit reset time from response headers
@@ -191,20 +1056,22 @@ impl GitHubClient {
     }
 
     /// Extract validation error message from GitHub API error response
+    ///
+    /// GitHub answers invalid searches with a generic `message` ("Validation
+    /// Failed") and the actual problems in `errors`, so both are kept.
     fn extract_validation_error(&self, error_body: &str) -> String {
         if let Ok(error_json) = serde_json::from_str::<Value>(error_body) {
-            if let Some(message) = error_json.get("message").and_then(|m| m.as_str()) {
-                return message.to_string();
-            }
-            if let Some(errors) = error_json.get("errors").and_then(|e| e.as_array()) {
-                let error_messages: Vec<String> = errors
-                    .iter()
-                    .filter_map(|e| e.get("message").and_then(|m| m.as_str()))
-                    .map(|s| s.to_string())
-                    .collect();
-                if !error_messages.is_empty() {
-                    return error_messages.join(", ");
-                }
+            let message = error_json.get("message").and_then(|m| m.as_str());
+            let details: Vec<&str> = error_json
+                .get("errors")
+                .and_then(|e| e.as_array())
+                .map(|errors| errors.iter().filter_map(|e| e.get("message").and_then(|m| m.as_str())).collect())
+                .unwrap_or_default();
+            match (message, details.is_empty()) {
+                (Some(message), false) => return format!("{}: {}", message, details.join(", ")),
+                (Some(message), true) => return message.to_string(),
+                (None, false) => return details.join(", "),
+                (None, true) => {}
             }
         }
         "Invalid query format".to_string()
@@ -212,14 +1079,17 @@ impl GitHubClient {
 
     /// Validate GitHub token by making a test API call
     pub async fn validate_token(&self) -> Result<()> {
+        if self.is_anonymous() {
+            return Err(AppError::authentication("No GitHub token to validate (anonymous client)"));
+        }
         let url = format!("{}/user", self.base_url);
         
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.token))
-            .header("Accept", "application/vnd.github.v3+json")
-            .send()
+            .send(
+                self.client
+                    .get(&url)
+                    .header("Accept", "application/vnd.github.v3+json"),
+            )
             .await?;
 
         match response.status() {
@@ -239,11 +1109,7 @@ impl GitHubClient {
         let url = format!("{}/rate_limit", self.base_url);
         
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.token))
-            .header("Accept", "application/vnd.github.v3+json")
-            .send()
+            .send(self.client.get(&url).header("Accept", "application/vnd.github.v3+json"))
             .await?;
 
         match response.status() {
@@ -264,6 +1130,140 @@ impl GitHubClient {
     }
 }
 
+/// Head commit of a branch with an approximate commit count
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchHead {
+    /// SHA of the newest commit on the branch
+    pub sha: String,
+    /// Number of commits reachable from the branch head
+    pub commit_count: i64,
+}
+
+/// Path and query string of `url`, the key of a recorded response
+fn request_path(url: &reqwest::Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+/// The `incomplete_results` flag of a search response
+#[derive(Default, serde::Deserialize)]
+struct CompletenessProbe {
+    #[serde(default)]
+    incomplete_results: bool,
+}
+
+/// Decode a JSON response body
+///
+/// With the `streaming-json` feature the body is parsed as it arrives instead
+/// of being buffered first, so a multi-megabyte page never sits in memory
+/// twice (once as bytes, once as values). Public so the benchmarks can
+/// measure either build.
+#[cfg(not(feature = "streaming-json"))]
+pub async fn decode_json<T: DeserializeOwned + Send + 'static>(response: reqwest::Response) -> Result<T> {
+    Ok(response.json().await?)
+}
+
+#[cfg(feature = "streaming-json")]
+pub async fn decode_json<T: DeserializeOwned + Send + 'static>(response: reqwest::Response) -> Result<T> {
+    use futures::TryStreamExt;
+    use std::io::BufReader;
+    use tokio_util::io::{StreamReader, SyncIoBridge};
+
+    let body = response.bytes_stream().map_err(std::io::Error::other);
+    let reader = BufReader::new(SyncIoBridge::new(StreamReader::new(body)));
+    tokio::task::spawn_blocking(move || serde_json::from_reader(reader))
+        .await
+        .map_err(|error| AppError::internal(format!("JSON decoding task failed: {}", error)))?
+        .map_err(AppError::from)
+}
+
+/// Incremental decoder of a response body, fed one network chunk at a time
+///
+/// `deflate` is zlib-wrapped per RFC 9110, but some servers send raw
+/// deflate data, so the wrapping is detected from the first two bytes.
+enum BodyDecoder {
+    /// No or an unsupported `Content-Encoding`; chunks pass through unchanged
+    Identity,
+    Gzip(flate2::write::MultiGzDecoder<Vec<u8>>),
+    /// `deflate` before enough bytes arrived to tell zlib from raw deflate
+    Deflate(Vec<u8>),
+    Zlib(flate2::write::ZlibDecoder<Vec<u8>>),
+    RawDeflate(flate2::write::DeflateDecoder<Vec<u8>>),
+}
+
+impl BodyDecoder {
+    /// Decode `chunk`, returning the output it completed
+    fn feed(&mut self, chunk: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            BodyDecoder::Identity => Ok(chunk.to_vec()),
+            BodyDecoder::Gzip(decoder) => {
+                decoder.write_all(chunk)?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            BodyDecoder::Zlib(decoder) => {
+                decoder.write_all(chunk)?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            BodyDecoder::RawDeflate(decoder) => {
+                decoder.write_all(chunk)?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            BodyDecoder::Deflate(pending) => {
+                pending.extend_from_slice(chunk);
+                if pending.len() < 2 {
+                    return Ok(Vec::new());
+                }
+                let header = u16::from_be_bytes([pending[0], pending[1]]);
+                let pending = std::mem::take(pending);
+                *self = if pending[0] & 0x0f == 8 && header % 31 == 0 {
+                    BodyDecoder::Zlib(flate2::write::ZlibDecoder::new(Vec::new()))
+                } else {
+                    BodyDecoder::RawDeflate(flate2::write::DeflateDecoder::new(Vec::new()))
+                };
+                self.feed(&pending)
+            }
+        }
+    }
+
+    /// Flush the output left once the body has ended
+    fn finish(&mut self) -> std::io::Result<Vec<u8>> {
+        match self {
+            BodyDecoder::Identity => Ok(Vec::new()),
+            BodyDecoder::Gzip(decoder) => {
+                decoder.try_finish()?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            BodyDecoder::Zlib(decoder) => {
+                decoder.try_finish()?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            BodyDecoder::RawDeflate(decoder) => {
+                decoder.try_finish()?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            BodyDecoder::Deflate(pending) if pending.is_empty() => Ok(Vec::new()),
+            BodyDecoder::Deflate(_) => Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "truncated deflate body")),
+        }
+    }
+}
+
+/// Extract the page number of the `rel="last"` entry from a Link header
+fn parse_last_page(link_header: &str) -> Option<i64> {
+    link_header
+        .split(',')
+        .find(|part| part.contains("rel=\"last\""))
+        .and_then(|part| {
+            let url = part.split(';').next()?.trim().trim_start_matches('<').trim_end_matches('>');
+            let query = url.split_once('?')?.1;
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("page="))
+                .and_then(|page| page.parse().ok())
+        })
+}
+
 /// Rate limit status information
 #[derive(Debug, Clone)]
 pub struct RateLimitStatus {
@@ -290,3 +1290,32 @@ struct RateLimitInfo {
     reset: i64,
 }
 
+/// Entry of a GitHub API directory listing
+#[derive(Debug, serde::Deserialize)]
+struct ContentEntry {
+    name: String,
+}
+
+/// GitHub API repository topics response structure
+#[derive(Debug, serde::Deserialize)]
+struct RepositoryTopicsResponse {
+    names: Vec<String>,
+}
+
+/// GitHub API workflow listing response structure
+#[derive(Debug, serde::Deserialize)]
+struct WorkflowsResponse {
+    workflows: Vec<Workflow>,
+}
+
+/// GitHub API workflow run listing response structure
+#[derive(Debug, serde::Deserialize)]
+struct WorkflowRunsResponse {
+    workflow_runs: Vec<WorkflowRun>,
+}
+
+/// Minimal commit reference from the commits listing
+#[derive(Debug, serde::Deserialize)]
+struct CommitRef {
+    sha: String,
+}
@@ -0,0 +1,115 @@
+//! Object-safe interface over the GitHub calls this crate makes
+//!
+//! Code that only needs to talk to GitHub can take `&dyn GitHubApi` (or a
+//! generic `A: GitHubApi`) so tests can substitute canned responses for a
+//! real [`GitHubClient`].
+
+use async_trait::async_trait;
+
+use super::{BranchHead, GitHubClient, RateLimitStatus};
+use crate::{
+    DependabotAlert, RepositoryDetails, Result, SearchResponse, SecurityAdvisory, Stargazer, TopicSearchResponse,
+    Workflow, WorkflowRun,
+};
+
+/// The GitHub REST calls made by [`GitHubClient`]
+#[async_trait]
+pub trait GitHubApi: Send + Sync {
+    /// Search `/search/repositories`
+    async fn search_repositories(&self, query: &str, per_page: Option<u32>, page: Option<u32>)
+        -> Result<SearchResponse>;
+
+    /// Search `/search/topics`
+    async fn search_topics(&self, query: &str, per_page: Option<u32>, page: Option<u32>)
+        -> Result<TopicSearchResponse>;
+
+    /// Full topic list of a repository
+    async fn get_repository_topics(&self, full_name: &str) -> Result<Vec<String>>;
+
+    /// Fields search results omit; `None` when the repository is gone
+    async fn get_repository_details(&self, full_name: &str) -> Result<Option<RepositoryDetails>>;
+
+    /// One page of stargazers with their `starred_at` timestamps
+    async fn get_stargazers(&self, full_name: &str, per_page: u32, page: u32) -> Result<Vec<Stargazer>>;
+
+    /// GitHub Actions workflows defined in a repository
+    async fn get_workflows(&self, full_name: &str) -> Result<Vec<Workflow>>;
+
+    /// Most recent workflow run on a branch
+    async fn get_latest_workflow_run(&self, full_name: &str, branch: &str) -> Result<Option<WorkflowRun>>;
+
+    /// Published security advisories of a repository
+    async fn get_security_advisories(&self, full_name: &str) -> Result<Vec<SecurityAdvisory>>;
+
+    /// One page of open Dependabot alerts; `None` when they cannot be read
+    async fn get_dependabot_alerts(&self, full_name: &str, page: u32) -> Result<Option<Vec<DependabotAlert>>>;
+
+    /// Head commit and commit count of a branch; `None` for empty repositories
+    async fn get_branch_head(&self, full_name: &str, branch: &str) -> Result<Option<BranchHead>>;
+
+    /// Names in the root directory; `None` for empty or missing repositories
+    async fn get_root_files(&self, full_name: &str) -> Result<Option<Vec<String>>>;
+
+    /// Check that the token is accepted
+    async fn validate_token(&self) -> Result<()>;
+
+    /// Current search rate limit
+    async fn get_rate_limit(&self) -> Result<RateLimitStatus>;
+}
+
+#[async_trait]
+impl GitHubApi for GitHubClient {
+    async fn search_repositories(&self, query: &str, per_page: Option<u32>, page: Option<u32>)
+        -> Result<SearchResponse> {
+        GitHubClient::search_repositories(self, query, per_page, page).await
+    }
+
+    async fn search_topics(&self, query: &str, per_page: Option<u32>, page: Option<u32>)
+        -> Result<TopicSearchResponse> {
+        GitHubClient::search_topics(self, query, per_page, page).await
+    }
+
+    async fn get_repository_topics(&self, full_name: &str) -> Result<Vec<String>> {
+        GitHubClient::get_repository_topics(self, full_name).await
+    }
+
+    async fn get_repository_details(&self, full_name: &str) -> Result<Option<RepositoryDetails>> {
+        GitHubClient::get_repository_details(self, full_name).await
+    }
+
+    async fn get_stargazers(&self, full_name: &str, per_page: u32, page: u32) -> Result<Vec<Stargazer>> {
+        GitHubClient::get_stargazers(self, full_name, per_page, page).await
+    }
+
+    async fn get_workflows(&self, full_name: &str) -> Result<Vec<Workflow>> {
+        GitHubClient::get_workflows(self, full_name).await
+    }
+
+    async fn get_latest_workflow_run(&self, full_name: &str, branch: &str) -> Result<Option<WorkflowRun>> {
+        GitHubClient::get_latest_workflow_run(self, full_name, branch).await
+    }
+
+    async fn get_security_advisories(&self, full_name: &str) -> Result<Vec<SecurityAdvisory>> {
+        GitHubClient::get_security_advisories(self, full_name).await
+    }
+
+    async fn get_dependabot_alerts(&self, full_name: &str, page: u32) -> Result<Option<Vec<DependabotAlert>>> {
+        GitHubClient::get_dependabot_alerts(self, full_name, page).await
+    }
+
+    async fn get_branch_head(&self, full_name: &str, branch: &str) -> Result<Option<BranchHead>> {
+        GitHubClient::get_branch_head(self, full_name, branch).await
+    }
+
+    async fn get_root_files(&self, full_name: &str) -> Result<Option<Vec<String>>> {
+        GitHubClient::get_root_files(self, full_name).await
+    }
+
+    async fn validate_token(&self) -> Result<()> {
+        GitHubClient::validate_token(self).await
+    }
+
+    async fn get_rate_limit(&self) -> Result<RateLimitStatus> {
+        GitHubClient::get_rate_limit(self).await
+    }
+}
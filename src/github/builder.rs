@@ -0,0 +1,155 @@
+//! Step-by-step construction of a [`GitHubClient`]
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{ApiUsage, GitHubClient, RateLimitConfig};
+use crate::{register_secret, ApiRecording, AppError, BearerAuth, CircuitBreaker, CircuitBreakerConfig, FaultInjector, HttpConfig, Middleware, ResponseCache, Result};
+
+/// Base URL of the public GitHub API
+pub const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Builder returned by [`GitHubClient::builder`]
+///
+/// ```rust,no_run
+/// # use github_pg_query::{GitHubClient, RateLimitConfig};
+/// # use std::time::Duration;
+/// # fn example() -> github_pg_query::Result<()> {
+/// let client = GitHubClient::builder()
+///     .token(std::env::var("GITHUB_TOKEN").unwrap_or_default())
+///     .base_url("https://ghe.example.com/api/v3")
+///     .timeout(Duration::from_secs(60))
+///     .retry(RateLimitConfig { max_retries: 5, ..RateLimitConfig::default() })
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct GitHubClientBuilder {
+    token: Option<String>,
+    anonymous: bool,
+    base_url: Option<String>,
+    http_config: HttpConfig,
+    rate_limit: RateLimitConfig,
+    circuit_breaker: CircuitBreakerConfig,
+    middleware: Vec<Arc<dyn Middleware>>,
+    text_match: bool,
+    cache: Option<ResponseCache>,
+    recording: Option<ApiRecording>,
+    faults: Option<FaultInjector>,
+}
+
+impl GitHubClientBuilder {
+    /// GitHub personal access token or API token (required)
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Send requests without a token, at GitHub's unauthenticated rate limits
+    ///
+    /// Search is limited to 10 requests per minute and other endpoints to 60
+    /// per hour; any [`token`](Self::token) is ignored.
+    pub fn anonymous(mut self) -> Self {
+        self.anonymous = true;
+        self
+    }
+
+    /// API base URL, e.g. `https://ghe.example.com/api/v3` (defaults to api.github.com)
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Timeouts, proxy, TLS, and connection pool settings
+    pub fn http_config(mut self, http_config: HttpConfig) -> Self {
+        self.http_config = http_config;
+        self
+    }
+
+    /// Overall request timeout (overrides the one in [`http_config`](Self::http_config))
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.http_config.timeout = timeout;
+        self
+    }
+
+    /// Retry backoff and pacing for requests that do not take their own [`RateLimitConfig`]
+    pub fn retry(mut self, config: RateLimitConfig) -> Self {
+        self.rate_limit = config;
+        self
+    }
+
+    /// Fail fast after consecutive server errors or timeouts
+    pub fn circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = config;
+        self
+    }
+
+    /// Run `middleware` around every request, after [`BearerAuth`] and those added before it
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Request matched fragments (`text_matches`) alongside search results
+    pub fn text_match(mut self, enabled: bool) -> Self {
+        self.text_match = enabled;
+        self
+    }
+
+    /// Reuse search responses from `cache` instead of repeating requests
+    pub fn response_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Store every response in a directory, or answer requests from one (see [`ApiRecording`])
+    pub fn recording(mut self, recording: ApiRecording) -> Self {
+        self.recording = Some(recording);
+        self
+    }
+
+    /// Inject synthetic failures into requests (see [`FaultInjector`])
+    pub fn fault_injection(mut self, faults: FaultInjector) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    /// Create the client
+    pub fn build(self) -> Result<GitHubClient> {
+        let mut middleware: Vec<Arc<dyn Middleware>> = Vec::new();
+        let token = if self.anonymous {
+            String::new()
+        } else {
+            let token = self.token.unwrap_or_default();
+            if token.is_empty() {
+                return Err(AppError::authentication("GitHub token cannot be empty"));
+            }
+            middleware.push(Arc::new(BearerAuth::new(&token)?));
+            register_secret(&token);
+            token
+        };
+        middleware.extend(self.middleware);
+
+        let client = self.http_config.build_client()?;
+
+        Ok(GitHubClient {
+            client,
+            token,
+            base_url: self.base_url.unwrap_or_else(|| DEFAULT_GITHUB_API_URL.to_string()),
+            http_config: self.http_config,
+            usage: Arc::new(Mutex::new(ApiUsage::default())),
+            rate_limit_log: Arc::new(Mutex::new(Vec::new())),
+            rate_limit: self.rate_limit,
+            pacing_slots: Arc::new(Mutex::new(BTreeMap::new())),
+            circuit_breaker: CircuitBreaker::new(self.circuit_breaker),
+            middleware,
+            text_match: self.text_match,
+            cache: self.cache,
+            recording: self.recording,
+            faults: self.faults,
+        })
+    }
+}
+
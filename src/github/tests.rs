@@ -1,5 +1,6 @@
 use super::*;
 use crate::models::{Repository, RepositoryOwner, RepositoryLicense, SearchResponse};
+use crate::CircuitBreakerConfig;
 use serde_json::json;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -17,6 +18,7 @@ pub struct MockResponse {
     pub body: String,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct MockRequest {
     pub method: String,
@@ -37,6 +39,7 @@ impl MockGitHubServer {
         self.responses.lock().await.push(response);
     }
 
+    #[allow(dead_code)]
     pub async fn get_requests(&self) -> Vec<MockRequest> {
         self.request_log.lock().await.clone()
     }
@@ -53,6 +56,7 @@ impl MockGitHubServer {
             total_count: repositories.len() as i64,
             incomplete_results: false,
             items: repositories,
+            quarantined: Vec::new(),
         };
 
         MockResponse {
@@ -155,6 +159,9 @@ fn create_test_repository() -> Repository {
         has_wiki: true,
         has_pages: false,
         has_downloads: true,
+        score: None,
+        text_matches: Vec::new(),
+        matched_language_filter: None,
     }
 }
 
@@ -183,6 +190,50 @@ mod unit_tests {
         assert_eq!(search_response.items[0], repo);
     }
 
+    #[test]
+    fn test_pacing_policy_interval() {
+        let policy = PacingPolicy { min_rate: 0.5, max_delay_ms: 10_000 };
+
+        // 100 requests over 60 seconds is above the threshold
+        assert_eq!(policy.interval(100, Duration::from_secs(60)), None);
+        // 20 requests over 60 seconds: one every 3 seconds
+        assert_eq!(policy.interval(20, Duration::from_secs(60)), Some(Duration::from_secs(3)));
+        // An exhausted quota waits for the reset, capped at max_delay_ms
+        assert_eq!(policy.interval(0, Duration::from_secs(5)), Some(Duration::from_secs(5)));
+        assert_eq!(policy.interval(0, Duration::from_secs(600)), Some(Duration::from_secs(10)));
+        // Nothing to pace once the window has reset
+        assert_eq!(policy.interval(0, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn test_builder_settings() {
+        let client = GitHubClient::builder()
+            .token("test_token")
+            .base_url("http://localhost:8080")
+            .timeout(Duration::from_secs(5))
+            .retry(RateLimitConfig { max_retries: 7, pacing: None, ..RateLimitConfig::default() })
+            .circuit_breaker(CircuitBreakerConfig { failure_threshold: 2, ..CircuitBreakerConfig::default() })
+            .build()
+            .unwrap();
+
+        assert_eq!(client.base_url(), "http://localhost:8080");
+        assert_eq!(client.http_config().timeout, Duration::from_secs(5));
+        assert_eq!(client.rate_limit().max_retries, 7);
+        assert_eq!(client.rate_limit().pacing, None);
+        assert_eq!(client.circuit_breaker().config().failure_threshold, 2);
+    }
+
+    #[test]
+    fn test_builder_defaults_and_token() {
+        let client = GitHubClient::builder().token("test_token").build().unwrap();
+        assert_eq!(client.base_url(), DEFAULT_GITHUB_API_URL);
+        assert_eq!(client.http_config().timeout, HttpConfig::default().timeout);
+
+        assert!(GitHubClient::builder().build().is_err());
+        assert!(GitHubClient::builder().token("").build().is_err());
+        assert!(!client.is_anonymous());
+    }
+
     #[test]
     fn test_create_rate_limit_response() {
         let response = MockGitHubServer::create_rate_limit_response();
@@ -257,6 +308,18 @@ mod integration_tests {
         assert_eq!(client.base_url, "https://api.github.com");
     }
 
+    #[test]
+    fn test_token_fingerprint() {
+        let client = GitHubClient::new("test_token".to_string()).unwrap();
+        let fingerprint = client.token_fingerprint();
+        assert_eq!(fingerprint.len(), 16);
+        assert!(!fingerprint.contains("test_token"));
+        assert_eq!(fingerprint, client.token_fingerprint());
+
+        let other = GitHubClient::new("other_token".to_string()).unwrap();
+        assert_ne!(fingerprint, other.token_fingerprint());
+    }
+
     #[test]
     fn test_github_client_empty_token_error() {
         let result = GitHubClient::new("".to_string());
@@ -318,6 +381,9 @@ mod integration_tests {
         }).to_string();
         
         let result = client.extract_validation_error(&error_with_message);
+        assert_eq!(result, "Validation Failed: Invalid syntax");
+
+        let result = client.extract_validation_error(&json!({"message": "Validation Failed"}).to_string());
         assert_eq!(result, "Validation Failed");
         
         // Test with errors array only
@@ -380,6 +446,24 @@ mod integration_tests {
         assert!(status.reset_at <= chrono::Utc::now());
     }
 
+    #[test]
+    fn test_http_metrics_percentiles() {
+        let usage = ApiUsage {
+            requests: 20,
+            latencies_ms: (1..=20).rev().collect(),
+            retry_sleep_ms: 500,
+            rate_limits: BTreeMap::from([(
+                "search".to_string(),
+                ResourceUsage { paced_ms: 250, ..ResourceUsage::default() },
+            )]),
+            ..ApiUsage::default()
+        };
+        let metrics = usage.http_metrics();
+        assert_eq!((metrics.latency_p50_ms, metrics.latency_p95_ms), (Some(10), Some(19)));
+        assert_eq!(metrics.rate_limit_sleep_ms, 750);
+        assert_eq!(ApiUsage::default().http_metrics().latency_p50_ms, None);
+    }
+
     #[test]
     fn test_backoff_calculation() {
         let config = RateLimitConfig::default();
@@ -412,8 +496,8 @@ mod property_tests {
     fn test_per_page_always_in_valid_range() {
         // Test that per_page clamping always produces valid values
         for input in 0..200u32 {
-            let clamped = Some(input).unwrap_or(30).clamp(1, 100);
-            assert!(clamped >= 1 && clamped <= 100, "Invalid per_page: {}", clamped);
+            let clamped = input.clamp(1, 100);
+            assert!((1..=100).contains(&clamped), "Invalid per_page: {}", clamped);
         }
     }
 
@@ -421,7 +505,7 @@ mod property_tests {
     fn test_page_always_positive() {
         // Test that page validation always produces positive values
         for input in 0..100u32 {
-            let page = Some(input).unwrap_or(1).max(1);
+            let page = input.max(1);
             assert!(page >= 1, "Invalid page: {}", page);
         }
     }
@@ -429,15 +513,15 @@ mod property_tests {
     #[test]
     fn test_backoff_progression_monotonic() {
         let config = RateLimitConfig::default();
-        let mut backoff_ms = config.initial_backoff_ms;
-        let mut previous = 0;
+        let retry = RetryOnRateLimit::new(config.clone());
+        let mut previous = Duration::ZERO;
 
         // Test that backoff always increases (until cap)
-        for _ in 0..10 {
-            assert!(backoff_ms >= previous, "Backoff decreased: {} -> {}", previous, backoff_ms);
-            previous = backoff_ms;
-            backoff_ms = ((backoff_ms as f64 * config.backoff_multiplier) as u64)
-                .min(config.max_backoff_ms);
+        for attempt in 0..10 {
+            let backoff = retry.backoff(attempt);
+            assert!(backoff >= previous, "Backoff decreased: {:?} -> {:?}", previous, backoff);
+            assert!(backoff <= Duration::from_millis(config.max_backoff_ms));
+            previous = backoff;
         }
     }
 
@@ -451,4 +535,929 @@ mod property_tests {
         assert!(GitHubClient::new("a".to_string()).is_ok());
         assert!(GitHubClient::new("very_long_token_string_that_might_be_realistic".to_string()).is_ok());
     }
-}
\ No newline at end of file
+}
+// HTTP-level tests against a local wiremock server
+#[cfg(test)]
+mod http_tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_get_root_files() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/contents"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"name": "Cargo.toml", "type": "file"},
+                {"name": "src", "type": "dir"}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/empty/contents"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({"message": "This repository is empty."})))
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::builder().anonymous().base_url(server.uri()).build().unwrap();
+        assert_eq!(
+            client.get_root_files("octocat/Hello-World").await.unwrap(),
+            Some(vec!["Cargo.toml".to_string(), "src".to_string()])
+        );
+        assert_eq!(client.get_root_files("octocat/empty").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_client_sends_no_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/topics"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"names": ["rust"]})))
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::builder().anonymous().base_url(server.uri()).build().unwrap();
+        assert!(client.is_anonymous());
+        assert_eq!(client.get_repository_topics("octocat/Hello-World").await.unwrap(), vec!["rust"]);
+
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests.iter().all(|request| !request.headers.contains_key("authorization")));
+        assert!(matches!(client.validate_token().await, Err(AppError::Authentication { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_preflight_query() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search/repositories"))
+            .and(query_param("q", "language:rust"))
+            .and(query_param("per_page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 4200,
+                "incomplete_results": false,
+                "items": [create_test_repository()]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/search/repositories"))
+            .and(query_param("q", "stars:>>1"))
+            .respond_with(ResponseTemplate::new(422).set_body_json(json!({
+                "message": "Validation Failed",
+                "errors": [{"message": "Invalid qualifier value: stars:>>1", "code": "invalid"}]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        assert_eq!(client.preflight_query(SearchType::Repositories, "language:rust").await.unwrap(), 4200);
+        let error = client.preflight_query(SearchType::Repositories, "stars:>>1").await.unwrap_err();
+        assert!(matches!(error, AppError::InvalidQuery { .. }));
+        assert!(error.to_string().contains("Invalid qualifier value: stars:>>1"));
+    }
+
+    #[tokio::test]
+    async fn test_search_responses_are_cached() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search/repositories"))
+            .and(query_param("q", "language:rust"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 1,
+                "incomplete_results": false,
+                "items": [create_test_repository()]
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/search/repositories"))
+            .and(query_param("q", "language:go"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 0,
+                "incomplete_results": true,
+                "items": []
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path(), Duration::from_secs(600));
+        let client = GitHubClient::builder()
+            .token("test_token")
+            .base_url(server.uri())
+            .response_cache(cache)
+            .build()
+            .unwrap();
+
+        let first = client.search_repositories("language:rust", Some(30), Some(1)).await.unwrap();
+        let second = client.search_repositories("language:rust", Some(30), Some(1)).await.unwrap();
+        assert_eq!(first.items[0].full_name, second.items[0].full_name);
+        // Another page is another key
+        client.search_repositories("language:rust", Some(30), Some(2)).await.unwrap();
+        // Incomplete results are never cached
+        client.search_repositories("language:go", Some(30), Some(1)).await.unwrap();
+        client.search_repositories("language:go", Some(30), Some(1)).await.unwrap();
+
+        let usage = client.api_usage();
+        assert_eq!(usage.cache_hits, 1);
+        assert_eq!(usage.requests, 4);
+        assert_eq!(usage.http_metrics().cache_hits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_recorded_responses_replay_offline() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search/repositories"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-ratelimit-remaining", "29")
+                    .insert_header("x-ratelimit-resource", "search")
+                    .set_body_json(json!({
+                        "total_count": 1,
+                        "incomplete_results": false,
+                        "items": [create_test_repository()]
+                    })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/topics"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({"message": "Not Found"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = GitHubClient::builder()
+            .token("test_token")
+            .base_url(server.uri())
+            .recording(ApiRecording::record(dir.path()))
+            .build()
+            .unwrap();
+        let recorded = recorder.search_repositories("language:rust", Some(30), Some(1)).await.unwrap();
+        assert!(recorder.get_repository_topics("octocat/Hello-World").await.is_err());
+
+        // Nothing listens on the replaying client's base URL
+        let replayer = GitHubClient::builder()
+            .anonymous()
+            .base_url("http://127.0.0.1:9")
+            .recording(ApiRecording::replay(dir.path()))
+            .build()
+            .unwrap();
+        let replayed = replayer.search_repositories("language:rust", Some(30), Some(1)).await.unwrap();
+        assert_eq!(replayed.items[0].full_name, recorded.items[0].full_name);
+        assert!(replayer.get_repository_topics("octocat/Hello-World").await.is_err());
+        assert_eq!(replayer.api_usage().rate_limits["search"].remaining, Some(29));
+
+        let missing = replayer.search_repositories("language:go", Some(30), Some(1)).await.unwrap_err();
+        assert!(missing.to_string().contains("No recorded response for GET /search/repositories"));
+    }
+
+    #[cfg(feature = "chaos")]
+    #[tokio::test]
+    async fn test_injected_faults() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search/repositories"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 1,
+                "incomplete_results": false,
+                "items": [create_test_repository()]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = |spec: &str| {
+            let faults = FaultInjector::new(spec.parse().unwrap()).unwrap();
+            let client = GitHubClient::builder()
+                .token("test_token")
+                .base_url(server.uri())
+                .retry(RateLimitConfig { max_retries: 0, ..RateLimitConfig::default() })
+                .fault_injection(faults.clone())
+                .build()
+                .unwrap();
+            (client, faults)
+        };
+
+        let (failing, faults) = client("http-500=1");
+        let error = failing.search_repositories("language:rust", None, None).await.unwrap_err();
+        assert!(error.to_string().contains("HTTP 500"));
+        assert_eq!(faults.counts().server_errors, 1);
+        assert!(server.received_requests().await.unwrap().is_empty());
+
+        let (truncating, faults) = client("truncate=1,slow=1,delay=10");
+        assert!(truncating.search_repositories("language:rust", None, None).await.is_err());
+        assert_eq!(faults.counts().truncated_bodies, 1);
+        assert_eq!(faults.counts().slow_responses, 1);
+    }
+
+    #[tokio::test]
+    async fn test_compressed_responses_are_decoded_and_counted() {
+        use flate2::write::{GzEncoder, ZlibEncoder};
+        use std::io::Write;
+
+        let body = serde_json::to_vec(&json!({
+            "total_count": 1,
+            "incomplete_results": false,
+            "items": [create_test_repository()]
+        }))
+        .unwrap();
+        let mut gzip = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(&body).unwrap();
+        let gzip = gzip.finish().unwrap();
+        let mut zlib = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        zlib.write_all(br#"{"names": ["rust"]}"#).unwrap();
+        let zlib = zlib.finish().unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search/repositories"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-encoding", "gzip")
+                    .set_body_raw(gzip.clone(), "application/json"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/topics"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-encoding", "deflate")
+                    .set_body_raw(zlib.clone(), "application/json"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        let response = client.search_repositories("rust", Some(1), Some(1)).await.unwrap();
+        assert_eq!(response.items, vec![create_test_repository()]);
+        assert_eq!(client.get_repository_topics("octocat/Hello-World").await.unwrap(), vec!["rust"]);
+
+        let usage = client.api_usage();
+        assert_eq!(usage.bytes_downloaded, (gzip.len() + zlib.len()) as u64);
+        assert_eq!(usage.bytes_decompressed, (body.len() + br#"{"names": ["rust"]}"#.len()) as u64);
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests.iter().all(|request| request.headers["accept-encoding"] == "gzip, deflate"));
+    }
+
+    #[tokio::test]
+    async fn test_streamed_bodies_are_counted_as_read() {
+        use flate2::write::DeflateEncoder;
+        use std::io::Write;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), "http://localhost".to_string()).unwrap();
+        let body = br#"{"names": ["rust"]}"#.to_vec();
+
+        // Chunked and uncompressed, without Content-Length
+        let chunks: Vec<std::io::Result<Vec<u8>>> = body.chunks(4).map(|chunk| Ok(chunk.to_vec())).collect();
+        let response = http::Response::builder()
+            .status(200)
+            .body(reqwest::Body::wrap_stream(futures::stream::iter(chunks)))
+            .unwrap();
+        let response = client.decompress(reqwest::Response::from(response)).await.unwrap();
+        assert_eq!(response.bytes().await.unwrap().as_ref(), body.as_slice());
+        let usage = client.api_usage();
+        assert_eq!((usage.bytes_downloaded, usage.bytes_decompressed), (body.len() as u64, body.len() as u64));
+
+        // Raw deflate arriving one byte at a time
+        let mut deflate = DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        deflate.write_all(&body).unwrap();
+        let deflate = deflate.finish().unwrap();
+        let chunks: Vec<std::io::Result<Vec<u8>>> = deflate.iter().map(|byte| Ok(vec![*byte])).collect();
+        let response = http::Response::builder()
+            .status(200)
+            .header("content-encoding", "deflate")
+            .body(reqwest::Body::wrap_stream(futures::stream::iter(chunks)))
+            .unwrap();
+        let response = client.decompress(reqwest::Response::from(response)).await.unwrap();
+        assert!(response.headers().get("content-encoding").is_none());
+        assert_eq!(response.bytes().await.unwrap().as_ref(), body.as_slice());
+        let usage = client.api_usage();
+        assert_eq!(usage.bytes_downloaded, (body.len() + deflate.len()) as u64);
+        assert_eq!(usage.bytes_decompressed, 2 * body.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_search_topics_success() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search/topics"))
+            .and(query_param("q", "rust"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 1,
+                "incomplete_results": false,
+                "items": [{
+                    "name": "rust",
+                    "display_name": "Rust",
+                    "curated": true,
+                    "featured": false,
+                    "score": 1.0
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        let response = client.search_topics("rust", Some(10), Some(1)).await.unwrap();
+
+        assert_eq!(response.total_count, 1);
+        assert_eq!(response.items[0].name, "rust");
+        assert!(response.items[0].curated);
+    }
+
+    #[tokio::test]
+    async fn test_search_decodes_full_page() {
+        let server = MockServer::start().await;
+        let items: Vec<Repository> = (0..100)
+            .map(|id| Repository { id, ..create_test_repository() })
+            .collect();
+        Mock::given(method("GET"))
+            .and(path("/search/repositories"))
+            .and(query_param("q", "large"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(SearchResponse {
+                total_count: 1000,
+                incomplete_results: false,
+                items: items.clone(),
+                quarantined: Vec::new(),
+            }))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/search/repositories"))
+            .and(query_param("q", "truncated"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"total_count": 1, "items": [{"id""#))
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        let response = client.search_repositories("large", Some(100), Some(1)).await.unwrap();
+        assert_eq!(response.total_count, 1000);
+        assert_eq!(response.items, items);
+
+        assert!(client.search_repositories("truncated", Some(100), Some(1)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_requests_text_matches() {
+        let server = MockServer::start().await;
+        let mut item = serde_json::to_value(create_test_repository()).unwrap();
+        item["score"] = json!(12.5);
+        item["text_matches"] = json!([{
+            "object_url": "https://api.github.com/repositories/1",
+            "object_type": "Repository",
+            "property": "description",
+            "fragment": "An async runtime for Rust",
+            "matches": [{"text": "async", "indices": [3, 8]}]
+        }]);
+        Mock::given(method("GET"))
+            .and(path("/search/repositories"))
+            .and(header("accept", "application/vnd.github.text-match+json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 1,
+                "incomplete_results": false,
+                "items": [item]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::builder()
+            .token("test_token")
+            .base_url(server.uri())
+            .text_match(true)
+            .build()
+            .unwrap();
+        let response = client.search_repositories("async in:description", None, None).await.unwrap();
+        let repo = &response.items[0];
+        assert_eq!(repo.score, Some(12.5));
+        assert_eq!(repo.text_matches.len(), 1);
+        assert_eq!(repo.text_matches[0].property, "description");
+        assert_eq!(repo.text_matches[0].matches[0].text, "async");
+        assert_eq!(repo.text_matches[0].matches[0].indices, vec![3, 8]);
+    }
+
+    #[tokio::test]
+    async fn test_api_usage_tracks_rate_limit_headers() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search/topics"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-ratelimit-resource", "search")
+                    .insert_header("x-ratelimit-limit", "30")
+                    .insert_header("x-ratelimit-remaining", "28")
+                    .insert_header("x-ratelimit-reset", "1640995200")
+                    .set_body_json(json!({"total_count": 0, "incomplete_results": false, "items": []})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/topics"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"names": []})))
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        client.search_topics("rust", None, None).await.unwrap();
+        client.clone().search_topics("rust", None, None).await.unwrap();
+        client.get_repository_topics("octocat/Hello-World").await.unwrap();
+
+        let usage = client.api_usage();
+        assert_eq!(usage.requests, 3);
+        let search = &usage.rate_limits["search"];
+        assert_eq!(search.requests, 2);
+        assert_eq!((search.limit, search.remaining), (Some(30), Some(28)));
+        assert!(search.reset_at.is_some());
+        assert!(!usage.rate_limits.contains_key("core"));
+
+        // Only responses with rate limit headers are logged
+        let log = client.take_rate_limit_log();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].endpoint, "/search/topics");
+        assert_eq!((log[0].status, log[0].resource.as_str()), (200, "search"));
+        assert_eq!((log[1].limit, log[1].remaining, log[1].used), (Some(30), Some(28), None));
+        assert!(client.take_rate_limit_log().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_paced_when_quota_runs_low() {
+        let server = MockServer::start().await;
+        let reset = (chrono::Utc::now() + chrono::Duration::seconds(60)).timestamp().to_string();
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/topics"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-ratelimit-resource", "core")
+                    .insert_header("x-ratelimit-limit", "5000")
+                    .insert_header("x-ratelimit-remaining", "10")
+                    .insert_header("x-ratelimit-reset", reset.as_str())
+                    .set_body_json(json!({"names": []})),
+            )
+            .mount(&server)
+            .await;
+
+        let paced = |pacing| {
+            GitHubClient::builder()
+                .token("test_token")
+                .base_url(server.uri())
+                .retry(RateLimitConfig { pacing, ..RateLimitConfig::default() })
+                .build()
+                .unwrap()
+        };
+
+        // 10 requests left for a minute paces at the 100 ms cap
+        let client = paced(Some(PacingPolicy { min_rate: 1.0, max_delay_ms: 100 }));
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            client.get_repository_topics("octocat/Hello-World").await.unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert!(client.api_usage().rate_limits["core"].paced_ms > 0);
+
+        let client = paced(None);
+        for _ in 0..3 {
+            client.get_repository_topics("octocat/Hello-World").await.unwrap();
+        }
+        assert_eq!(client.api_usage().rate_limits["core"].paced_ms, 0);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_fails_fast_after_server_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/topics"))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::builder()
+            .token("test_token")
+            .base_url(server.uri())
+            .circuit_breaker(CircuitBreakerConfig {
+                failure_threshold: 3,
+                cool_down: Duration::from_secs(60),
+            })
+            .build()
+            .unwrap();
+
+        for _ in 0..3 {
+            let result = client.get_repository_topics("octocat/Hello-World").await;
+            assert!(matches!(result, Err(AppError::GitHubApi { .. })));
+        }
+        // Further requests, including from clones, never reach the server
+        let result = client.clone().get_repository_topics("octocat/Hello-World").await;
+        assert!(matches!(result, Err(AppError::CircuitOpen { .. })));
+        let result = client.search_topics("rust", None, None).await;
+        assert!(matches!(result, Err(AppError::CircuitOpen { .. })));
+
+        let usage = client.api_usage();
+        assert_eq!(usage.requests, 3);
+        assert_eq!(usage.circuit_breaker_trips, 1);
+        assert_eq!(usage.circuit_breaker_rejections, 2);
+    }
+
+    /// Signs requests and retries the first server error once
+    #[derive(Debug, Default)]
+    struct SigningMiddleware {
+        statuses: std::sync::Mutex<Vec<u16>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Middleware for SigningMiddleware {
+        async fn on_request(&self, request: &mut reqwest::Request) -> Result<()> {
+            request.headers_mut().insert("x-signature", "signed".parse().unwrap());
+            Ok(())
+        }
+
+        async fn on_response(
+            &self,
+            _request: &reqwest::Request,
+            response: &reqwest::Response,
+            attempt: u32,
+        ) -> Result<MiddlewareAction> {
+            self.statuses.lock().unwrap().push(response.status().as_u16());
+            if response.status().is_server_error() && attempt == 0 {
+                return Ok(MiddlewareAction::Retry(Duration::from_millis(1)));
+            }
+            Ok(MiddlewareAction::Continue)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_middleware() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/topics"))
+            .and(header("authorization", "Bearer test_token"))
+            .and(header("x-signature", "signed"))
+            .respond_with(ResponseTemplate::new(500))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/topics"))
+            .and(header("x-signature", "signed"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"names": ["rust"]})))
+            .mount(&server)
+            .await;
+
+        let middleware = Arc::new(SigningMiddleware::default());
+        let client = GitHubClient::builder()
+            .token("test_token")
+            .base_url(server.uri())
+            .middleware(middleware.clone())
+            .build()
+            .unwrap();
+
+        let topics = client.get_repository_topics("octocat/Hello-World").await.unwrap();
+        assert_eq!(topics, vec!["rust"]);
+        assert_eq!(*middleware.statuses.lock().unwrap(), vec![500, 200]);
+        assert_eq!(client.api_usage().requests, 2);
+
+        let metrics = client.api_usage().http_metrics();
+        assert_eq!((metrics.requests, metrics.retries), (2, 1));
+        assert_eq!(metrics.rate_limit_sleep_ms, 1);
+        assert!(metrics.bytes_downloaded >= r#"{"names":["rust"]}"#.len() as u64);
+        assert!(metrics.latency_p50_ms.is_some() && metrics.latency_p95_ms >= metrics.latency_p50_ms);
+    }
+
+    #[tokio::test]
+    async fn test_get_repository_topics() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/topics"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "names": ["octocat", "atom", "electron"]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        let topics = client.get_repository_topics("octocat/Hello-World").await.unwrap();
+
+        assert_eq!(topics, vec!["octocat", "atom", "electron"]);
+    }
+
+    #[tokio::test]
+    async fn test_requests_carry_user_agent_suffix_and_request_id() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/topics"))
+            .and(header("user-agent", "github-pg-query/0.1.0 (data-team@example.com)"))
+            .and(header("x-request-id", "crawl-2024-01-02"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "names": ["rust"] })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let http_config = HttpConfig {
+            user_agent_suffix: Some("(data-team@example.com)".to_string()),
+            request_id: Some("crawl-2024-01-02".to_string()),
+            ..HttpConfig::default()
+        };
+        let client = GitHubClient::builder()
+            .token("test_token")
+            .base_url(server.uri())
+            .http_config(http_config)
+            .build()
+            .unwrap();
+
+        assert_eq!(client.get_repository_topics("octocat/Hello-World").await.unwrap(), vec!["rust"]);
+        assert_eq!(client.http_metrics().request_id.as_deref(), Some("crawl-2024-01-02"));
+    }
+
+    #[tokio::test]
+    async fn test_get_repository_topics_not_found() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/missing/topics"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Not Found"))
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        let result = client.get_repository_topics("octocat/missing").await;
+
+        assert!(matches!(result, Err(AppError::GitHubApi { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_get_repository_details() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "full_name": "octocat/Hello-World",
+                "watchers_count": 80,
+                "subscribers_count": 42,
+                "network_count": 7
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/deleted"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Not Found"))
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        let details = client.get_repository_details("octocat/Hello-World").await.unwrap();
+        assert_eq!(details, Some(RepositoryDetails { subscribers_count: 42, network_count: 7 }));
+        assert_eq!(client.get_repository_details("octocat/deleted").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_stargazers_uses_star_media_type() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/stargazers"))
+            .and(wiremock::matchers::header("Accept", "application/vnd.github.star+json"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {
+                    "starred_at": "2023-01-02T03:04:05Z",
+                    "user": {"id": 42, "login": "stargazer"}
+                }
+            ])))
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        let stargazers = client.get_stargazers("octocat/Hello-World", 100, 2).await.unwrap();
+
+        assert_eq!(stargazers.len(), 1);
+        assert_eq!(stargazers[0].user.login, "stargazer");
+        assert_eq!(stargazers[0].starred_at.to_rfc3339(), "2023-01-02T03:04:05+00:00");
+    }
+
+    #[tokio::test]
+    async fn test_get_workflows_and_latest_run() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/actions/workflows"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 1,
+                "workflows": [
+                    {"id": 1, "name": "CI", "path": ".github/workflows/ci.yml", "state": "active"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/actions/runs"))
+            .and(query_param("branch", "main"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_count": 1,
+                "workflow_runs": [{
+                    "id": 99,
+                    "name": "CI",
+                    "status": "completed",
+                    "conclusion": "success",
+                    "head_branch": "main",
+                    "created_at": "2024-01-01T00:00:00Z"
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        let workflows = client.get_workflows("octocat/Hello-World").await.unwrap();
+        let run = client
+            .get_latest_workflow_run("octocat/Hello-World", "main")
+            .await
+            .unwrap();
+
+        assert_eq!(workflows.len(), 1);
+        assert_eq!(workflows[0].path, ".github/workflows/ci.yml");
+        let ci = crate::RepositoryCi { workflows, latest_run: run };
+        assert!(ci.is_passing());
+    }
+
+    #[tokio::test]
+    async fn test_dependabot_alerts_without_permission_is_none() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/dependabot/alerts"))
+            .respond_with(
+                ResponseTemplate::new(403)
+                    .insert_header("x-ratelimit-remaining", "4999")
+                    .set_body_json(json!({"message": "Resource not accessible by integration"})),
+            )
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        let alerts = client.get_dependabot_alerts("octocat/Hello-World", 1).await.unwrap();
+
+        assert!(alerts.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dependabot_alerts_quota_exhausted_is_rate_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/dependabot/alerts"))
+            .respond_with(
+                ResponseTemplate::new(403)
+                    .insert_header("x-ratelimit-remaining", "0")
+                    .insert_header("x-ratelimit-reset", "1640995200"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        let result = client.get_dependabot_alerts("octocat/Hello-World", 1).await;
+
+        assert!(matches!(result, Err(AppError::RateLimit { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_get_branch_head_uses_link_header_for_count() {
+        let server = MockServer::start().await;
+        let link = format!(
+            "<{0}/repositories/1/commits?sha=main&per_page=1&page=2>; rel=\"next\", \
+             <{0}/repositories/1/commits?sha=main&per_page=1&page=1234>; rel=\"last\"",
+            server.uri()
+        );
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/Hello-World/commits"))
+            .and(query_param("sha", "main"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("link", link.as_str())
+                    .set_body_json(json!([{"sha": "6dcb09b5b57875f334f61aebed695e2e4193db5e"}])),
+            )
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        let head = client.get_branch_head("octocat/Hello-World", "main").await.unwrap().unwrap();
+
+        assert_eq!(head.sha, "6dcb09b5b57875f334f61aebed695e2e4193db5e");
+        assert_eq!(head.commit_count, 1234);
+    }
+
+    #[tokio::test]
+    async fn test_get_branch_head_empty_repository() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/octocat/empty/commits"))
+            .respond_with(ResponseTemplate::new(409).set_body_json(json!({"message": "Git Repository is empty."})))
+            .mount(&server)
+            .await;
+
+        let client = GitHubClient::with_base_url("test_token".to_string(), server.uri()).unwrap();
+        assert!(client.get_branch_head("octocat/empty", "main").await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parse_last_page() {
+        assert_eq!(
+            parse_last_page("<https://api.github.com/x?per_page=1&page=2>; rel=\"next\", <https://api.github.com/x?per_page=1&page=57>; rel=\"last\""),
+            Some(57)
+        );
+        assert_eq!(parse_last_page("<https://api.github.com/x?page=2>; rel=\"next\""), None);
+    }
+}
+
+
+// Substituting the GitHubApi trait for a real client
+#[cfg(test)]
+mod api_tests {
+    use super::*;
+
+    /// Serves one repository and treats everything else as missing
+    struct StubApi;
+
+    #[async_trait::async_trait]
+    impl GitHubApi for StubApi {
+        async fn search_repositories(&self, _query: &str, _per_page: Option<u32>, _page: Option<u32>)
+            -> Result<SearchResponse> {
+            Ok(SearchResponse { total_count: 1, incomplete_results: false, items: vec![create_test_repository()], quarantined: Vec::new() })
+        }
+
+        async fn search_topics(&self, _query: &str, _per_page: Option<u32>, _page: Option<u32>)
+            -> Result<TopicSearchResponse> {
+            Err(AppError::internal("not stubbed"))
+        }
+
+        async fn get_repository_topics(&self, _full_name: &str) -> Result<Vec<String>> {
+            Ok(vec!["rust".to_string()])
+        }
+
+        async fn get_repository_details(&self, full_name: &str) -> Result<Option<RepositoryDetails>> {
+            Ok((full_name == "octocat/Hello-World").then_some(RepositoryDetails {
+                subscribers_count: 10,
+                network_count: 3,
+            }))
+        }
+
+        async fn get_stargazers(&self, _full_name: &str, _per_page: u32, _page: u32) -> Result<Vec<Stargazer>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_workflows(&self, _full_name: &str) -> Result<Vec<Workflow>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_latest_workflow_run(&self, _full_name: &str, _branch: &str) -> Result<Option<WorkflowRun>> {
+            Ok(None)
+        }
+
+        async fn get_security_advisories(&self, _full_name: &str) -> Result<Vec<SecurityAdvisory>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_dependabot_alerts(&self, _full_name: &str, _page: u32) -> Result<Option<Vec<DependabotAlert>>> {
+            Ok(None)
+        }
+
+        async fn get_root_files(&self, _full_name: &str) -> Result<Option<Vec<String>>> {
+            Ok(Some(vec!["Cargo.toml".to_string()]))
+        }
+
+        async fn get_branch_head(&self, _full_name: &str, _branch: &str) -> Result<Option<BranchHead>> {
+            Ok(None)
+        }
+
+        async fn validate_token(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get_rate_limit(&self) -> Result<RateLimitStatus> {
+            Err(AppError::internal("not stubbed"))
+        }
+    }
+
+    /// Orchestration written against the trait rather than the client
+    async fn stored_watchers(api: &dyn GitHubApi, query: &str) -> Result<Vec<(String, i64)>> {
+        let mut watchers = Vec::new();
+        for repo in api.search_repositories(query, None, None).await?.items {
+            if let Some(details) = api.get_repository_details(&repo.full_name).await? {
+                watchers.push((repo.full_name, details.subscribers_count));
+            }
+        }
+        Ok(watchers)
+    }
+
+    #[tokio::test]
+    async fn test_stub_api_substitutes_for_client() {
+        let watchers = stored_watchers(&StubApi, "language:rust").await.unwrap();
+        assert_eq!(watchers, vec![("octocat/Hello-World".to_string(), 10)]);
+
+        let api: Box<dyn GitHubApi> = Box::new(StubApi);
+        assert!(api.validate_token().await.is_ok());
+        assert!(api.get_rate_limit().await.is_err());
+
+        // The real client implements the same trait
+        let client = GitHubClient::new("test_token".to_string()).unwrap();
+        let _api: &dyn GitHubApi = &client;
+    }
+}
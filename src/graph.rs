@@ -0,0 +1,163 @@
+//! # Graph Module
+//!
+//! Topic co-occurrence graphs of a snapshot, written by `analyze topics`
+//! as GraphML for Gephi or DOT for Graphviz. Each topic is a node weighted
+//! by the repositories that carry it; two topics are joined by an edge
+//! weighted by the repositories that carry both.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{AppError, Result};
+
+/// File format of an exported graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// GraphML, read by Gephi, Cytoscape, and networkx
+    GraphMl,
+    /// Graphviz DOT
+    Dot,
+}
+
+impl GraphFormat {
+    /// All supported formats
+    pub const ALL: &'static [GraphFormat] = &[GraphFormat::GraphMl, GraphFormat::Dot];
+
+    /// Name used on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GraphFormat::GraphMl => "graphml",
+            GraphFormat::Dot => "dot",
+        }
+    }
+}
+
+impl fmt::Display for GraphFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for GraphFormat {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        GraphFormat::ALL
+            .iter()
+            .copied()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| {
+                AppError::configuration(format!("Unknown graph format '{}' (supported: graphml, dot)", value))
+            })
+    }
+}
+
+/// Topics of a snapshot and how often they appear together
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TopicGraph {
+    /// Topics with the number of repositories carrying each
+    pub nodes: Vec<(String, i64)>,
+    /// Topic pairs, in name order within the pair, with the number of repositories carrying both
+    pub edges: Vec<(String, String, i64)>,
+}
+
+impl TopicGraph {
+    /// The graph in `format`
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::GraphMl => self.to_graphml(),
+            GraphFormat::Dot => self.to_dot(),
+        }
+    }
+
+    fn to_graphml(&self) -> String {
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"repositories\" for=\"node\" attr.name=\"repositories\" attr.type=\"long\"/>\n",
+            "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"long\"/>\n",
+            "  <graph id=\"topics\" edgedefault=\"undirected\">\n",
+        ));
+        for (topic, repositories) in &self.nodes {
+            out.push_str(&format!(
+                "    <node id=\"{}\"><data key=\"repositories\">{}</data></node>\n",
+                xml_escape(topic),
+                repositories
+            ));
+        }
+        for (source, target, weight) in &self.edges {
+            out.push_str(&format!(
+                "    <edge source=\"{}\" target=\"{}\"><data key=\"weight\">{}</data></edge>\n",
+                xml_escape(source),
+                xml_escape(target),
+                weight
+            ));
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    fn to_dot(&self) -> String {
+        let mut out = String::from("graph topics {\n");
+        for (topic, repositories) in &self.nodes {
+            out.push_str(&format!("  {} [repositories={}];\n", dot_id(topic), repositories));
+        }
+        for (source, target, weight) in &self.edges {
+            out.push_str(&format!("  {} -- {} [weight={}];\n", dot_id(source), dot_id(target), weight));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Escape text for an XML attribute value
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Quoted DOT identifier; topics may start with a digit or contain `-`
+fn dot_id(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> TopicGraph {
+        TopicGraph {
+            nodes: vec![("rust".to_string(), 3), ("web".to_string(), 2), ("c++".to_string(), 1)],
+            edges: vec![("rust".to_string(), "web".to_string(), 2)],
+        }
+    }
+
+    #[test]
+    fn test_graph_format_from_str() {
+        assert_eq!("graphml".parse::<GraphFormat>().unwrap(), GraphFormat::GraphMl);
+        assert_eq!("dot".parse::<GraphFormat>().unwrap(), GraphFormat::Dot);
+        assert!("gexf".parse::<GraphFormat>().is_err());
+    }
+
+    #[test]
+    fn test_render_dot() {
+        assert_eq!(
+            graph().render(GraphFormat::Dot),
+            "graph topics {\n  \"rust\" [repositories=3];\n  \"web\" [repositories=2];\n  \"c++\" [repositories=1];\n  \"rust\" -- \"web\" [weight=2];\n}\n"
+        );
+        assert_eq!(dot_id("say \"hi\""), "\"say \\\"hi\\\"\"");
+    }
+
+    #[test]
+    fn test_render_graphml() {
+        let graphml = graph().render(GraphFormat::GraphMl);
+        assert!(graphml.starts_with("<?xml"));
+        assert!(graphml.contains("<node id=\"rust\"><data key=\"repositories\">3</data></node>"));
+        assert!(graphml.contains("<edge source=\"rust\" target=\"web\"><data key=\"weight\">2</data></edge>"));
+        assert!(graphml.ends_with("</graphml>\n"));
+        assert_eq!(xml_escape("a<b & \"c\""), "a&lt;b &amp; &quot;c&quot;");
+    }
+}
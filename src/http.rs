@@ -0,0 +1,188 @@
+//! # HTTP Module
+//!
+//! Shared reqwest client settings used by the GitHub and Scorecard clients,
+//! so slow enterprise proxies and large result pages can be accommodated
+//! without code changes.
+//!
+//! `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY` are honored automatically; an
+//! explicit proxy takes precedence over the environment.
+//!
+//! A user agent suffix and an `X-Request-Id` sent with every request let
+//! GitHub support and proxy logs be matched to a specific crawl.
+
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Certificate, Client, Proxy};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::{AppError, Result};
+
+/// User agent sent with every request
+pub const USER_AGENT: &str = "github-pg-query/0.1.0";
+
+/// Header carrying [`HttpConfig::request_id`]
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// HTTP client configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpConfig {
+    /// Total time allowed for a request, including reading the body
+    pub timeout: Duration,
+    /// Time allowed to establish a connection
+    pub connect_timeout: Duration,
+    /// How long idle pooled connections are kept alive (`None` keeps them indefinitely)
+    pub pool_idle_timeout: Option<Duration>,
+    /// Maximum idle connections kept per host
+    pub pool_max_idle_per_host: usize,
+    /// Proxy URL for all requests, overriding the proxy environment variables
+    pub proxy: Option<String>,
+    /// PEM file with an additional trusted root certificate
+    pub ca_cert: Option<PathBuf>,
+    /// Skip TLS certificate verification (self-signed GitHub Enterprise only)
+    pub insecure: bool,
+    /// Appended to [`USER_AGENT`], e.g. a team name or contact address
+    pub user_agent_suffix: Option<String>,
+    /// Sent as `X-Request-Id` with every request of the run
+    pub request_id: Option<String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(10),
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            pool_max_idle_per_host: 8,
+            proxy: None,
+            ca_cert: None,
+            insecure: false,
+            user_agent_suffix: None,
+            request_id: None,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// [`USER_AGENT`] followed by the configured suffix
+    pub fn user_agent(&self) -> String {
+        match &self.user_agent_suffix {
+            Some(suffix) => format!("{} {}", USER_AGENT, suffix),
+            None => USER_AGENT.to_string(),
+        }
+    }
+
+    /// Build a reqwest client with these settings
+    pub fn build_client(&self) -> Result<Client> {
+        let user_agent = HeaderValue::from_str(&self.user_agent()).map_err(|_| {
+            AppError::configuration(format!(
+                "Invalid user agent suffix '{}'",
+                self.user_agent_suffix.as_deref().unwrap_or_default()
+            ))
+        })?;
+        let mut headers = HeaderMap::new();
+        if let Some(request_id) = &self.request_id {
+            let value = HeaderValue::from_str(request_id)
+                .map_err(|_| AppError::configuration(format!("Invalid request ID '{}'", request_id)))?;
+            headers.insert(REQUEST_ID_HEADER, value);
+        }
+
+        let mut builder = Client::builder()
+            .timeout(self.timeout)
+            .connect_timeout(self.connect_timeout)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .user_agent(user_agent)
+            .default_headers(headers);
+
+        if let Some(proxy) = &self.proxy {
+            let proxy = Proxy::all(proxy)
+                .map_err(|e| AppError::configuration(format!("Invalid proxy URL '{}': {}", proxy, e)))?;
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(path) = &self.ca_cert {
+            let pem = std::fs::read(path).map_err(|e| {
+                AppError::configuration(format!("Failed to read CA certificate {}: {}", path.display(), e))
+            })?;
+            let certificate = Certificate::from_pem(&pem).map_err(|e| {
+                AppError::configuration(format!("Invalid CA certificate {}: {}", path.display(), e))
+            })?;
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        if self.insecure {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        builder
+            .build()
+            .map_err(|e| AppError::configuration(format!("Failed to create HTTP client: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_http_config() {
+        let config = HttpConfig::default();
+        assert_eq!(config.timeout, Duration::from_secs(30));
+        assert_eq!(config.connect_timeout, Duration::from_secs(10));
+        assert!(config.build_client().is_ok());
+    }
+
+    #[test]
+    fn test_build_client_without_idle_timeout() {
+        let config = HttpConfig {
+            pool_idle_timeout: None,
+            pool_max_idle_per_host: 0,
+            ..HttpConfig::default()
+        };
+        assert!(config.build_client().is_ok());
+    }
+
+    #[test]
+    fn test_build_client_with_proxy() {
+        let config = HttpConfig {
+            proxy: Some("http://proxy.internal:3128".to_string()),
+            insecure: true,
+            ..HttpConfig::default()
+        };
+        assert!(config.build_client().is_ok());
+
+        let config = HttpConfig {
+            proxy: Some("not a url".to_string()),
+            ..HttpConfig::default()
+        };
+        assert!(config.build_client().is_err());
+    }
+
+    #[test]
+    fn test_user_agent_and_request_id() {
+        let config = HttpConfig {
+            user_agent_suffix: Some("(data-team@example.com)".to_string()),
+            request_id: Some("crawl-2024-01-02".to_string()),
+            ..HttpConfig::default()
+        };
+        assert_eq!(config.user_agent(), "github-pg-query/0.1.0 (data-team@example.com)");
+        assert_eq!(HttpConfig::default().user_agent(), USER_AGENT);
+        assert!(config.build_client().is_ok());
+
+        let config = HttpConfig {
+            request_id: Some("line\nbreak".to_string()),
+            ..HttpConfig::default()
+        };
+        assert!(config.build_client().is_err());
+    }
+
+    #[test]
+    fn test_build_client_with_missing_ca_cert() {
+        let config = HttpConfig {
+            ca_cert: Some(PathBuf::from("/nonexistent/ca.pem")),
+            ..HttpConfig::default()
+        };
+        let error = config.build_client().unwrap_err();
+        assert!(error.to_string().contains("Failed to read CA certificate"));
+    }
+}
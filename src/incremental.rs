@@ -0,0 +1,116 @@
+//! # Incremental Re-crawl Module
+//!
+//! Support for `--incremental <previous_table>`: narrows a repeat run of the
+//! same query to repositories pushed since the previous snapshot and drops
+//! results whose `updated_at` has not moved.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::{AppError, DatabaseManager, Repository, Result};
+
+/// State of a previous snapshot used as the baseline for an incremental run
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalBaseline {
+    /// Table the baseline was loaded from
+    pub table_name: String,
+    /// Most recent fetch time recorded in the baseline table
+    pub last_fetched_at: Option<DateTime<Utc>>,
+    /// Known `updated_at` per github_id
+    pub updated_at: HashMap<i64, DateTime<Utc>>,
+}
+
+impl IncrementalBaseline {
+    /// Load the baseline from an existing snapshot table
+    pub async fn load(db: &DatabaseManager, table_name: &str) -> Result<Self> {
+        DatabaseManager::validate_table_name(table_name)?;
+        if !db.table_exists(table_name).await? {
+            return Err(AppError::configuration(format!(
+                "Incremental baseline table {} does not exist",
+                table_name
+            )));
+        }
+
+        Ok(Self {
+            table_name: table_name.to_string(),
+            last_fetched_at: db.get_last_fetched_at(table_name).await?,
+            updated_at: db.get_updated_at_map(table_name).await?,
+        })
+    }
+
+    /// Append a `pushed:>` qualifier unless the query already constrains `pushed`
+    pub fn qualify_query(&self, query: &str) -> String {
+        match self.last_fetched_at {
+            Some(since) if !query.contains("pushed:") => {
+                format!("{} pushed:>{}", query, since.format("%Y-%m-%dT%H:%M:%SZ"))
+            }
+            _ => query.to_string(),
+        }
+    }
+
+    /// Keep only repositories that are new or whose `updated_at` advanced
+    ///
+    /// Returns the changed repositories and the number of skipped ones.
+    pub fn filter_changed(&self, repositories: Vec<Repository>) -> (Vec<Repository>, usize) {
+        let total = repositories.len();
+        let changed: Vec<Repository> = repositories
+            .into_iter()
+            .filter(|repo| match self.updated_at.get(&repo.id) {
+                Some(previous) => repo.updated_at > *previous,
+                None => true,
+            })
+            .collect();
+        let skipped = total - changed.len();
+        (changed, skipped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baseline() -> IncrementalBaseline {
+        IncrementalBaseline {
+            table_name: "repos_20240101000000".to_string(),
+            last_fetched_at: Some("2024-01-01T12:30:00Z".parse().unwrap()),
+            updated_at: HashMap::from([(1, "2024-01-01T00:00:00Z".parse().unwrap())]),
+        }
+    }
+
+    #[test]
+    fn test_qualify_query_appends_pushed() {
+        assert_eq!(
+            baseline().qualify_query("language:rust"),
+            "language:rust pushed:>2024-01-01T12:30:00Z"
+        );
+    }
+
+    #[test]
+    fn test_qualify_query_keeps_existing_pushed() {
+        let query = "language:rust pushed:>2023-06-01";
+        assert_eq!(baseline().qualify_query(query), query);
+        assert_eq!(IncrementalBaseline::default().qualify_query("rust"), "rust");
+    }
+
+    #[test]
+    fn test_filter_changed() {
+        let template = crate::models::test_repository(1, "o/r");
+
+        let unchanged = template.clone();
+        let mut updated = template.clone();
+        updated.updated_at = "2024-02-01T00:00:00Z".parse().unwrap();
+        let mut older = template.clone();
+        older.updated_at = "2023-12-01T00:00:00Z".parse().unwrap();
+        let mut new_repo = template;
+        new_repo.id = 2;
+
+        let (changed, skipped) = baseline().filter_changed(vec![unchanged, new_repo]);
+        assert_eq!(skipped, 1);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].id, 2);
+
+        let (changed, skipped) = baseline().filter_changed(vec![updated.clone(), older]);
+        assert_eq!(changed, vec![updated]);
+        assert_eq!(skipped, 1);
+    }
+}
@@ -1,11 +1,57 @@
 pub mod models;
 pub mod errors;
+pub mod redact;
+pub mod http;
 pub mod github;
+pub mod circuit_breaker;
+pub mod middleware;
 pub mod database;
 pub mod cli;
+pub mod enrichment;
+pub mod scorecard;
+pub mod incremental;
+pub mod report;
+pub mod notify;
+pub mod telemetry;
+pub mod export;
+pub mod archive;
+pub mod sink;
+pub mod server;
+pub mod template;
+pub mod qualifiers;
+pub mod cache;
+pub mod recording;
+pub mod chaos;
+pub mod category;
+pub mod graph;
+pub mod alerts;
+pub mod audit;
 
 pub use models::*;
 pub use errors::*;
+pub use redact::*;
+pub use http::*;
 pub use github::*;
+pub use circuit_breaker::*;
+pub use middleware::*;
 pub use database::*;
-pub use cli::*;
\ No newline at end of file
+pub use cli::*;
+pub use enrichment::*;
+pub use scorecard::*;
+pub use incremental::*;
+pub use report::*;
+pub use notify::*;
+pub use telemetry::*;
+pub use export::*;
+pub use archive::*;
+pub use sink::*;
+pub use server::*;
+pub use template::*;
+pub use qualifiers::*;
+pub use cache::*;
+pub use recording::*;
+pub use chaos::*;
+pub use category::*;
+pub use graph::*;
+pub use alerts::*;
+pub use audit::*;
\ No newline at end of file
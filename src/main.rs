@@ -39,15 +39,23 @@
 //! detailed setup instructions.
 
 use github_pg_query::{
-    CliConfig, DatabaseManager, GitHubClient, ProgressIndicator, 
-    QueryMetadata, Result
+    enrich_details, enrich_repositories, exit_code, AppError, CliCommand, CliConfig, DatabaseManager, GitHubClient, HistoryFilter, IncompletePolicy,
+    IncrementalBaseline, Notifier, OutputStyle, Telemetry, ProgressIndicator, QueryMetadata, RepositoryPages, Result, RunReport, SearchResponse,
+    TableStats, Distribution, histogram_lines, export_to_clickhouse, ClickHouseExporter, ExportFormat,
+    archive_table, restore_archive, ArchiveLocation, connect_sink, store_pages, StorageBackend,
+    bind_and_serve, ProgressEvent, Redacted, read_queries_file, SearchLauncher, SearchRequest, ServerState,
+    AlertRules, CategoryRules, CheckStatus, render_audit, EnrichmentKind, read_tracked_repositories, Repository, SchemaFormat, SearchType, SnapshotSchema, TopicSearchResponse, QueryVariables, has_placeholders, render_query, template_parameters, uses_last_run, MIGRATOR, REPOSITORY_SEARCH_ORDER, REPOSITORY_SEARCH_SORT
 };
-use std::time::Instant;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::Instrument;
+use std::time::{Duration, Instant};
 
 /// Main entry point for the GitHub PostgreSQL Query tool.
 /// 
 /// This function orchestrates the complete workflow:
-/// 1. Loads environment variables from .env file if present
+/// 1. Loads environment variables from `--env-file` or the nearest .env file
 /// 2. Parses and validates CLI arguments
 /// 3. Handles dry-run mode for configuration validation
 /// 4. Executes the main search and storage workflow
@@ -55,20 +63,210 @@ use std::time::Instant;
 /// # Error Handling
 /// 
 /// All errors are handled gracefully with user-friendly messages.
-/// The application exits with code 1 on any error.
+/// The exit code identifies the failure class (see `exit_code`): 2 for
+/// configuration, 3 for authentication, 4 for rate limits, 5 for database
+/// errors, 6 for partial results, and 1 for anything else.
 #[tokio::main]
 async fn main() {
-    // Load environment variables from .env file if it exists
-    dotenvy::dotenv().ok();
-    // Parse CLI arguments and validate configuration
+    // Parse CLI arguments (after loading --env-file or the nearest .env) and validate configuration
     let config = match CliConfig::parse() {
         Ok(config) => config,
         Err(error) => {
-            CliConfig::display_error(&error);
-            std::process::exit(1);
+            CliConfig::display_error_with_style(&error, OutputStyle::from_args(std::env::args()));
+            std::process::exit(error.exit_code());
         }
     };
 
+    let warning = config.output.marker("⚠️  ", "[WARN] ");
+    if config.http.insecure {
+        eprintln!("{}WARNING: TLS certificate verification is disabled (--insecure).", warning);
+        eprintln!("{}Responses can be intercepted or forged; use --ca-cert instead where possible.", warning);
+    }
+
+    let telemetry = match &config.otel_endpoint {
+        Some(endpoint) => match Telemetry::init(endpoint) {
+            Ok(telemetry) => Some(telemetry),
+            Err(error) => {
+                eprintln!("{}Span export disabled: {}", warning, error);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let span = tracing::info_span!(
+        "run",
+        command = ?config.command,
+        query = %Redacted(&config.search_query),
+        request_id = config.http.request_id.as_deref()
+    );
+    let code = run(&config).instrument(span).await;
+
+    // process::exit skips destructors, so flush spans explicitly
+    if let Some(telemetry) = telemetry {
+        telemetry.shutdown();
+    }
+    std::process::exit(code);
+}
+
+/// Runs the selected command and returns the process exit code.
+async fn run(config: &CliConfig) -> i32 {
+    // `run` and queries with `{{...}}` placeholders search with the rendered template
+    let rendered;
+    let templated = config.command == CliCommand::Search && has_placeholders(&config.search_query);
+    let config = if config.command == CliCommand::Run || templated {
+        match render_search_query(config).await {
+            Ok(search) => {
+                rendered = search;
+                &rendered
+            }
+            Err(error) => return fail(config, &error),
+        }
+    } else {
+        config
+    };
+
+    if config.anonymous
+        && config.replay.is_none()
+        && matches!(config.command, CliCommand::Search | CliCommand::Serve | CliCommand::Backfill)
+    {
+        ProgressIndicator::new("GitHub authentication".to_string(), config.output).warning(
+            "Running without a GitHub token: search is limited to 10 requests per minute, other API calls to 60 per hour",
+        );
+    }
+    if config.command == CliCommand::Migrate {
+        return match execute_migrate(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Doctor {
+        return match execute_doctor(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Fsck {
+        return match execute_fsck(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::List {
+        return match execute_list(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Schema {
+        return match execute_schema(config) {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::SaveQuery {
+        return match execute_save_query(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Drop {
+        return match execute_drop(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::History {
+        return match execute_history(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::HistoryExport {
+        return match execute_history_export(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Rename {
+        return match execute_rename(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Stats {
+        return match execute_stats(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::SearchLocal {
+        return match execute_search_local(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Export {
+        return match execute_export(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Archive {
+        return match execute_archive(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Restore {
+        return match execute_restore(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Serve {
+        return match execute_serve(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Backfill {
+        return match execute_backfill(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Trends {
+        return match execute_trends(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::AnalyzeOwners {
+        return match execute_analyze_owners(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::AnalyzeTopics {
+        return match execute_analyze_topics(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Compare {
+        return match execute_compare(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+    if config.command == CliCommand::Duplicates {
+        return match execute_duplicates(config).await {
+            Ok(()) => exit_code::SUCCESS,
+            Err(error) => fail(config, &error),
+        };
+    }
+
     // Display configuration summary if verbose
     if config.verbose {
         config.display_summary();
@@ -76,18 +274,854 @@ async fn main() {
 
     // Handle dry run mode
     if config.dry_run {
-        if let Err(error) = validate_dry_run(&config).await {
-            CliConfig::display_error(&error);
-            std::process::exit(1);
+        if let Err(error) = validate_dry_run(config).await {
+            return fail(config, &error);
         }
-        println!("✅ Dry run completed successfully - configuration is valid");
-        return;
+        ProgressIndicator::new("Dry run".to_string(), config.output)
+            .summary("Dry run completed successfully - configuration is valid", &[]);
+        return exit_code::SUCCESS;
     }
 
     // Execute the main workflow
-    if let Err(error) = execute_search_workflow(&config).await {
-        CliConfig::display_error(&error);
-        std::process::exit(1);
+    let (report, outcome) = execute_run(config).await;
+
+    match outcome {
+        Err(error) => fail(config, &error),
+        Ok(()) if report.is_partial() => exit_code::PARTIAL_RESULTS,
+        Ok(()) => exit_code::SUCCESS,
+    }
+}
+
+/// Runs a search, then writes the run report and sends the notification if configured.
+async fn execute_run(config: &CliConfig) -> (RunReport, Result<()>) {
+    let mut report = RunReport::new(config.search_type.as_str(), config.search_query.clone());
+    let outcome = execute_search_workflow(config, &mut report).await;
+    report.finish(&outcome);
+
+    let warning = config.output.marker("⚠️  ", "[WARN] ");
+    if let Some(path) = &config.report {
+        if let Err(error) = report.write(path) {
+            eprintln!("{}Failed to write report {}: {}", warning, path.display(), error);
+        }
+    }
+
+    if let Some(url) = &config.notify_url {
+        let sent = match Notifier::new(url.clone(), config.notify_format, &config.http) {
+            Ok(notifier) => notifier.send(&report).await,
+            Err(error) => Err(error),
+        };
+        if let Err(error) = sent {
+            eprintln!("{}Failed to send notification: {}", warning, error);
+        }
+    }
+
+    (report, outcome)
+}
+
+/// Displays an error and returns its exit code.
+fn fail(config: &CliConfig, error: &AppError) -> i32 {
+    CliConfig::display_error_with_style(error, config.output);
+    error.exit_code()
+}
+
+/// Connects for a command that only reads, through the read pool and without migrating.
+async fn connect_read_only(config: &CliConfig) -> Result<DatabaseManager> {
+    let mut options = config.database.clone();
+    options.read_only = true;
+    DatabaseManager::new_with_options(&config.database_url, &options).await
+}
+
+/// Applies pending schema migrations and reports the resulting version.
+/// 
+/// Connecting already migrates, so this mainly gives operators an explicit
+/// step to run after upgrading and a listing of the applied versions.
+async fn execute_migrate(config: &CliConfig) -> Result<()> {
+    let progress = ProgressIndicator::new("Applying database migrations".to_string(), config.output);
+    progress.start();
+
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+    let report = db_manager.migrate().await?;
+    let applied = db_manager.applied_migrations().await?;
+
+    progress.success(&format!(
+        "Schema {} is at version {}",
+        db_manager.schema(),
+        report.current_version.map_or("none".to_string(), |v| v.to_string())
+    ));
+    for version in &applied {
+        if let Some(migration) = MIGRATOR.iter().find(|m| m.version == *version) {
+            progress.info(&format!("  {:04} {}", migration.version, migration.description));
+        }
+    }
+
+    let progress = ProgressIndicator::new("Indexing older snapshots for search-local".to_string(), config.output);
+    progress.start();
+    let indexed = db_manager.ensure_search_indexes().await?;
+    progress.success(&format!("Added the full-text index to {} snapshots", indexed.len()));
+    for table_name in &indexed {
+        progress.info(&format!("  {}", table_name));
+    }
+
+    Ok(())
+}
+
+/// Reports the privilege checks of the ingest and read-only roles.
+///
+/// Excess privileges are warnings; missing privileges, or writes the
+/// read-only role could make, fail the command.
+async fn execute_doctor(config: &CliConfig) -> Result<()> {
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+    let progress = ProgressIndicator::new("Database privileges".to_string(), config.output);
+
+    let mut checks = db_manager.check_ingest_privileges().await?;
+    if config.database.read_url.is_some() {
+        checks.extend(db_manager.check_read_privileges().await?);
+    } else {
+        progress.info("DATABASE_URL_READ is not set; reads use the ingest role");
+    }
+
+    for check in &checks {
+        let line = format!("{}: {}", check.name, check.detail);
+        match check.status {
+            CheckStatus::Ok => progress.success(&line),
+            CheckStatus::Warning => progress.warning(&line),
+            CheckStatus::Failed => progress.error(&line),
+        }
+    }
+
+    let failed = checks.iter().filter(|check| check.status == CheckStatus::Failed).count();
+    if failed > 0 {
+        return Err(AppError::configuration(format!("{} database privilege checks failed", failed)));
+    }
+    Ok(())
+}
+
+/// Reports inconsistencies between snapshot tables and their metadata.
+///
+/// With `--repair`, result counts and registry entries are fixed; the
+/// command fails while problems it cannot fix remain.
+async fn execute_fsck(config: &CliConfig) -> Result<()> {
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+    let progress = ProgressIndicator::new("Snapshot integrity".to_string(), config.output);
+
+    let issues = db_manager.fsck().await?;
+    if issues.is_empty() {
+        progress.success("Result counts, the snapshot registry, and stored rows are consistent");
+        return Ok(());
+    }
+
+    let mut remaining = 0;
+    for issue in &issues {
+        if config.repair && db_manager.repair_fsck_issue(issue).await? {
+            progress.success(&format!("repaired {}", issue.message()));
+        } else {
+            remaining += 1;
+            if issue.repairable() && !config.repair {
+                progress.warning(&format!("{} (fix with --repair)", issue.message()));
+            } else {
+                progress.error(&issue.message());
+            }
+        }
+    }
+
+    if remaining > 0 {
+        return Err(AppError::validation("snapshots", format!("{} of {} integrity problems remain", remaining, issues.len())));
+    }
+    Ok(())
+}
+
+/// Prints every snapshot table, newest first, with its size, age, and query.
+async fn execute_list(config: &CliConfig) -> Result<()> {
+    let db_manager = connect_read_only(config).await?;
+    let tables = db_manager.list_snapshot_tables().await?;
+    if tables.is_empty() {
+        ProgressIndicator::new("Snapshot tables".to_string(), config.output)
+            .warning("No repository tables found; run a search first");
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now();
+    println!(
+        "  {:<32}  {:>8}  {:>9}  {:<16}  {:>6}  Query",
+        "Table", "Rows", "Size", "Created", "Age"
+    );
+    for table in &tables {
+        println!(
+            "  {:<32}  {:>8}  {:>9}  {:<16}  {:>6}  {}",
+            table.table_name,
+            table.row_count,
+            format_size(table.total_bytes),
+            table.created_at.map_or("-".to_string(), |at| at.format("%Y-%m-%d %H:%M").to_string()),
+            table.created_at.map_or("-".to_string(), |at| format_age(now - at)),
+            table.search_query.as_deref().unwrap_or("-")
+        );
+    }
+
+    Ok(())
+}
+
+/// Lists recorded runs, newest first, keeping those with every `--label` given.
+async fn execute_history(config: &CliConfig) -> Result<()> {
+    let db_manager = connect_read_only(config).await?;
+    let filter = HistoryFilter {
+        limit: Some(config.limit as i64),
+        success_only: config.success_only,
+        labels: config.labels.clone(),
+    };
+    let runs = db_manager.find_query_history(&filter).await?;
+    if runs.is_empty() {
+        ProgressIndicator::new("Query history".to_string(), config.output).warning("No recorded runs match");
+        return Ok(());
+    }
+
+    println!(
+        "  {:<16}  {:<6}  {:>7}  {:<32}  {:<24}  Query",
+        "Executed", "Status", "Results", "Table", "Labels"
+    );
+    for run in &runs {
+        let labels: Vec<String> = run.labels.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+        println!(
+            "  {:<16}  {:<6}  {:>7}  {:<32}  {:<24}  {}",
+            run.executed_at.format("%Y-%m-%d %H:%M"),
+            if run.success { "ok" } else { "failed" },
+            run.result_count,
+            run.table_name,
+            if labels.is_empty() { "-".to_string() } else { labels.join(",") },
+            run.search_query
+        );
+        if let Some(note) = &run.note {
+            println!("  {:<16}  {}", "", note);
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the audit trail of recorded runs since `--since` to `--output` or stdout.
+async fn execute_history_export(config: &CliConfig) -> Result<()> {
+    let db_manager = connect_read_only(config).await?;
+    let records = db_manager.audit_trail(config.since).await?;
+    let rendered = render_audit(&records, config.audit_format)?;
+
+    match &config.audit_output {
+        Some(path) => {
+            std::fs::write(path, rendered).map_err(|e| {
+                AppError::configuration(format!("Cannot write {}: {}", path.display(), e))
+            })?;
+            ProgressIndicator::new("Audit trail".to_string(), config.output)
+                .success(&format!("Wrote {} runs to {}", records.len(), path.display()));
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+/// Prints the definition of a snapshot table with the selected columns, without connecting.
+fn execute_schema(config: &CliConfig) -> Result<()> {
+    let table_name = config
+        .table
+        .clone()
+        .unwrap_or_else(|| {
+            format!(
+                "{}repos_{}",
+                config.database.table_prefix,
+                config.database.table_timestamp.format(chrono::Utc::now(), false)
+            )
+        });
+    let schema = SnapshotSchema::new(&config.database, &table_name)?;
+    match config.schema_format {
+        SchemaFormat::Sql => print!("{}", schema.to_sql()),
+        SchemaFormat::Json => println!("{}", serde_json::to_string_pretty(&schema.to_json())?),
+    }
+    Ok(())
+}
+
+/// Stores the `save-query` template under its name, replacing an older one.
+async fn execute_save_query(config: &CliConfig) -> Result<()> {
+    let name = config.saved_query.as_deref().unwrap_or_default();
+    let template = config.query_template.as_deref().unwrap_or_default();
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+    let saved = db_manager.save_query(name, template).await?;
+
+    let parameters = template_parameters(&saved.template)?;
+    let usage = parameters
+        .iter()
+        .map(|parameter| format!(" --param {}=<value>", parameter))
+        .collect::<String>();
+    ProgressIndicator::new("Saved query".to_string(), config.output)
+        .success(&format!("Saved '{}'; run it with: github-pg-query run {}{}", saved.name, saved.name, usage));
+    Ok(())
+}
+
+/// Fills in the placeholders of the search query, or of the saved query named
+/// by `run`, and returns a search configuration for the result.
+async fn render_search_query(config: &CliConfig) -> Result<CliConfig> {
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+    let template = match (config.command, config.saved_query.as_deref()) {
+        (CliCommand::Run, Some(name)) => db_manager
+            .get_saved_query(name)
+            .await?
+            .ok_or_else(|| AppError::configuration(format!("No saved query named '{}'; create it with save-query", name)))?
+            .template,
+        _ => config.search_query.clone(),
+    };
+
+    let mut variables = QueryVariables {
+        params: config.query_params.clone(),
+        ..QueryVariables::new()
+    };
+    if uses_last_run(&template)? {
+        variables.last_run = db_manager
+            .last_successful_run(config.saved_query.as_deref(), &template)
+            .await?;
+    }
+
+    let mut search = config.clone();
+    search.command = CliCommand::Search;
+    search.search_query = render_query(&template, &variables)?;
+    search.query_template = Some(template);
+    search.validate()?;
+    Ok(search)
+}
+
+/// Drops the snapshot tables matching `drop`'s pattern after confirmation.
+///
+/// Refuses the whole batch when any table is read by a view other than its
+/// analytics views; with `--dry-run` only the matches are listed.
+async fn execute_drop(config: &CliConfig) -> Result<()> {
+    let pattern = config.drop_pattern.as_deref().unwrap_or_default();
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+    let tables = db_manager.find_snapshot_tables(pattern).await?;
+    if tables.is_empty() {
+        return Err(AppError::configuration(format!("No snapshot tables match '{}'", pattern)));
+    }
+
+    let mut referenced = Vec::new();
+    for table in &tables {
+        let views = db_manager.dependent_views(table).await?;
+        if !views.is_empty() {
+            referenced.push(format!("{} (used by {})", table, views.join(", ")));
+        }
+    }
+    if !referenced.is_empty() {
+        return Err(AppError::configuration(format!(
+            "Refusing to drop tables referenced by saved views: {}",
+            referenced.join("; ")
+        )));
+    }
+
+    let verb = if config.dry_run { "Would drop" } else { "Dropping" };
+    println!("{} {} table(s):", verb, tables.len());
+    for table in &tables {
+        println!("  {}", table);
+    }
+    if config.dry_run {
+        return Ok(());
+    }
+    if !config.assume_yes && !confirm(&format!("Drop {} table(s)? [y/N] ", tables.len()))? {
+        return Err(AppError::configuration("Drop cancelled"));
+    }
+
+    let progress = ProgressIndicator::new("Dropping snapshot tables".to_string(), config.output);
+    progress.start();
+    for table in &tables {
+        db_manager.drop_table(table).await?;
+    }
+    progress.success(&format!("Dropped {} table(s)", tables.len()));
+    Ok(())
+}
+
+/// Renames a snapshot table together with its registry entries, history, and analytics views.
+async fn execute_rename(config: &CliConfig) -> Result<()> {
+    let table_name = config.table.as_deref().unwrap_or_default();
+    let new_name = config.rename_to.as_deref().unwrap_or_default();
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+
+    let progress = ProgressIndicator::new(format!("Renaming {} to {}", table_name, new_name), config.output);
+    progress.start();
+    let views = db_manager.rename_table(table_name, new_name).await?;
+    progress.success(&format!("Renamed {} to {}", table_name, new_name));
+    for view in &views {
+        progress.info(&format!("  Rebuilt view {}", view));
+    }
+    Ok(())
+}
+
+/// Asks a yes/no question on the terminal; without one, pass `--yes` instead.
+fn confirm(prompt: &str) -> Result<bool> {
+    use std::io::{IsTerminal, Write};
+
+    if !std::io::stdin().is_terminal() {
+        return Err(AppError::configuration("Refusing to drop without confirmation; pass --yes"));
+    }
+    print!("{}", prompt);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Formats a byte count with a binary unit, e.g. `1.5 MiB`.
+fn format_size(bytes: i64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Formats an age in its largest whole unit, e.g. `3d`.
+fn format_age(age: chrono::Duration) -> String {
+    if age.num_days() > 0 {
+        format!("{}d", age.num_days())
+    } else if age.num_hours() > 0 {
+        format!("{}h", age.num_hours())
+    } else {
+        format!("{}m", age.num_minutes().max(0))
+    }
+}
+
+/// Prints statistics for a stored repository snapshot.
+/// 
+/// Uses the table named on the command line, or the most recent
+/// `repos_*` table when none is given.
+async fn execute_stats(config: &CliConfig) -> Result<()> {
+    let db_manager = connect_read_only(config).await?;
+    let table_name = resolve_snapshot_table(&db_manager, config).await?;
+    let stats = db_manager.get_table_stats_with_limit(&table_name, config.top).await?;
+    display_table_stats(&stats);
+
+    let stars = db_manager.get_star_distribution(&table_name, &config.percentiles).await?;
+    display_distribution("Stars", &stars, config.output);
+    let forks = db_manager.get_fork_distribution(&table_name, &config.percentiles).await?;
+    display_distribution("Forks", &forks, config.output);
+
+    Ok(())
+}
+
+/// Searches stored repository names and descriptions without calling GitHub.
+async fn execute_search_local(config: &CliConfig) -> Result<()> {
+    let db_manager = connect_read_only(config).await?;
+    // Snapshots from older versions lack the index until `migrate` adds it; searching works without it, just slower
+    let table_name = resolve_snapshot_table(&db_manager, config).await?;
+
+    let hits = db_manager
+        .search_local(&table_name, &config.search_query, config.limit as i64)
+        .await?;
+    if hits.is_empty() {
+        ProgressIndicator::new("Local search".to_string(), config.output)
+            .warning(&format!("No repositories in {} match \"{}\"", table_name, config.search_query));
+        return Ok(());
+    }
+
+    println!("{} matches in {}", hits.len(), table_name);
+    for hit in &hits {
+        println!();
+        println!(
+            "{} ({} stars{})",
+            hit.full_name,
+            hit.stargazers_count,
+            hit.language.as_deref().map_or(String::new(), |language| format!(", {}", language))
+        );
+        if let Some(description) = &hit.description {
+            println!("  {}", description);
+        }
+        println!("  {}", hit.html_url);
+    }
+
+    Ok(())
+}
+
+/// Copies a snapshot table to the `--format` target at `--url`.
+async fn execute_export(config: &CliConfig) -> Result<()> {
+    let db_manager = connect_read_only(config).await?;
+    let source = resolve_snapshot_table(&db_manager, config).await?;
+    let target = config.export_table.clone().unwrap_or_else(|| source.clone());
+    let url = config
+        .export_url
+        .as_deref()
+        .ok_or_else(|| AppError::configuration("--url is required"))?;
+
+    let progress = ProgressIndicator::new(
+        format!("Exporting {} to {} table {}", source, config.export_format, target),
+        config.output
+    );
+    progress.start();
+
+    let exported = match config.export_format {
+        ExportFormat::ClickHouse => {
+            let exporter = ClickHouseExporter::new(url, &config.http)?;
+            export_to_clickhouse(&db_manager, &exporter, &source, &target, |rows| {
+                progress.update(&format!("{} rows sent", rows));
+            })
+            .await?
+        }
+    };
+
+    progress.success(&format!("Exported {} rows to {}", exported, target));
+    Ok(())
+}
+
+/// Uploads a snapshot table to object storage and records the archive URL.
+async fn execute_archive(config: &CliConfig) -> Result<()> {
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+    let table_name = resolve_snapshot_table(&db_manager, config).await?;
+    let destination = config
+        .archive_to
+        .as_deref()
+        .ok_or_else(|| AppError::configuration("--to is required"))?;
+    let location = ArchiveLocation::parse(destination)?;
+
+    let progress = ProgressIndicator::new(
+        format!("Archiving {} as {}", table_name, config.archive_format),
+        config.output
+    );
+    progress.start();
+
+    let outcome = archive_table(&db_manager, &location, &table_name, config.archive_format).await?;
+    progress.success(&format!(
+        "Archived {} rows ({} bytes) to {}",
+        outcome.rows, outcome.bytes, outcome.url
+    ));
+
+    Ok(())
+}
+
+/// Recreates a snapshot table from an archive written by `archive`.
+async fn execute_restore(config: &CliConfig) -> Result<()> {
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+    let source = config
+        .restore_from
+        .as_deref()
+        .ok_or_else(|| AppError::configuration("An archive URL is required"))?;
+
+    let progress = ProgressIndicator::new(format!("Restoring {}", source), config.output);
+    progress.start();
+
+    let outcome = restore_archive(&db_manager, source, config.table.as_deref()).await?;
+    progress.success(&format!(
+        "Restored {} rows into {}",
+        outcome.rows.total(),
+        outcome.table_name
+    ));
+
+    Ok(())
+}
+
+/// Fetches repository details for snapshot rows that do not have them yet.
+///
+/// Rows are marked as they complete, so rerunning after an interruption
+/// only looks up the remainder.
+async fn execute_backfill(config: &CliConfig) -> Result<()> {
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+    let table_name = resolve_snapshot_table(&db_manager, config).await?;
+    let client = build_github_client(config)?;
+
+    let progress = ProgressIndicator::new(format!("Backfilling repository details in {}", table_name), config.output);
+    progress.start();
+
+    let outcome = enrich_details(&client, &db_manager, &table_name, config.details_concurrency).await;
+    save_rate_limit_log(&db_manager, &client, None, &progress).await;
+    let summary = outcome?;
+    progress.success(&format!(
+        "Updated {} repositories ({} no longer available, {} failed)",
+        summary.enriched, summary.skipped, summary.failed
+    ));
+
+    Ok(())
+}
+
+/// Serves the REST API, running requested searches with the server's own options.
+async fn execute_serve(config: &CliConfig) -> Result<()> {
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+
+    let server_config = config.clone();
+    let launcher: SearchLauncher = Arc::new(move |request: SearchRequest| {
+        let mut config = server_config.clone();
+        config.command = CliCommand::Search;
+        config.search_query = request.query;
+        config.per_page = request.per_page.unwrap_or(config.per_page);
+        config.pages = request.pages.unwrap_or(config.pages);
+        // Concurrent runs would interleave their progress output
+        config.output.quiet = true;
+        config.output.json = false;
+        Box::pin(async move { execute_run(&config).await.0 })
+    });
+
+    let state = ServerState::new(db_manager, launcher, config.jobs);
+    if let Some(path) = &config.queries_file {
+        let queries = read_queries_file(path)?;
+        for query in &queries {
+            state.enqueue(&SearchRequest { query: query.clone(), per_page: None, pages: None }).await?;
+        }
+        ProgressIndicator::new("Queueing searches".to_string(), config.output)
+            .success(&format!("Queued {} searches from {}", queries.len(), path.display()));
+    }
+
+    ProgressIndicator::new("Starting API server".to_string(), config.output).success(&format!(
+        "Listening on http://{} ({} concurrent searches)",
+        config.serve_addr, config.jobs.concurrency
+    ));
+    bind_and_serve(config.serve_addr, state).await
+}
+
+/// Creates a GitHub client with the HTTP, retry, and circuit breaker options from the command line.
+fn build_github_client(config: &CliConfig) -> Result<GitHubClient> {
+    let builder = if config.anonymous {
+        GitHubClient::builder().anonymous()
+    } else {
+        GitHubClient::builder().token(config.github_token.clone())
+    };
+    let builder = builder
+        .http_config(config.http.clone())
+        .retry(config.rate_limit.clone())
+        .circuit_breaker(config.circuit_breaker)
+        .text_match(config.text_match);
+    let builder = match config.response_cache() {
+        Some(cache) => builder.response_cache(cache),
+        None => builder,
+    };
+    let builder = match config.api_recording() {
+        Some(recording) => builder.recording(recording),
+        None => builder,
+    };
+    match &config.chaos {
+        Some(faults) => builder.fault_injection(faults.clone()).build(),
+        None => builder.build(),
+    }
+}
+
+/// Returns the table named with `--table`/`TABLE`, or the latest repository snapshot.
+async fn resolve_snapshot_table(db_manager: &DatabaseManager, config: &CliConfig) -> Result<String> {
+    let table_name = match &config.table {
+        Some(table) => table.clone(),
+        None => db_manager
+            .list_repository_tables()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::configuration("No repository tables found; run a search first"))?,
+    };
+
+    if !db_manager.table_exists(&table_name).await? {
+        return Err(AppError::configuration(format!("Table {} does not exist", table_name)));
+    }
+    Ok(table_name)
+}
+
+/// Prints a [`TableStats`] summary to stdout.
+fn display_table_stats(stats: &TableStats) {
+    let total = stats.total_repositories;
+    let share = |count: i64| if total > 0 { count as f64 * 100.0 / total as f64 } else { 0.0 };
+    let date = |at: Option<chrono::DateTime<chrono::Utc>>| {
+        at.map_or("-".to_string(), |at| at.format("%Y-%m-%d").to_string())
+    };
+
+    println!("Table: {}", stats.table_name);
+    println!("  Repositories: {}", total);
+    println!("  Owners:       {}", stats.unique_owners);
+    println!("  Languages:    {}", stats.unique_languages);
+    println!(
+        "  Stars:        avg {:.1}, median {:.1}, max {}",
+        stats.avg_stars, stats.median_stars, stats.max_stars
+    );
+    println!(
+        "  Forks:        {:.1}%   Archived: {:.1}%",
+        stats.fork_ratio * 100.0,
+        stats.archived_ratio * 100.0
+    );
+    println!("  Created:      {} to {}", date(stats.oldest_repo), date(stats.newest_repo));
+
+    println!();
+    println!("Top languages:");
+    for (language, count) in &stats.top_languages {
+        println!("  {:<20} {:>8} {:>6.1}%", language, count, share(*count));
+    }
+
+    println!();
+    println!("Licenses:");
+    for (license, count) in &stats.licenses {
+        println!("  {:<20} {:>8} {:>6.1}%", license, count, share(*count));
+    }
+}
+
+/// Prints how successive snapshots of a query changed.
+/// 
+/// Snapshots come from the `previous_query_id` chain ending at `--query-id`,
+/// or from every successful run of `--search-query`.
+async fn execute_trends(config: &CliConfig) -> Result<()> {
+    let db_manager = connect_read_only(config).await?;
+    let snapshots = match config.query_id {
+        Some(query_id) => db_manager.get_snapshot_chain(query_id).await?,
+        None => db_manager.get_query_snapshots(&config.search_query).await?,
+    };
+    let Some(latest) = snapshots.last() else {
+        return Err(AppError::configuration("No snapshots found for that query"));
+    };
+
+    let intervals = db_manager.get_trends(&snapshots).await?;
+    println!("Query: {}", latest.search_query);
+    println!("Snapshots: {} ({} comparable intervals)", snapshots.len(), intervals.len());
+    if intervals.is_empty() {
+        ProgressIndicator::new("Trends".to_string(), config.output)
+            .warning("At least two snapshots with existing tables are needed to report trends");
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "  {:<16}  {:<16}  {:>8}  {:>6}  {:>7}  {:>11}",
+        "From", "To", "Repos", "New", "Dropped", "Star growth"
+    );
+    for interval in &intervals {
+        let diff = &interval.diff;
+        println!(
+            "  {:<16}  {:<16}  {:>8}  {:>6}  {:>7}  {:>+11}",
+            interval.from.executed_at.format("%Y-%m-%d %H:%M"),
+            interval.to.executed_at.format("%Y-%m-%d %H:%M"),
+            diff.current_count,
+            diff.new_entrants,
+            diff.dropped,
+            diff.star_growth
+        );
+    }
+
+    Ok(())
+}
+
+/// Aggregates a snapshot into `owner_stats` and prints the top owners by stars.
+async fn execute_analyze_owners(config: &CliConfig) -> Result<()> {
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+    let table_name = resolve_snapshot_table(&db_manager, config).await?;
+    let owners = db_manager.analyze_owners(&table_name).await?;
+    let top = db_manager.get_owner_stats(&table_name, config.top as i64).await?;
+
+    println!("{} owners in {} (stored in owner_stats)", owners, table_name);
+    println!();
+    println!(
+        "  {:<30}  {:<12}  {:>5}  {:>9}  {:<14}  {:>8}",
+        "Owner", "Type", "Repos", "Stars", "Language", "Age (y)"
+    );
+    for owner in &top {
+        println!(
+            "  {:<30}  {:<12}  {:>5}  {:>9}  {:<14}  {:>8.1}",
+            owner.owner_login,
+            owner.owner_type.as_deref().unwrap_or("-"),
+            owner.repositories,
+            owner.total_stars,
+            owner.dominant_language.as_deref().unwrap_or("-"),
+            owner.avg_repo_age_days / 365.25
+        );
+    }
+
+    Ok(())
+}
+
+/// Writes the topic co-occurrence graph of a snapshot to `--output` or stdout.
+async fn execute_analyze_topics(config: &CliConfig) -> Result<()> {
+    let db_manager = connect_read_only(config).await?;
+    let table_name = resolve_snapshot_table(&db_manager, config).await?;
+    let graph = db_manager.topic_graph(&table_name, config.min_weight as i64).await?;
+    let rendered = graph.render(config.graph_format);
+
+    match &config.graph_output {
+        Some(path) => {
+            std::fs::write(path, rendered).map_err(|e| {
+                AppError::configuration(format!("Cannot write {}: {}", path.display(), e))
+            })?;
+            ProgressIndicator::new("Topic graph".to_string(), config.output).success(&format!(
+                "Wrote {} topics and {} co-occurrences of {} to {}",
+                graph.nodes.len(),
+                graph.edges.len(),
+                table_name,
+                path.display()
+            ));
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+/// Prints the rank and stars of each tracked repository in a snapshot.
+async fn execute_compare(config: &CliConfig) -> Result<()> {
+    let path = config
+        .compare_with
+        .as_deref()
+        .ok_or_else(|| AppError::configuration("--with is required"))?;
+    let tracked = read_tracked_repositories(path)?;
+    let db_manager = connect_read_only(config).await?;
+    let table_name = resolve_snapshot_table(&db_manager, config).await?;
+    let results = db_manager.compare_repositories(&table_name, &tracked).await?;
+
+    let found = results.iter().filter(|result| result.rank.is_some()).count();
+    println!("{} of {} tracked repositories appear in {}", found, results.len(), table_name);
+    println!();
+    println!("  {:<40}  {:>6}  {:>9}", "Repository", "Rank", "Stars");
+    for result in &results {
+        match (result.rank, result.stargazers_count) {
+            (Some(rank), Some(stars)) => println!("  {:<40}  {:>6}  {:>9}", result.full_name, rank, stars),
+            _ => println!("  {:<40}  {:>6}  {:>9}", result.full_name, "-", "-"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Flags likely mirrors in a snapshot and lists them by original.
+async fn execute_duplicates(config: &CliConfig) -> Result<()> {
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+    let table_name = resolve_snapshot_table(&db_manager, config).await?;
+    let duplicates = db_manager.detect_duplicates(&table_name, &config.duplicates).await?;
+    if duplicates.is_empty() {
+        ProgressIndicator::new("Duplicates".to_string(), config.output)
+            .success(&format!("No likely duplicates in {}", table_name));
+        return Ok(());
+    }
+
+    println!("{} likely duplicates in {} (stored in duplicates)", duplicates.len(), table_name);
+    let mut original = "";
+    for duplicate in &duplicates {
+        if duplicate.original_full_name != original {
+            original = &duplicate.original_full_name;
+            println!();
+            println!("{}", original);
+        }
+        println!(
+            "  {} (description {:.0}% alike, size {:.1}% apart)",
+            duplicate.full_name,
+            duplicate.description_similarity * 100.0,
+            duplicate.size_difference * 100.0
+        );
+    }
+
+    Ok(())
+}
+
+/// Prints percentiles and an ASCII histogram of a [`Distribution`].
+fn display_distribution(title: &str, distribution: &Distribution, style: OutputStyle) {
+    let percentiles: Vec<String> = distribution
+        .percentiles
+        .iter()
+        .map(|(percentile, value)| format!("p{} {:.0}", percentile * 100.0, value))
+        .collect();
+    let rows: Vec<(String, i64)> = distribution
+        .buckets
+        .iter()
+        .map(|bucket| (format!("{}-{}", bucket.min, bucket.max), bucket.repositories))
+        .collect();
+
+    println!();
+    println!("{}: {}", title, percentiles.join(", "));
+    for line in histogram_lines(&rows, 40, style) {
+        println!("  {}", line);
     }
 }
 
@@ -96,7 +1130,8 @@ async fn main() {
 /// This function performs comprehensive validation of:
 /// - GitHub token validity and permissions
 /// - Database connectivity and accessibility
-/// - Search query format and syntax
+/// - Search query syntax, by sending each query GitHub would receive with
+///   `per_page=1` so GitHub's validation errors surface before a real run
 /// 
 /// # Arguments
 /// 
@@ -113,24 +1148,41 @@ async fn main() {
 /// github-pg-query "language:rust" --dry-run
 /// ```
 async fn validate_dry_run(config: &CliConfig) -> Result<()> {
-    let progress = ProgressIndicator::new("Dry run validation".to_string(), config.verbose);
+    let progress = ProgressIndicator::new("Dry run validation".to_string(), config.output);
     progress.start();
 
     // Validate GitHub client
-    progress.update("Validating GitHub token");
-    let github_client = GitHubClient::new(config.github_token.clone())?;
-    github_client.validate_token().await?;
-    progress.update("GitHub token is valid");
+    let github_client = build_github_client(config)?;
+    if github_client.is_anonymous() {
+        progress.update("Skipping GitHub token validation (--anonymous)");
+    } else {
+        progress.update("Validating GitHub token");
+        github_client.validate_token().await?;
+        progress.update("GitHub token is valid");
+    }
 
     // Validate database connection
     progress.update("Validating database connection");
-    let _db_manager = DatabaseManager::new(&config.database_url).await?;
+    let _db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
     progress.update("Database connection is valid");
 
-    // Validate search query format (basic validation)
-    progress.update("Validating search query format");
-    // The query validation is already done in CliConfig::parse()
-    progress.update("Search query format is valid");
+    // Preflight the queries a run would send; GitHub rejects invalid syntax with a 422
+    let queries = match config.search_type {
+        SearchType::Repositories => {
+            let now = chrono::Utc::now();
+            search_passes(&config.repository_query(), &config.languages)
+                .0
+                .into_iter()
+                .map(|pass| config.result_filter.qualify_query(&pass.query, now))
+                .collect()
+        }
+        SearchType::Topics => vec![config.search_query.clone()],
+    };
+    for query in &queries {
+        progress.update(&format!("Preflighting search query: '{}'", query));
+        let total_count = github_client.preflight_query(config.search_type, query).await?;
+        progress.update(&format!("Search query is valid ({} {} match)", total_count, config.search_type.as_str()));
+    }
 
     progress.success("All validations passed");
     Ok(())
@@ -148,6 +1200,7 @@ async fn validate_dry_run(config: &CliConfig) -> Result<()> {
 /// # Arguments
 /// 
 /// * `config` - The validated CLI configuration
+/// * `report` - Run summary filled in as the workflow progresses
 /// 
 /// # Returns
 /// 
@@ -159,71 +1212,214 @@ async fn validate_dry_run(config: &CliConfig) -> Result<()> {
 /// 1. **GitHub Client**: Creates authenticated client with rate limiting
 /// 2. **Database Setup**: Connects and creates timestamped table
 /// 3. **Search Execution**: Queries GitHub API with specified parameters
-/// 4. **Data Storage**: Inserts repositories with duplicate handling
+/// 4. **Data Storage**: Inserts each page with duplicate handling while the next is fetched
 /// 5. **Metadata Tracking**: Records query statistics and performance
 /// 
 /// # Error Handling
 /// 
 /// Errors at any step are propagated with context. Failed queries
 /// are recorded in the query history for analysis.
-async fn execute_search_workflow(config: &CliConfig) -> Result<()> {
-    let start_time = Instant::now();
-    
+async fn execute_search_workflow(config: &CliConfig, report: &mut RunReport) -> Result<()> {
     // Initialize GitHub client
-    let progress = ProgressIndicator::new("Initializing GitHub client".to_string(), config.verbose);
+    let progress = ProgressIndicator::new("Initializing GitHub client".to_string(), config.output);
     progress.start();
-    let github_client = GitHubClient::new(config.github_token.clone())?;
+    let github_client = build_github_client(config)?;
     progress.success("GitHub client initialized");
 
+    let outcome = match config.search_type {
+        SearchType::Repositories => execute_repository_search(config, &github_client, report).await,
+        SearchType::Topics => execute_topic_search_workflow(config, &github_client, report).await,
+    };
+    report.api_usage = Some(github_client.api_usage());
+    outcome
+}
+
+/// Searches repositories and stores them in a `repos_*` table.
+async fn execute_repository_search(
+    config: &CliConfig,
+    github_client: &GitHubClient,
+    report: &mut RunReport,
+) -> Result<()> {
+    let start_time = Instant::now();
+
     // Initialize database manager
-    let progress = ProgressIndicator::new("Connecting to database".to_string(), config.verbose);
+    let progress = ProgressIndicator::new("Connecting to database".to_string(), config.output);
     progress.start();
-    let db_manager = DatabaseManager::new(&config.database_url).await?;
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+    let db_manager = match &config.chaos {
+        Some(faults) => db_manager.with_fault_injection(faults.clone()),
+        None => db_manager,
+    };
     progress.success("Database connection established");
 
-    // Generate table name for this query
-    let table_name = DatabaseManager::generate_table_name();
+    let mut sinks = Vec::with_capacity(config.sinks.len());
+    for url in &config.sinks {
+        let progress = ProgressIndicator::new(format!("Connecting to sink: {}", url), config.output);
+        progress.start();
+        sinks.push(connect_sink(url).await?);
+        progress.success(&format!("Connected to {}", url));
+    }
+
+    // Load the previous snapshot when re-crawling incrementally
+    let baseline = match &config.incremental {
+        Some(previous_table) => {
+            let progress = ProgressIndicator::new(
+                format!("Loading incremental baseline: {}", previous_table),
+                config.output
+            );
+            progress.start();
+            let baseline = IncrementalBaseline::load(&db_manager, previous_table).await?;
+            progress.success(&format!("Baseline has {} repositories", baseline.updated_at.len()));
+            Some(baseline)
+        }
+        None => None,
+    };
+    let search_query = match &baseline {
+        Some(baseline) => baseline.qualify_query(&config.repository_query()),
+        None => config.repository_query(),
+    };
+    let (mut passes, search_query) = search_passes(&search_query, &config.languages);
+    let now = chrono::Utc::now();
+    for pass in &mut passes {
+        pass.query = config.result_filter.qualify_query(&pass.query, now);
+    }
+    report.query = search_query.clone();
+
+    // --append-to and --output-table name a fixed table; otherwise each run claims a timestamped one
+    let table_name = match config.append_to.clone().or_else(|| config.output_table.clone()) {
+        Some(table_name) => table_name,
+        None if config.sink_only => db_manager.new_table_name(),
+        None => db_manager.reserve_table_name().await?,
+    };
+    let action = if config.append_to.is_some() { "Checking" } else { "Creating" };
     let progress = ProgressIndicator::new(
-        format!("Creating table: {}", table_name), 
-        config.verbose
+        format!("{} table: {}", action, table_name),
+        config.output
     );
     progress.start();
     
     // Create query metadata
     let mut query_metadata = QueryMetadata::new(
-        config.search_query.clone(),
+        search_query.clone(),
         table_name.clone()
     );
+    record_request_parameters(&mut query_metadata, github_client, config);
+    if !config.sink_only {
+        report.table_name = Some(table_name.clone());
+        report.query_id = Some(query_metadata.id);
+    }
+    query_metadata.sort = Some(REPOSITORY_SEARCH_SORT.to_string());
+    query_metadata.sort_order = Some(REPOSITORY_SEARCH_ORDER.to_string());
 
     // Create repository table
-    db_manager.create_repository_table(&table_name).await?;
-    progress.success(&format!("Table {} created", table_name));
+    if config.sink_only {
+        progress.success(&format!("Skipping table {} (--sink-only)", table_name));
+    } else if config.append_to.is_some() {
+        db_manager.check_append_target(&table_name).await?;
+        progress.success(&format!("Appending to table {}", table_name));
+    } else {
+        db_manager.create_repository_table(&table_name).await?;
+        progress.success(&format!("Table {} created", table_name));
+    }
 
-    // Execute GitHub search
+    // Execute GitHub search, storing each page while the next one is fetched
     let progress = ProgressIndicator::new(
-        format!("Searching GitHub: '{}'", config.search_query), 
-        config.verbose
+        format!("Searching GitHub: '{}'", search_query), 
+        config.output
     );
     progress.start();
     
     let search_start = Instant::now();
-    let search_result = github_client.search_repositories(
-        &config.search_query,
-        Some(config.per_page),
-        Some(config.page)
-    ).await;
+    let (sender, receiver) = mpsc::channel(PIPELINE_DEPTH);
+    let (search_result, stored) = tokio::join!(
+        fetch_repository_pages(github_client, &passes, config, &progress, sender),
+        store_pages(
+            receiver,
+            (!config.sink_only).then_some(&db_manager as &dyn StorageBackend),
+            &sinks,
+            &table_name,
+            baseline.as_ref(),
+        )
+    );
 
     let search_duration = search_start.elapsed();
+    report.search_duration_ms = Some(search_duration.as_millis() as i64);
 
-    match search_result {
+    // Index whatever was stored, even when the search or the store failed part way
+    if !config.sink_only && config.database.defer_indexes {
+        let progress = ProgressIndicator::new(format!("Indexing {}", table_name), config.output);
+        progress.start();
+        match db_manager.create_deferred_indexes(&table_name).await {
+            Ok(created) => progress.success(&format!("Created {} indexes on {}", created, table_name)),
+            Err(error) if search_result.is_err() || stored.is_err() => {
+                progress.warning(&format!("Failed to index {}: {}", table_name, error))
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    let quarantined = match search_result {
         Ok(search_response) => {
-            let result_count = search_response.items.len() as i64;
-            progress.success(&format!(
-                "Found {} repositories (total: {}, page: {})", 
-                result_count, 
-                search_response.total_count,
-                config.page
-            ));
+            let stored = match stored {
+                Ok(stored) => stored,
+                Err(error) => {
+                    progress.error(&format!("Storing results failed: {}", error));
+                    query_metadata.pages_fetched = Some(search_response.pages_fetched as i32);
+                    record_failed_run(&db_manager, github_client, config, &mut query_metadata, &error, search_duration, &progress).await;
+                    return Err(error);
+                }
+            };
+            report.pages_fetched = search_response.pages_fetched;
+            report.incomplete_results = search_response.incomplete_results;
+            report.counts.total_count = search_response.total_count;
+            report.counts.fetched = search_response.fetched_count;
+            report.counts.duplicates = search_response.duplicate_count;
+            report.counts.filtered = search_response.filtered_count;
+            if search_response.pages_fetched > 1 {
+                progress.success(&format!(
+                    "Found {} unique repositories (total: {}, pages: {}-{})",
+                    search_response.unique_count(),
+                    search_response.total_count,
+                    config.page,
+                    config.page + search_response.pages_fetched - 1
+                ));
+                if search_response.duplicate_count > 0 {
+                    progress.info(&format!(
+                        "Dropped {} repositories repeated across pages",
+                        search_response.duplicate_count
+                    ));
+                }
+            } else {
+                progress.success(&format!(
+                    "Found {} repositories (total: {}, page: {})", 
+                    search_response.unique_count(), 
+                    search_response.total_count,
+                    config.page
+                ));
+            }
+            if search_response.filtered_count > 0 {
+                progress.info(&format!(
+                    "Filtered out {} repositories ({})",
+                    search_response.filtered_count,
+                    config.result_filter.to_arguments().unwrap_or_default()
+                ));
+            }
+            report.counts.quarantined = search_response.quarantined.len();
+            if !search_response.quarantined.is_empty() {
+                progress.warning(&format!(
+                    "Quarantined {} search items that could not be read as repositories",
+                    search_response.quarantined.len()
+                ));
+            }
+
+            if let Some(baseline) = &baseline {
+                report.counts.unchanged = stored.unchanged;
+                progress.info(&format!(
+                    "Skipped {} repositories unchanged since {}",
+                    stored.unchanged, baseline.table_name
+                ));
+            }
+            let result_count = stored.items.len() as i64;
 
             if config.verbose {
                 progress.info(&format!("Search completed in {:.2}s", search_duration.as_secs_f64()));
@@ -232,71 +1428,552 @@ async fn execute_search_workflow(config: &CliConfig) -> Result<()> {
                 }
             }
 
-            // Store repositories in database
-            if !search_response.items.is_empty() {
+            // Repositories were stored page by page during the search
+            if !stored.items.is_empty() {
                 let progress = ProgressIndicator::new(
                     format!("Storing {} repositories", result_count), 
-                    config.verbose
+                    config.output
                 );
-                progress.start();
 
-                let inserted_count = db_manager.insert_repositories(
-                    &table_name, 
-                    &search_response.items
-                ).await?;
+                let outcome = &stored.outcome;
+                for (sink, published) in sinks.iter().zip(&stored.published) {
+                    report.counts.published += published;
+                    if config.verbose {
+                        progress.info(&format!("Published {} repositories to {}", published, sink.describe()));
+                    }
+                }
 
-                progress.success(&format!("Stored {} repositories", inserted_count));
+                if config.sink_only {
+                    progress.success(&format!("Published {} repositories", report.counts.published));
+                } else {
+                    progress.success(&format!("Stored {} repositories", outcome.total()));
+                }
+                report.counts.inserted = outcome.inserted;
+                report.counts.updated = outcome.updated;
+                report.counts.failed = outcome.failed;
+                if outcome.failed > 0 {
+                    progress.warning(&format!(
+                        "{} repositories were not stored because their batch failed",
+                        outcome.failed
+                    ));
+                }
+                if !outcome.rejected.is_empty() {
+                    progress.warning(&format!("Skipped {} invalid repositories", outcome.rejected.len()));
+                    if config.verbose {
+                        for reject in &outcome.rejected {
+                            progress.info(&format!("  {} ({}): {}", reject.full_name, reject.github_id, reject.reason));
+                        }
+                    }
+                }
+                report.counts.rejected = outcome.rejected.len();
+                report.rejects = outcome.rejected.clone();
+                if !config.sink_only {
+                    progress.event(ProgressEvent::Rows {
+                        table: table_name.clone(),
+                        inserted: outcome.inserted,
+                        updated: outcome.updated,
+                    });
+                }
 
-                if config.verbose && inserted_count != result_count {
+                if config.verbose && outcome.updated > 0 {
                     progress.info(&format!(
-                        "Note: {} repositories were updated (duplicates)", 
-                        result_count - inserted_count
+                        "Note: {} new, {} updated in place", 
+                        outcome.inserted,
+                        outcome.updated
+                    ));
+                }
+
+                if !config.enrichments.is_empty() {
+                    let kinds: Vec<&str> = config.enrichments.iter().map(|k| k.as_str()).collect();
+                    let progress = ProgressIndicator::new(
+                        format!("Enriching repositories ({})", kinds.join(", ")),
+                        config.output
+                    );
+                    progress.start();
+
+                    let rules = if config.enrichments.contains(&EnrichmentKind::Category) {
+                        CategoryRules::load(config.category_rules.as_deref())?
+                    } else {
+                        CategoryRules::default()
+                    };
+                    let summary = enrich_repositories(
+                        github_client,
+                        &db_manager,
+                        &table_name,
+                        &stored.items,
+                        &config.enrichments,
+                        &rules
+                    ).await?;
+
+                    progress.success(&format!("Enriched {} repositories", summary.enriched));
+                    report.counts.enriched = summary.enriched;
+                    report.counts.enrichment_skipped = summary.skipped;
+                    report.counts.enrichment_failed = summary.failed;
+                    if summary.skipped > 0 {
+                        progress.info(&format!("{} repositories had nothing to enrich", summary.skipped));
+                    }
+                    if summary.failed > 0 {
+                        progress.warning(&format!("{} enrichment lookups failed", summary.failed));
+                    }
+                }
+
+                if config.create_views {
+                    let progress = ProgressIndicator::new("Creating analytics views".to_string(), config.output);
+                    progress.start();
+
+                    let views = db_manager.create_analytics_views(&table_name).await?;
+                    progress.success(&format!("Created {} analytics views", views.len()));
+                    if config.verbose {
+                        for view in &views {
+                            progress.info(&format!("  {}", view));
+                        }
+                    }
+                }
+
+                if config.update_latest {
+                    let progress = ProgressIndicator::new("Updating repositories_latest".to_string(), config.output);
+                    progress.start();
+                    let updated = db_manager.update_latest(&table_name).await?;
+                    progress.success(&format!("Updated {} repositories in repositories_latest", updated));
+                }
+
+                if config.track_changes {
+                    let progress = ProgressIndicator::new("Recording repository changes".to_string(), config.output);
+                    progress.start();
+                    let update = db_manager.track_changes(&table_name).await?;
+                    progress.success(&format!(
+                        "Opened {} versions in repository_history ({} closed by changes)",
+                        update.opened, update.closed
                     ));
                 }
+
+                if config.timeseries {
+                    let progress = ProgressIndicator::new("Recording star counts".to_string(), config.output);
+                    progress.start();
+                    let recorded = db_manager.record_star_counts(&table_name, query_metadata.executed_at).await?;
+                    progress.success(&format!("Recorded {} star counts in star_counts", recorded));
+                }
             } else {
-                let progress = ProgressIndicator::new("No repositories found".to_string(), config.verbose);
+                let progress = ProgressIndicator::new("No repositories found".to_string(), config.output);
                 progress.warning("No repositories matched the search query");
             }
 
             // Update query metadata with success
+            query_metadata.pages_fetched = Some(search_response.pages_fetched as i32);
+            query_metadata.incomplete_results = search_response.incomplete_results;
+            query_metadata.rows_failed = stored.outcome.failed;
+            if !config.sink_only {
+                query_metadata.previous_query_id = db_manager.find_previous_snapshot(&query_metadata).await?;
+            }
             query_metadata.mark_success(result_count, search_duration.as_millis() as i64);
+            query_metadata.http_metrics = Some(github_client.http_metrics());
+            search_response.quarantined
         }
         Err(error) => {
-            // Update query metadata with failure
-            query_metadata.mark_failure(
-                error.to_string(), 
-                search_duration.as_millis() as i64
-            );
-            
             progress.error(&format!("Search failed: {}", error));
-            
-            // Save the failed query metadata before returning error
-            if let Err(save_error) = db_manager.save_query_metadata(&query_metadata).await {
-                progress.warning(&format!("Failed to save query metadata: {}", save_error));
+            record_failed_run(&db_manager, github_client, config, &mut query_metadata, &error, search_duration, &progress).await;
+            return Err(error);
+        }
+    };
+
+    // Save query metadata (sink-only runs leave no snapshot to record)
+    if !config.sink_only {
+        let progress = ProgressIndicator::new("Saving query metadata".to_string(), config.output);
+        progress.start();
+        db_manager.save_query_metadata(&query_metadata).await?;
+        db_manager.insert_quarantined_items(Some(query_metadata.id), &table_name, &quarantined).await?;
+        progress.success("Query metadata saved");
+        save_rate_limit_log(&db_manager, github_client, Some(query_metadata.id), &progress).await;
+        if config.timeseries {
+            db_manager.record_run_metrics(&query_metadata).await?;
+        }
+    }
+
+    if let (Some(path), Some(previous_id)) = (&config.alert_rules, query_metadata.previous_query_id) {
+        let progress = ProgressIndicator::new("Checking alert rules".to_string(), config.output);
+        progress.start();
+        let rules = AlertRules::load(path)?;
+        // Re-runs into the same table left no earlier copy of it to compare with
+        match db_manager.snapshot_table_of(previous_id).await? {
+            Some(previous) if previous != table_name && db_manager.table_exists(&previous).await? => {
+                report.alerts = db_manager.evaluate_alerts(&rules, &table_name, &previous).await?;
+                progress.success(&format!("{} alerts against {}", report.alerts.len(), previous));
+                for alert in &report.alerts {
+                    progress.warning(&alert.message());
+                }
+            }
+            _ => progress.info("No earlier snapshot table to compare with; alert rules skipped"),
+        }
+    }
+
+    // Display final summary
+    let total_duration = start_time.elapsed();
+    let destination = if config.sink_only {
+        let sinks: Vec<String> = config.sinks.iter().map(|sink| sink.to_string()).collect();
+        format!("Published to: {}", sinks.join(", "))
+    } else {
+        format!("Table name: {}", table_name)
+    };
+    let mut summary = vec![
+        destination,
+        format!("Results: {} repositories", query_metadata.result_count),
+        format!("Total time: {:.2}s", total_duration.as_secs_f64()),
+    ];
+    if config.verbose {
+        summary.push(format!("Search time: {:.2}s", search_duration.as_secs_f64()));
+        summary.push(format!("Query ID: {}", query_metadata.id));
+        if let Some(metrics) = &query_metadata.http_metrics {
+            summary.push(format!("HTTP: {}", metrics.summary()));
+        }
+    }
+    progress.summary("Search completed successfully!", &summary);
+
+    Ok(())
+}
+
+/// Marks the run failed and saves its metadata before the error is returned.
+///
+/// Saving is best effort: a failed write only produces a warning, so the
+/// run's own error is the one reported.
+async fn record_failed_run(
+    db_manager: &DatabaseManager,
+    github_client: &GitHubClient,
+    config: &CliConfig,
+    query_metadata: &mut QueryMetadata,
+    error: &AppError,
+    duration: Duration,
+    progress: &ProgressIndicator,
+) {
+    query_metadata.mark_failure(error.to_string(), duration.as_millis() as i64);
+    query_metadata.http_metrics = Some(github_client.http_metrics());
+
+    // Sink-only runs leave no snapshot to record
+    if config.sink_only {
+        return;
+    }
+    match db_manager.save_query_metadata(query_metadata).await {
+        Ok(()) => save_rate_limit_log(db_manager, github_client, Some(query_metadata.id), progress).await,
+        Err(save_error) => progress.warning(&format!("Failed to save query metadata: {}", save_error)),
+    }
+    if config.timeseries {
+        if let Err(metrics_error) = db_manager.record_run_metrics(query_metadata).await {
+            progress.warning(&format!("Failed to record run metrics: {}", metrics_error));
+        }
+    }
+}
+
+/// Writes the rate limit headers collected so far to `rate_limit_log`.
+///
+/// The log is diagnostic, so a failed write only produces a warning.
+async fn save_rate_limit_log(
+    db_manager: &DatabaseManager,
+    github_client: &GitHubClient,
+    query_id: Option<uuid::Uuid>,
+    progress: &ProgressIndicator,
+) {
+    let samples = github_client.take_rate_limit_log();
+    if let Err(error) = db_manager.insert_rate_limit_log(query_id, &samples).await {
+        progress.warning(&format!("Failed to save rate limit log: {}", error));
+    }
+}
+
+/// Records the request parameters needed to reproduce and audit a run
+fn record_request_parameters(metadata: &mut QueryMetadata, client: &GitHubClient, config: &CliConfig) {
+    metadata.per_page = Some(config.per_page as i32);
+    metadata.api_base_url = Some(client.base_url().to_string());
+    metadata.token_fingerprint = (!client.is_anonymous()).then(|| client.token_fingerprint());
+    metadata.saved_query = config.saved_query.clone();
+    metadata.query_template = config.query_template.clone();
+    metadata.result_filter = config.result_filter.to_arguments();
+    metadata.labels = config.labels.clone();
+    metadata.note = config.note.clone();
+}
+
+/// GitHub search never returns results beyond the first 1000 matches
+const SEARCH_RESULT_LIMIT: u32 = 1000;
+
+/// One GitHub search of a run; `--languages` runs one per language
+struct SearchPass {
+    query: String,
+    /// `--languages` entry recorded in `matched_language_filter`
+    language: Option<String>,
+}
+
+/// `language:` qualifier for a `--languages` entry, quoted when it has spaces
+fn language_qualifier(language: &str) -> String {
+    if language.contains(char::is_whitespace) {
+        format!("language:\"{}\"", language)
+    } else {
+        format!("language:{}", language)
+    }
+}
+
+/// The searches a run makes, and the query recorded for it in `query_history`
+fn search_passes(search_query: &str, languages: &[String]) -> (Vec<SearchPass>, String) {
+    if languages.is_empty() {
+        let pass = SearchPass { query: search_query.to_string(), language: None };
+        return (vec![pass], search_query.to_string());
+    }
+
+    let passes = languages
+        .iter()
+        .map(|language| SearchPass {
+            query: format!("{} {}", search_query, language_qualifier(language)),
+            language: Some(language.clone()),
+        })
+        .collect();
+    let qualifiers: Vec<String> = languages.iter().map(|language| language_qualifier(language)).collect();
+    (passes, format!("{} ({})", search_query, qualifiers.join(" OR ")))
+}
+
+/// Fetches `config.pages` consecutive result pages of every pass, deduplicating by github_id.
+/// 
+/// The repositories each page adds are sent to `batches` as soon as the page
+/// arrives. A pass stops early on a short page or once the search result
+/// limit is reached; all passes stop when the receiving side has stopped
+/// storing. The total count is the sum of the passes' totals.
+async fn fetch_repository_pages(
+    github_client: &GitHubClient,
+    passes: &[SearchPass],
+    config: &CliConfig,
+    progress: &ProgressIndicator,
+    batches: mpsc::Sender<Vec<Repository>>,
+) -> Result<RepositoryPages> {
+    let mut pages = RepositoryPages::default();
+    let total_pages = config.pages as u64 * passes.len() as u64;
+    let steps = (total_pages > 1).then(|| progress.steps(total_pages, "pages"));
+    let mut total_count = 0;
+
+    'passes: for pass in passes {
+        for page in config.page..config.page + config.pages {
+            if let Some(steps) = &steps {
+                let remaining = github_client
+                    .api_usage()
+                    .rate_limits
+                    .get("search")
+                    .and_then(|usage| usage.remaining)
+                    .map_or(String::new(), |remaining| format!(", rate limit remaining {}", remaining));
+                steps.status(&format!("page {}, {} repositories{}", page, pages.unique_count(), remaining));
+            }
+
+            let mut response = search_with_policy(
+                config.incomplete,
+                progress,
+                |response: &SearchResponse| response.incomplete_results,
+                || github_client.search_repositories(&pass.query, Some(config.per_page), Some(page))
+            )
+            .instrument(tracing::info_span!("search.page", page, language = pass.language.as_deref()))
+            .await?;
+            for repo in &mut response.items {
+                repo.matched_language_filter = pass.language.clone();
+            }
+
+            let page_len = response.items.len();
+            let added = pages.absorb_matching(response, &config.result_filter);
+            let batch = pages.items[pages.items.len() - added..].to_vec();
+            if batches.send(batch).await.is_err() {
+                break 'passes;
+            }
+            if let Some(steps) = &steps {
+                steps.advance();
+            }
+            progress.event(ProgressEvent::Page {
+                page,
+                pages: config.pages,
+                rows: pages.unique_count(),
+                rate_limit_remaining: github_client
+                    .api_usage()
+                    .rate_limits
+                    .get("search")
+                    .and_then(|usage| usage.remaining),
+            });
+
+            if page_len < config.per_page as usize || page * config.per_page >= SEARCH_RESULT_LIMIT {
+                break;
+            }
+        }
+        total_count += pages.total_count;
+    }
+    pages.total_count = total_count;
+
+    if let Some(steps) = &steps {
+        steps.finish();
+    }
+    Ok(pages)
+}
+
+/// Pages buffered between the fetch and store halves of a repository search
+const PIPELINE_DEPTH: usize = 2;
+
+/// Number of times a page flagged as incomplete is re-issued under `--incomplete retry`
+const INCOMPLETE_RETRIES: u32 = 3;
+
+/// Runs a search request, applying the configured incomplete-results policy.
+/// 
+/// Under `retry` the request is re-issued with a linear backoff and the last
+/// response is returned even if it is still incomplete.
+async fn search_with_policy<T, F, Fut>(
+    policy: IncompletePolicy,
+    progress: &ProgressIndicator,
+    is_incomplete: fn(&T) -> bool,
+    mut search: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut response = search().await?;
+    let mut attempt = 0;
+
+    while is_incomplete(&response) {
+        match policy {
+            IncompletePolicy::Accept => break,
+            IncompletePolicy::Fail => {
+                return Err(AppError::partial_results(
+                    "Search returned incomplete results (--incomplete fail)"
+                ));
+            }
+            IncompletePolicy::Retry => {
+                if attempt == INCOMPLETE_RETRIES {
+                    break;
+                }
+                attempt += 1;
+                progress.update(&format!(
+                    "incomplete results, retrying ({}/{})",
+                    attempt, INCOMPLETE_RETRIES
+                ));
+                tokio::time::sleep(Duration::from_secs(attempt as u64)).await;
+                response = search().await?;
+            }
+        }
+    }
+
+    Ok(response)
+}
+
+/// Executes a topic search and stores the results in a `topics_*` table.
+/// 
+/// Mirrors the repository workflow: results are upserted by topic name
+/// and the run is recorded in the query history.
+async fn execute_topic_search_workflow(
+    config: &CliConfig,
+    github_client: &GitHubClient,
+    report: &mut RunReport,
+) -> Result<()> {
+    let start_time = Instant::now();
+
+    let progress = ProgressIndicator::new("Connecting to database".to_string(), config.output);
+    progress.start();
+    let db_manager = DatabaseManager::new_with_options(&config.database_url, &config.database).await?;
+    progress.success("Database connection established");
+
+    let table_name = match &config.output_table {
+        Some(table_name) => table_name.clone(),
+        None => db_manager.reserve_topic_table_name().await?,
+    };
+    let progress = ProgressIndicator::new(
+        format!("Creating table: {}", table_name),
+        config.output
+    );
+    progress.start();
+
+    let mut query_metadata = QueryMetadata::new(
+        config.search_query.clone(),
+        table_name.clone()
+    );
+    record_request_parameters(&mut query_metadata, github_client, config);
+    report.table_name = Some(table_name.clone());
+    report.query_id = Some(query_metadata.id);
+
+    db_manager.create_topic_table(&table_name).await?;
+    progress.success(&format!("Table {} created", table_name));
+
+    let progress = ProgressIndicator::new(
+        format!("Searching GitHub topics: '{}'", config.search_query),
+        config.output
+    );
+    progress.start();
+
+    let search_start = Instant::now();
+    let search_result = search_with_policy(
+        config.incomplete,
+        &progress,
+        |response: &TopicSearchResponse| response.incomplete_results,
+        || github_client.search_topics(&config.search_query, Some(config.per_page), Some(config.page))
+    ).await;
+    let search_duration = search_start.elapsed();
+    report.search_duration_ms = Some(search_duration.as_millis() as i64);
+
+    match search_result {
+        Ok(search_response) => {
+            let result_count = search_response.items.len() as i64;
+            report.pages_fetched = 1;
+            report.incomplete_results = search_response.incomplete_results;
+            report.counts.total_count = search_response.total_count;
+            report.counts.fetched = search_response.items.len();
+            progress.success(&format!(
+                "Found {} topics (total: {}, page: {})",
+                result_count,
+                search_response.total_count,
+                config.page
+            ));
+
+            if config.verbose && search_response.incomplete_results {
+                progress.warning("Search results may be incomplete due to timeout");
+            }
+
+            if !search_response.items.is_empty() {
+                let progress = ProgressIndicator::new(
+                    format!("Storing {} topics", result_count),
+                    config.output
+                );
+                progress.start();
+                let inserted_count = db_manager.insert_topics(&table_name, &search_response.items).await?;
+                progress.success(&format!("Stored {} topics", inserted_count));
+                progress.event(ProgressEvent::Rows { table: table_name.clone(), inserted: inserted_count, updated: 0 });
+                report.counts.inserted = inserted_count;
+            } else {
+                let progress = ProgressIndicator::new("No topics found".to_string(), config.output);
+                progress.warning("No topics matched the search query");
+            }
+
+            query_metadata.pages_fetched = Some(1);
+            query_metadata.incomplete_results = search_response.incomplete_results;
+            query_metadata.mark_success(result_count, search_duration.as_millis() as i64);
+            query_metadata.http_metrics = Some(github_client.http_metrics());
+        }
+        Err(error) => {
+            query_metadata.mark_failure(error.to_string(), search_duration.as_millis() as i64);
+            query_metadata.http_metrics = Some(github_client.http_metrics());
+            progress.error(&format!("Search failed: {}", error));
+
+            match db_manager.save_query_metadata(&query_metadata).await {
+                Ok(()) => save_rate_limit_log(&db_manager, github_client, Some(query_metadata.id), &progress).await,
+                Err(save_error) => progress.warning(&format!("Failed to save query metadata: {}", save_error)),
             }
-            
+
             return Err(error);
         }
     }
 
-    // Save query metadata
-    let progress = ProgressIndicator::new("Saving query metadata".to_string(), config.verbose);
+    let progress = ProgressIndicator::new("Saving query metadata".to_string(), config.output);
     progress.start();
     db_manager.save_query_metadata(&query_metadata).await?;
     progress.success("Query metadata saved");
+    save_rate_limit_log(&db_manager, github_client, Some(query_metadata.id), &progress).await;
 
-    // Display final summary
     let total_duration = start_time.elapsed();
-    println!();
-    println!("🎉 Search completed successfully!");
-    println!("   Table name: {}", table_name);
-    println!("   Results: {} repositories", query_metadata.result_count);
-    println!("   Total time: {:.2}s", total_duration.as_secs_f64());
-    
+    let mut summary = vec![
+        format!("Table name: {}", table_name),
+        format!("Results: {} topics", query_metadata.result_count),
+        format!("Total time: {:.2}s", total_duration.as_secs_f64()),
+    ];
     if config.verbose {
-        println!("   Search time: {:.2}s", search_duration.as_secs_f64());
-        println!("   Query ID: {}", query_metadata.id);
+        if let Some(metrics) = &query_metadata.http_metrics {
+            summary.push(format!("HTTP: {}", metrics.summary()));
+        }
     }
+    progress.summary("Topic search completed successfully!", &summary);
 
     Ok(())
-}
\ No newline at end of file
+}
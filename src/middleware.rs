@@ -0,0 +1,143 @@
+//! # Middleware Module
+//!
+//! Hooks around every request `GitHubClient` sends. Middlewares run in the
+//! order they were added: `on_request` can add headers or sign the request,
+//! `on_response` can log, cache, or ask for the request to be sent again.
+//! Authentication and rate limit retries are built-in middlewares.
+
+use async_trait::async_trait;
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::{Request, Response, StatusCode};
+use std::fmt;
+use std::time::Duration;
+
+use crate::{AppError, RateLimitConfig, Result};
+
+/// What to do with a response once a middleware has seen it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MiddlewareAction {
+    /// Pass the response on to the next middleware and the caller
+    Continue,
+    /// Send the request again after the delay
+    Retry(Duration),
+}
+
+/// Hook into the requests sent by a `GitHubClient`
+#[async_trait]
+pub trait Middleware: fmt::Debug + Send + Sync {
+    /// Adjust a request before it is sent; errors abort the request
+    async fn on_request(&self, _request: &mut Request) -> Result<()> {
+        Ok(())
+    }
+
+    /// Inspect a response; `attempt` counts earlier retries of the same request
+    async fn on_response(&self, _request: &Request, _response: &Response, _attempt: u32) -> Result<MiddlewareAction> {
+        Ok(MiddlewareAction::Continue)
+    }
+}
+
+/// Lets callers keep a handle on a middleware's state after adding it to a client
+#[async_trait]
+impl<M: Middleware + ?Sized> Middleware for std::sync::Arc<M> {
+    async fn on_request(&self, request: &mut Request) -> Result<()> {
+        (**self).on_request(request).await
+    }
+
+    async fn on_response(&self, request: &Request, response: &Response, attempt: u32) -> Result<MiddlewareAction> {
+        (**self).on_response(request, response, attempt).await
+    }
+}
+
+/// Sends the token as an `Authorization: Bearer` header
+pub struct BearerAuth {
+    header: HeaderValue,
+}
+
+impl BearerAuth {
+    /// Create the middleware, rejecting tokens that cannot be sent as a header
+    pub fn new(token: &str) -> Result<Self> {
+        let mut header = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|_| AppError::authentication("GitHub token contains invalid characters"))?;
+        header.set_sensitive(true);
+        Ok(Self { header })
+    }
+}
+
+impl fmt::Debug for BearerAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BearerAuth")
+    }
+}
+
+#[async_trait]
+impl Middleware for BearerAuth {
+    async fn on_request(&self, request: &mut Request) -> Result<()> {
+        request.headers_mut().insert(AUTHORIZATION, self.header.clone());
+        Ok(())
+    }
+}
+
+/// Retries 403 and 429 responses with exponential backoff and jitter
+#[derive(Debug, Clone)]
+pub struct RetryOnRateLimit {
+    config: RateLimitConfig,
+}
+
+impl RetryOnRateLimit {
+    /// Retry according to the backoff settings in `config`
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self { config }
+    }
+
+    /// Delay before retry number `attempt` (0-based), without jitter
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff_ms = self.config.initial_backoff_ms as f64 * self.config.backoff_multiplier.powi(attempt as i32);
+        Duration::from_millis((backoff_ms as u64).min(self.config.max_backoff_ms))
+    }
+}
+
+#[async_trait]
+impl Middleware for RetryOnRateLimit {
+    async fn on_response(&self, _request: &Request, response: &Response, attempt: u32) -> Result<MiddlewareAction> {
+        let rate_limited = matches!(response.status(), StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS);
+        if !rate_limited || attempt >= self.config.max_retries {
+            return Ok(MiddlewareAction::Continue);
+        }
+
+        let backoff = self.backoff(attempt);
+        let jitter = Duration::from_millis(fastrand::u64(0..=backoff.as_millis() as u64 / 4));
+        Ok(MiddlewareAction::Retry(backoff + jitter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_backoff() {
+        let retry = RetryOnRateLimit::new(RateLimitConfig {
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1000,
+            backoff_multiplier: 3.0,
+            ..RateLimitConfig::default()
+        });
+        assert_eq!(retry.backoff(0), Duration::from_millis(100));
+        assert_eq!(retry.backoff(1), Duration::from_millis(300));
+        assert_eq!(retry.backoff(2), Duration::from_millis(900));
+        assert_eq!(retry.backoff(3), Duration::from_millis(1000));
+    }
+
+    #[tokio::test]
+    async fn test_bearer_auth_sets_sensitive_header() {
+        let auth = BearerAuth::new("ghp_secret").unwrap();
+        let mut request = Request::new(reqwest::Method::GET, "https://api.github.com/user".parse().unwrap());
+        auth.on_request(&mut request).await.unwrap();
+
+        let header = &request.headers()[AUTHORIZATION];
+        assert_eq!(header, "Bearer ghp_secret");
+        assert!(header.is_sensitive());
+        assert_eq!(format!("{:?}", auth), "BearerAuth");
+        assert!(BearerAuth::new("bad\ntoken").is_err());
+    }
+}
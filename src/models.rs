@@ -1,9 +1,16 @@
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
 use crate::errors::{AppError, Result};
 
 /// Repository data structure matching GitHub API response
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Only the identifying fields and timestamps are required when
+/// deserializing; the rest fall back to their defaults when GitHub omits
+/// them or sends `null` (e.g. the owner of some deleted accounts), and
+/// [`Repository::validated`] decides whether such a row may be stored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Repository {
     /// GitHub repository ID
     pub id: i64,
@@ -21,45 +28,58 @@ pub struct Repository {
     pub html_url: String,
     
     /// Clone URL (HTTPS)
+    #[serde(default, deserialize_with = "null_as_default")]
     pub clone_url: String,
     
     /// SSH URL
+    #[serde(default, deserialize_with = "null_as_default")]
     pub ssh_url: String,
     
     /// Repository size in KB
+    #[serde(default, deserialize_with = "null_as_default")]
     pub size: i64,
     
     /// Star count
+    #[serde(default, deserialize_with = "null_as_default")]
     pub stargazers_count: i64,
     
     /// Watchers count
+    #[serde(default, deserialize_with = "null_as_default")]
     pub watchers_count: i64,
     
     /// Forks count
+    #[serde(default, deserialize_with = "null_as_default")]
     pub forks_count: i64,
     
     /// Open issues count
+    #[serde(default, deserialize_with = "null_as_default")]
     pub open_issues_count: i64,
     
     /// Primary language
     pub language: Option<String>,
     
     /// Default branch
+    #[serde(default, deserialize_with = "null_as_default")]
     pub default_branch: String,
     
     /// Repository visibility (public/private)
+    #[serde(default, deserialize_with = "null_as_default")]
     pub visibility: String,
     
     /// Is the repository private
+    #[serde(default, deserialize_with = "null_as_default")]
     pub private: bool,
     
     /// Is the repository a fork
+    #[serde(default, deserialize_with = "null_as_default")]
     pub fork: bool,
     
     /// Is the repository archived
+    #[serde(default, deserialize_with = "null_as_default")]
     pub archived: bool,
     
     /// Is the repository disabled
+    #[serde(default, deserialize_with = "null_as_default")]
     pub disabled: bool,
     
     /// Repository creation date
@@ -72,32 +92,82 @@ pub struct Repository {
     pub pushed_at: Option<DateTime<Utc>>,
     
     /// Repository owner information
+    #[serde(default, deserialize_with = "null_as_default")]
     pub owner: RepositoryOwner,
     
     /// Repository license information
     pub license: Option<RepositoryLicense>,
     
     /// Repository topics/tags
+    #[serde(default, deserialize_with = "null_as_default")]
     pub topics: Vec<String>,
     
     /// Has issues enabled
+    #[serde(default, deserialize_with = "null_as_default")]
     pub has_issues: bool,
     
     /// Has projects enabled
+    #[serde(default, deserialize_with = "null_as_default")]
     pub has_projects: bool,
     
     /// Has wiki enabled
+    #[serde(default, deserialize_with = "null_as_default")]
     pub has_wiki: bool,
     
     /// Has pages enabled
+    #[serde(default, deserialize_with = "null_as_default")]
     pub has_pages: bool,
     
     /// Has downloads enabled
+    #[serde(default, deserialize_with = "null_as_default")]
     pub has_downloads: bool,
+
+    /// Relevance score GitHub assigned to the search result
+    #[serde(default)]
+    pub score: Option<f64>,
+
+    /// Matched fragments, returned when searching with `--text-match`
+    #[serde(default, deserialize_with = "null_as_default", skip_serializing_if = "Vec::is_empty")]
+    pub text_matches: Vec<TextMatch>,
+
+    /// `--languages` entry whose search returned the repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_language_filter: Option<String>,
 }
 
-/// Repository owner information
+/// A fragment of a repository field that matched the search terms
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextMatch {
+    /// API URL of the matched object
+    #[serde(default)]
+    pub object_url: Option<String>,
+
+    /// Kind of object matched (e.g., "Repository")
+    #[serde(default)]
+    pub object_type: Option<String>,
+
+    /// Field the fragment was taken from (e.g., "description")
+    pub property: String,
+
+    /// Text surrounding the matches
+    pub fragment: String,
+
+    /// Matched terms and their offsets within `fragment`
+    #[serde(default)]
+    pub matches: Vec<TextMatchTerm>,
+}
+
+/// A matched term within a [`TextMatch`] fragment
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextMatchTerm {
+    /// Term as it appears in the fragment
+    pub text: String,
+    /// Start and end offsets of the term in the fragment
+    pub indices: Vec<i64>,
+}
+
+/// Repository owner information
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RepositoryOwner {
     /// Owner ID
     pub id: i64,
@@ -106,16 +176,20 @@ pub struct RepositoryOwner {
     pub login: String,
     
     /// Owner type (User, Organization)
+    #[serde(default, deserialize_with = "null_as_default")]
     #[serde(rename = "type")]
     pub owner_type: String,
     
     /// Owner avatar URL
+    #[serde(default, deserialize_with = "null_as_default")]
     pub avatar_url: String,
     
     /// Owner HTML URL
+    #[serde(default, deserialize_with = "null_as_default")]
     pub html_url: String,
     
     /// Is site admin
+    #[serde(default, deserialize_with = "null_as_default")]
     pub site_admin: bool,
 }
 
@@ -123,9 +197,11 @@ pub struct RepositoryOwner {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RepositoryLicense {
     /// License key (e.g., "mit", "apache-2.0")
+    #[serde(default, deserialize_with = "null_as_default")]
     pub key: String,
     
     /// License name
+    #[serde(default, deserialize_with = "null_as_default")]
     pub name: String,
     
     /// License SPDX ID
@@ -136,7 +212,11 @@ pub struct RepositoryLicense {
 }
 
 /// GitHub API search response wrapper
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Items are mapped one at a time, so an item that still cannot be read as
+/// a [`Repository`] ends up in `quarantined` instead of failing the page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RawSearchResponse")]
 pub struct SearchResponse {
     /// Total count of repositories found
     pub total_count: i64,
@@ -146,6 +226,492 @@ pub struct SearchResponse {
     
     /// Array of repository items
     pub items: Vec<Repository>,
+
+    /// Items that could not be mapped to a repository
+    #[serde(skip_serializing)]
+    pub quarantined: Vec<QuarantinedItem>,
+}
+
+/// A search item that could not be mapped to a [`Repository`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuarantinedItem {
+    /// The item's `id`, when it had one
+    pub github_id: Option<i64>,
+    /// Deserialization error
+    pub error: String,
+    /// The item as GitHub returned it
+    pub payload: serde_json::Value,
+}
+
+/// Search response as received, before items are mapped
+#[derive(Deserialize)]
+struct RawSearchResponse {
+    total_count: i64,
+    incomplete_results: bool,
+    items: Vec<serde_json::Value>,
+}
+
+impl From<RawSearchResponse> for SearchResponse {
+    fn from(raw: RawSearchResponse) -> Self {
+        let mut items = Vec::with_capacity(raw.items.len());
+        let mut quarantined = Vec::new();
+        for payload in raw.items {
+            match Repository::deserialize(&payload) {
+                Ok(repo) => items.push(repo),
+                Err(error) => quarantined.push(QuarantinedItem {
+                    github_id: payload.get("id").and_then(serde_json::Value::as_i64),
+                    error: error.to_string(),
+                    payload,
+                }),
+            }
+        }
+
+        Self {
+            total_count: raw.total_count,
+            incomplete_results: raw.incomplete_results,
+            items,
+            quarantined,
+        }
+    }
+}
+
+/// Deserialize `null` as the field type's default
+fn null_as_default<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Client-side filters applied to search results before they are stored
+///
+/// GitHub search cannot exclude several owners or skip archived repositories
+/// in one query, so `--exclude-owner`, `--only-owner`, `--exclude-forks`, and
+/// `--exclude-archived` drop repositories after each page arrives. The
+/// data-quality minimums `--min-stars` and `--pushed-within` are also added
+/// to the search query (see [`ResultFilter::qualify_query`]) so GitHub does
+/// most of the work; `--has-license` and `--has-description` have no search
+/// qualifier and are only checked here.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResultFilter {
+    /// Owner logins whose repositories are dropped, lowercased
+    pub exclude_owners: Vec<String>,
+    /// Owner logins whose repositories are kept, lowercased; empty keeps all
+    pub only_owners: Vec<String>,
+    /// Drop forks
+    pub exclude_forks: bool,
+    /// Drop archived repositories
+    pub exclude_archived: bool,
+    /// Drop repositories with fewer stars
+    pub min_stars: Option<u32>,
+    /// Drop repositories not pushed to within this period
+    pub pushed_within: Option<chrono::Duration>,
+    /// Drop repositories without a detected license
+    pub has_license: bool,
+    /// Drop repositories without a description
+    pub has_description: bool,
+}
+
+impl ResultFilter {
+    /// Whether the filter keeps every repository
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Check that every owner is a possible GitHub login and none is both kept and dropped
+    pub fn validate(&self) -> Result<()> {
+        let is_login = |owner: &String| {
+            owner.len() <= 39
+                && !owner.starts_with('-')
+                && owner.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        };
+        if let Some(owner) = self.exclude_owners.iter().chain(&self.only_owners).find(|owner| !is_login(owner)) {
+            return Err(AppError::validation(
+                "owner",
+                format!("'{}' is not a GitHub user or organization login", owner),
+            ));
+        }
+        if let Some(owner) = self.only_owners.iter().find(|owner| self.exclude_owners.contains(owner)) {
+            return Err(AppError::configuration(format!(
+                "'{}' is given to both --only-owner and --exclude-owner",
+                owner
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether `repo` passes the filter
+    pub fn matches(&self, repo: &Repository) -> bool {
+        let owner = repo.owner.login.to_ascii_lowercase();
+        let excluded = (self.exclude_forks && repo.fork)
+            || (self.exclude_archived && repo.archived)
+            || self.exclude_owners.contains(&owner)
+            || (!self.only_owners.is_empty() && !self.only_owners.contains(&owner));
+        let too_few_stars = self.min_stars.is_some_and(|min| repo.stargazers_count < i64::from(min));
+        let stale = self
+            .pushed_within
+            .is_some_and(|period| repo.pushed_at.is_none_or(|pushed_at| pushed_at < Utc::now() - period));
+        let unlicensed = self.has_license && repo.license.is_none();
+        let undescribed = self.has_description
+            && repo.description.as_deref().is_none_or(|description| description.trim().is_empty());
+        !(excluded || too_few_stars || stale || unlicensed || undescribed)
+    }
+
+    /// Add `stars:>=` and `pushed:>=` qualifiers for `--min-stars` and `--pushed-within`
+    ///
+    /// A qualifier the query already has is left alone; the client-side check
+    /// in [`ResultFilter::matches`] still applies.
+    pub fn qualify_query(&self, query: &str, now: DateTime<Utc>) -> String {
+        let mut query = query.to_string();
+        if let Some(min) = self.min_stars.filter(|_| !query.contains("stars:")) {
+            query.push_str(&format!(" stars:>={}", min));
+        }
+        if let Some(period) = self.pushed_within.filter(|_| !query.contains("pushed:")) {
+            query.push_str(&format!(" pushed:>={}", (now - period).format("%Y-%m-%d")));
+        }
+        query
+    }
+
+    /// The filter as command line flags, as recorded in `query_history.result_filter`
+    pub fn to_arguments(&self) -> Option<String> {
+        let mut arguments = Vec::new();
+        if !self.only_owners.is_empty() {
+            arguments.push(format!("--only-owner {}", self.only_owners.join(",")));
+        }
+        if !self.exclude_owners.is_empty() {
+            arguments.push(format!("--exclude-owner {}", self.exclude_owners.join(",")));
+        }
+        if self.exclude_forks {
+            arguments.push("--exclude-forks".to_string());
+        }
+        if self.exclude_archived {
+            arguments.push("--exclude-archived".to_string());
+        }
+        if let Some(min) = self.min_stars {
+            arguments.push(format!("--min-stars {}", min));
+        }
+        if let Some(period) = self.pushed_within {
+            arguments.push(format!("--pushed-within {}", crate::format_period(period)));
+        }
+        if self.has_license {
+            arguments.push("--has-license".to_string());
+        }
+        if self.has_description {
+            arguments.push("--has-description".to_string());
+        }
+        (!arguments.is_empty()).then(|| arguments.join(" "))
+    }
+}
+
+/// Repositories collected across the pages of a single run
+///
+/// Sorting by `updated` lets repositories shift between pages while a run is
+/// in progress, so the same github_id can come back twice. Only the first
+/// occurrence is kept.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepositoryPages {
+    /// Unique repositories in the order they were first seen
+    pub items: Vec<Repository>,
+
+    /// Total count reported by the most recent page
+    pub total_count: i64,
+
+    /// Whether any page reported incomplete results
+    pub incomplete_results: bool,
+
+    /// Number of pages absorbed
+    pub pages_fetched: u32,
+
+    /// Number of repositories returned across all pages, duplicates included
+    pub fetched_count: usize,
+
+    /// Number of repositories dropped because they were already seen
+    pub duplicate_count: usize,
+
+    /// Number of repositories dropped by the run's [`ResultFilter`]
+    pub filtered_count: usize,
+
+    /// Items that could not be mapped, across all pages
+    pub quarantined: Vec<QuarantinedItem>,
+
+    seen: HashSet<i64>,
+}
+
+impl RepositoryPages {
+    /// Add one page of results, returning the number of new repositories
+    pub fn absorb(&mut self, response: SearchResponse) -> usize {
+        self.total_count = response.total_count;
+        self.incomplete_results |= response.incomplete_results;
+        self.pages_fetched += 1;
+        self.fetched_count += response.items.len();
+        self.quarantined.extend(response.quarantined);
+
+        let before = self.items.len();
+        for repo in response.items {
+            if self.seen.insert(repo.id) {
+                self.items.push(repo);
+            } else {
+                self.duplicate_count += 1;
+            }
+        }
+        self.items.len() - before
+    }
+
+    /// Add one page of results, dropping the repositories `filter` rejects
+    pub fn absorb_matching(&mut self, mut response: SearchResponse, filter: &ResultFilter) -> usize {
+        let page_len = response.items.len();
+        response.items.retain(|repo| filter.matches(repo));
+        let filtered = page_len - response.items.len();
+        self.filtered_count += filtered;
+        self.fetched_count += filtered;
+        self.absorb(response)
+    }
+
+    /// Number of unique repositories collected so far
+    pub fn unique_count(&self) -> usize {
+        self.items.len()
+    }
+}
+
+/// GitHub topic returned by the topic search API
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Topic {
+    /// Topic name (e.g., "rust")
+    pub name: String,
+    
+    /// Human-friendly display name
+    pub display_name: Option<String>,
+    
+    /// Short topic description
+    pub short_description: Option<String>,
+    
+    /// Full topic description
+    pub description: Option<String>,
+    
+    /// Creator of the topic subject
+    pub created_by: Option<String>,
+    
+    /// Free-form release information
+    pub released: Option<String>,
+    
+    /// Topic creation date
+    pub created_at: Option<DateTime<Utc>>,
+    
+    /// Topic last update date
+    pub updated_at: Option<DateTime<Utc>>,
+    
+    /// Is the topic featured by GitHub
+    #[serde(default)]
+    pub featured: bool,
+    
+    /// Is the topic curated by GitHub
+    #[serde(default)]
+    pub curated: bool,
+    
+    /// Number of repositories tagged with the topic (when reported)
+    #[serde(default)]
+    pub repository_count: Option<i64>,
+    
+    /// Search relevance score
+    #[serde(default)]
+    pub score: Option<f64>,
+}
+
+/// GitHub API topic search response wrapper
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopicSearchResponse {
+    /// Total count of topics found
+    pub total_count: i64,
+    
+    /// Whether the search was incomplete (due to timeout)
+    pub incomplete_results: bool,
+    
+    /// Array of topic items
+    pub items: Vec<Topic>,
+}
+
+/// Fields of `/repos/{owner}/{repo}` that search results leave out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepositoryDetails {
+    /// Users watching the repository (search's `watchers_count` repeats the star count)
+    pub subscribers_count: i64,
+    
+    /// Repositories in the fork network
+    pub network_count: i64,
+}
+
+/// Stargazer entry returned with the `star+json` media type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stargazer {
+    /// When the repository was starred
+    pub starred_at: DateTime<Utc>,
+    
+    /// User who starred the repository
+    pub user: StargazerUser,
+}
+
+/// Minimal user information attached to a stargazer entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StargazerUser {
+    /// User ID
+    pub id: i64,
+    
+    /// User login/username
+    pub login: String,
+}
+
+/// GitHub Actions workflow definition
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Workflow {
+    /// Workflow ID
+    pub id: i64,
+    
+    /// Workflow name
+    pub name: String,
+    
+    /// Path of the workflow file (e.g., ".github/workflows/ci.yml")
+    pub path: String,
+    
+    /// Workflow state (active, disabled_manually, ...)
+    pub state: String,
+}
+
+/// GitHub Actions workflow run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowRun {
+    /// Run ID
+    pub id: i64,
+    
+    /// Name of the workflow that ran
+    pub name: Option<String>,
+    
+    /// Run status (queued, in_progress, completed)
+    pub status: Option<String>,
+    
+    /// Run conclusion (success, failure, cancelled, ...)
+    pub conclusion: Option<String>,
+    
+    /// Branch the run executed on
+    pub head_branch: Option<String>,
+    
+    /// When the run was created
+    pub created_at: DateTime<Utc>,
+}
+
+/// CI metadata collected for a repository
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepositoryCi {
+    /// Workflows defined under `.github/workflows`
+    pub workflows: Vec<Workflow>,
+    
+    /// Most recent workflow run on the default branch
+    pub latest_run: Option<WorkflowRun>,
+}
+
+impl RepositoryCi {
+    /// Whether the most recent default-branch run completed successfully
+    pub fn is_passing(&self) -> bool {
+        self.latest_run
+            .as_ref()
+            .and_then(|run| run.conclusion.as_deref())
+            == Some("success")
+    }
+}
+
+/// Repository security advisory (GHSA) summary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityAdvisory {
+    /// GitHub Security Advisory identifier
+    pub ghsa_id: String,
+    
+    /// Advisory severity (low, medium, high, critical)
+    pub severity: Option<String>,
+}
+
+/// Open Dependabot alert summary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependabotAlert {
+    /// Alert number within the repository
+    pub number: i64,
+    
+    /// Advisory the alert was raised for
+    pub security_advisory: SecurityAdvisory,
+}
+
+/// Count of findings per severity level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeverityCounts {
+    pub critical: i32,
+    pub high: i32,
+    pub medium: i32,
+    pub low: i32,
+}
+
+impl SeverityCounts {
+    /// Count one finding with the given severity; unknown severities are ignored
+    pub fn record(&mut self, severity: Option<&str>) {
+        match severity.map(|s| s.to_ascii_lowercase()).as_deref() {
+            Some("critical") => self.critical += 1,
+            Some("high") => self.high += 1,
+            Some("medium") | Some("moderate") => self.medium += 1,
+            Some("low") => self.low += 1,
+            _ => {}
+        }
+    }
+    
+    /// Total number of counted findings
+    pub fn total(&self) -> i32 {
+        self.critical + self.high + self.medium + self.low
+    }
+}
+
+/// Security rollup collected for a repository
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepositorySecurity {
+    /// Published repository security advisories by severity
+    pub advisories: SeverityCounts,
+    
+    /// Open Dependabot alerts by severity, when the token may read them
+    pub dependabot_alerts: Option<SeverityCounts>,
+}
+
+/// OSSF Scorecard result for a repository
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scorecard {
+    /// Date the scorecard was computed
+    pub date: String,
+    
+    /// Aggregate score (0-10)
+    pub score: f64,
+    
+    /// Scorecard tool version information
+    #[serde(default)]
+    pub scorecard: Option<ScorecardVersion>,
+    
+    /// Individual check results
+    #[serde(default)]
+    pub checks: Vec<ScorecardCheck>,
+}
+
+/// Version of the scorecard tool that produced a result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScorecardVersion {
+    pub version: Option<String>,
+    pub commit: Option<String>,
+}
+
+/// Single OSSF Scorecard check result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScorecardCheck {
+    /// Check name (e.g., "Code-Review")
+    pub name: String,
+    
+    /// Check score (0-10, or -1 when inconclusive)
+    pub score: i32,
+    
+    /// Human-readable explanation of the score
+    pub reason: Option<String>,
 }
 
 /// Query metadata for tracking search history
@@ -174,9 +740,198 @@ pub struct QueryMetadata {
     
     /// Error message if query failed
     pub error_message: Option<String>,
+
+    /// Whether GitHub still reported incomplete results after any retries
+    #[serde(default)]
+    pub incomplete_results: bool,
+
+    /// Results requested per page
+    #[serde(default)]
+    pub per_page: Option<i32>,
+
+    /// Number of result pages fetched
+    #[serde(default)]
+    pub pages_fetched: Option<i32>,
+
+    /// Sort field sent to the search API, if any
+    #[serde(default)]
+    pub sort: Option<String>,
+
+    /// Sort direction sent to the search API, if any
+    #[serde(default)]
+    pub sort_order: Option<String>,
+
+    /// GitHub API base URL the query was sent to
+    #[serde(default)]
+    pub api_base_url: Option<String>,
+
+    /// Version of this tool that ran the query
+    #[serde(default)]
+    pub tool_version: Option<String>,
+
+    /// Truncated SHA-256 of the token used, never the token itself
+    #[serde(default)]
+    pub token_fingerprint: Option<String>,
+
+    /// Previous successful run of the same query, linking snapshots over time
+    #[serde(default)]
+    pub previous_query_id: Option<uuid::Uuid>,
+
+    /// Object storage URL the snapshot was archived to
+    #[serde(default)]
+    pub archive_url: Option<String>,
+
+    /// When the snapshot was archived
+    #[serde(default)]
+    pub archived_at: Option<DateTime<Utc>>,
+
+    /// Rows rolled back because their insert batch failed
+    #[serde(default)]
+    pub rows_failed: i64,
+
+    /// Saved query template the search was rendered from
+    #[serde(default)]
+    pub saved_query: Option<String>,
+
+    /// Query before its `{{...}}` placeholders were filled in
+    #[serde(default)]
+    pub query_template: Option<String>,
+
+    /// Client-side result filters, as command line flags
+    #[serde(default)]
+    pub result_filter: Option<String>,
+
+    /// HTTP traffic of the run as observed by the client
+    #[serde(default)]
+    pub http_metrics: Option<HttpMetrics>,
+
+    /// Labels given with `--label key=value`
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+
+    /// Free-text note given with `--note`
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// HTTP traffic of one run, stored in `query_history.http_metrics`
+///
+/// Helps tune `--pages`, concurrency, and pacing: a high sleep time means
+/// the run was rate limited, high latencies mean GitHub was slow.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpMetrics {
+    /// Requests sent, including retries
+    pub requests: u64,
+    /// Response body bytes as transferred, compressed where GitHub compressed them
+    pub bytes_downloaded: u64,
+    /// Response body bytes after decompression
+    #[serde(default)]
+    pub bytes_decompressed: u64,
+    /// Requests sent again after a retryable response
+    pub retries: u64,
+    /// Time slept before retries and while pacing requests
+    pub rate_limit_sleep_ms: u64,
+    /// Median time until response headers arrived
+    pub latency_p50_ms: Option<u64>,
+    /// 95th percentile time until response headers arrived
+    pub latency_p95_ms: Option<u64>,
+    /// Search pages served from the response cache instead of GitHub
+    #[serde(default)]
+    pub cache_hits: u64,
+    /// `X-Request-Id` sent with every request of the run
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+impl HttpMetrics {
+    /// One-line summary for verbose output
+    pub fn summary(&self) -> String {
+        let latency = |value: Option<u64>| value.map_or_else(|| "-".to_string(), |ms| format!("{}ms", ms));
+        let mut summary = format!(
+            "{} requests, {:.1} KiB transferred ({:.1} KiB decompressed), {} retries, {:.1}s rate limit sleep, latency p50 {} / p95 {}",
+            self.requests,
+            self.bytes_downloaded as f64 / 1024.0,
+            self.bytes_decompressed as f64 / 1024.0,
+            self.retries,
+            self.rate_limit_sleep_ms as f64 / 1000.0,
+            latency(self.latency_p50_ms),
+            latency(self.latency_p95_ms)
+        );
+        if self.cache_hits > 0 {
+            summary.push_str(&format!(", {} cached pages", self.cache_hits));
+        }
+        if let Some(request_id) = &self.request_id {
+            summary.push_str(&format!(", request ID {}", request_id));
+        }
+        summary
+    }
+}
+
+/// How strictly repositories are checked before they are stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Every rule in [`Repository::validate`]
+    #[default]
+    Strict,
+    /// Only the fields that identify a repository; minor issues are coerced or ignored
+    Lenient,
+    /// Store repositories as GitHub returned them
+    Off,
 }
 
 impl Repository {
+    /// Validate under `mode`, returning the repository to store
+    ///
+    /// Lenient mode requires `full_name`, `name`, and the owner login, skips
+    /// URL, branch, and owner type checks (some GHE instances return an empty
+    /// `ssh_url`), maps an unknown visibility to `public` or `private` from the
+    /// `private` flag, and clamps negative counts to zero. A copy is only made
+    /// when something had to be coerced.
+    pub fn validated(&self, mode: ValidationMode) -> Result<Cow<'_, Repository>> {
+        match mode {
+            ValidationMode::Strict => self.validate().map(|()| Cow::Borrowed(self)),
+            ValidationMode::Off => Ok(Cow::Borrowed(self)),
+            ValidationMode::Lenient => {
+                if self.full_name.is_empty() {
+                    return Err(AppError::validation("full_name", "cannot be empty"));
+                }
+                if self.name.is_empty() {
+                    return Err(AppError::validation("name", "cannot be empty"));
+                }
+                if self.owner.login.is_empty() {
+                    return Err(AppError::validation("owner.login", "cannot be empty"));
+                }
+
+                let known_visibility = ["public", "private", "internal"].contains(&self.visibility.as_str());
+                let counts = [
+                    self.size,
+                    self.stargazers_count,
+                    self.watchers_count,
+                    self.forks_count,
+                    self.open_issues_count,
+                ];
+                if known_visibility && counts.iter().all(|count| *count >= 0) {
+                    return Ok(Cow::Borrowed(self));
+                }
+
+                let mut repo = self.clone();
+                if !known_visibility {
+                    repo.visibility = if repo.private { "private" } else { "public" }.to_string();
+                }
+                for count in [
+                    &mut repo.size,
+                    &mut repo.stargazers_count,
+                    &mut repo.watchers_count,
+                    &mut repo.forks_count,
+                    &mut repo.open_issues_count,
+                ] {
+                    *count = (*count).max(0);
+                }
+                Ok(Cow::Owned(repo))
+            }
+        }
+    }
+
     /// Validate repository data according to business rules
     pub fn validate(&self) -> Result<()> {
         // Validate required fields
@@ -312,6 +1067,23 @@ impl RepositoryLicense {
     }
 }
 
+impl Topic {
+    /// Validate topic data
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() {
+            return Err(AppError::validation("topic.name", "cannot be empty"));
+        }
+        
+        if let Some(count) = self.repository_count {
+            if count < 0 {
+                return Err(AppError::validation("topic.repository_count", "cannot be negative"));
+            }
+        }
+        
+        Ok(())
+    }
+}
+
 impl QueryMetadata {
     /// Create new query metadata
     pub fn new(search_query: String, table_name: String) -> Self {
@@ -324,8 +1096,55 @@ impl QueryMetadata {
             duration_ms: 0,
             success: false,
             error_message: None,
+            incomplete_results: false,
+            per_page: None,
+            pages_fetched: None,
+            sort: None,
+            sort_order: None,
+            api_base_url: None,
+            tool_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            token_fingerprint: None,
+            previous_query_id: None,
+            archive_url: None,
+            archived_at: None,
+            rows_failed: 0,
+            saved_query: None,
+            query_template: None,
+            result_filter: None,
+            http_metrics: None,
+            labels: BTreeMap::new(),
+            note: None,
         }
     }
+
+    /// Parse a `key=value` label
+    ///
+    /// Keys are up to 63 lowercase letters, digits, `-`, `_`, and `.`;
+    /// values are up to 255 characters and may not be empty.
+    pub fn parse_label(label: &str) -> Result<(String, String)> {
+        let (key, value) = label
+            .split_once('=')
+            .ok_or_else(|| AppError::validation("label", format!("'{}' is not KEY=VALUE", label)))?;
+        let (key, value) = (key.trim(), value.trim());
+        let valid_key = !key.is_empty()
+            && key.len() <= 63
+            && key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'));
+        if !valid_key {
+            return Err(AppError::validation(
+                "label",
+                format!("key '{}' must be 1-63 lowercase letters, digits, '-', '_', or '.'", key),
+            ));
+        }
+        if value.is_empty() || value.chars().count() > 255 {
+            return Err(AppError::validation(
+                "label",
+                format!("value of '{}' must be 1-255 characters", key),
+            ));
+        }
+        Ok((key.to_string(), value.to_string()))
+    }
     
     /// Mark query as completed successfully
     pub fn mark_success(&mut self, result_count: i64, duration_ms: i64) {
@@ -349,5 +1168,53 @@ impl QueryMetadata {
     }
 }
 
+/// A valid public repository named `owner/name`, for unit tests to adjust
+#[cfg(test)]
+pub(crate) fn test_repository(id: i64, full_name: &str) -> Repository {
+    let (login, name) = full_name.split_once('/').expect("full_name is owner/name");
+    Repository {
+        id,
+        full_name: full_name.to_string(),
+        name: name.to_string(),
+        description: None,
+        html_url: format!("https://github.com/{}", full_name),
+        clone_url: format!("https://github.com/{}.git", full_name),
+        ssh_url: format!("git@github.com:{}.git", full_name),
+        size: 1,
+        stargazers_count: 1,
+        watchers_count: 1,
+        forks_count: 0,
+        open_issues_count: 0,
+        language: None,
+        default_branch: "main".to_string(),
+        visibility: "public".to_string(),
+        private: false,
+        fork: false,
+        archived: false,
+        disabled: false,
+        created_at: "2023-01-01T00:00:00Z".parse().unwrap(),
+        updated_at: "2024-01-01T00:00:00Z".parse().unwrap(),
+        pushed_at: None,
+        owner: RepositoryOwner {
+            id,
+            login: login.to_string(),
+            owner_type: "User".to_string(),
+            avatar_url: format!("https://avatars.githubusercontent.com/u/{}", id),
+            html_url: format!("https://github.com/{}", login),
+            site_admin: false,
+        },
+        license: None,
+        topics: Vec::new(),
+        has_issues: true,
+        has_projects: false,
+        has_wiki: false,
+        has_pages: false,
+        has_downloads: false,
+        score: None,
+        text_matches: Vec::new(),
+        matched_language_filter: None,
+    }
+}
+
 #[cfg(test)]
 mod tests;
\ No newline at end of file
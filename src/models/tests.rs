@@ -65,7 +65,7 @@ prop_compose! {
         has_pages in any::<bool>(),
         has_downloads in any::<bool>()
     ) -> Repository {
-        let name = full_name.split('/').last().unwrap_or("repo").to_string();
+        let name = full_name.split('/').next_back().unwrap_or("repo").to_string();
         let now = Utc::now();
         
         Repository {
@@ -99,6 +99,9 @@ prop_compose! {
             has_wiki,
             has_pages,
             has_downloads,
+            score: None,
+            text_matches: Vec::new(),
+            matched_language_filter: None,
         }
     }
 }
@@ -145,6 +148,30 @@ mod unit_tests {
         }
     }
 
+    #[test]
+    fn test_repository_validation_modes() {
+        let mut repo = create_valid_test_repository();
+        repo.ssh_url = String::new();
+        repo.visibility = "limited".to_string();
+        repo.private = true;
+        repo.forks_count = -3;
+        assert!(repo.validated(ValidationMode::Strict).is_err());
+
+        let lenient = repo.validated(ValidationMode::Lenient).unwrap();
+        assert_eq!(lenient.visibility, "private");
+        assert_eq!(lenient.forks_count, 0);
+        assert_eq!(lenient.ssh_url, "");
+
+        let off = repo.validated(ValidationMode::Off).unwrap();
+        assert!(matches!(off, Cow::Borrowed(_)));
+        assert_eq!(off.forks_count, -3);
+
+        let valid = create_valid_test_repository();
+        assert!(matches!(valid.validated(ValidationMode::Lenient).unwrap(), Cow::Borrowed(_)));
+        repo.full_name = String::new();
+        assert!(repo.validated(ValidationMode::Lenient).is_err());
+    }
+
     #[test]
     fn test_repository_owner_validation_comprehensive() {
         let validation_tests = vec![
@@ -244,6 +271,7 @@ mod unit_tests {
             total_count: 1,
             incomplete_results: false,
             items: vec![repo.clone()],
+            quarantined: Vec::new(),
         };
 
         // Test serialization
@@ -258,6 +286,185 @@ mod unit_tests {
         assert_eq!(deserialized.items[0], repo);
     }
 
+    #[test]
+    fn test_search_response_tolerates_and_quarantines_items() {
+        let mut sparse = serde_json::to_value(create_valid_test_repository()).unwrap();
+        let fields = sparse.as_object_mut().unwrap();
+        fields.insert("owner".to_string(), serde_json::Value::Null);
+        fields.insert("license".to_string(), serde_json::Value::Null);
+        fields.insert("default_branch".to_string(), serde_json::Value::Null);
+        fields.remove("visibility");
+        fields.remove("ssh_url");
+        fields.remove("topics");
+
+        let mut broken = serde_json::to_value(create_valid_test_repository()).unwrap();
+        broken["id"] = serde_json::json!(42);
+        broken.as_object_mut().unwrap().remove("full_name");
+
+        let page = serde_json::json!({
+            "total_count": 3,
+            "incomplete_results": false,
+            "items": [sparse, broken, {"name": "no-id"}]
+        });
+        let response: SearchResponse = serde_json::from_value(page).unwrap();
+
+        assert_eq!(response.items.len(), 1);
+        let repo = &response.items[0];
+        assert_eq!(repo.owner, RepositoryOwner::default());
+        assert_eq!((repo.visibility.as_str(), repo.ssh_url.as_str(), repo.default_branch.as_str()), ("", "", ""));
+        assert!(repo.license.is_none() && repo.topics.is_empty());
+
+        assert_eq!(response.quarantined.len(), 2);
+        assert_eq!(response.quarantined[0].github_id, Some(42));
+        assert!(response.quarantined[0].error.contains("full_name"));
+        assert_eq!(response.quarantined[1].github_id, None);
+        assert_eq!(response.quarantined[1].payload["name"], "no-id");
+
+        let mut pages = RepositoryPages::default();
+        pages.absorb(response);
+        assert_eq!(pages.quarantined.len(), 2);
+    }
+
+    #[test]
+    fn test_repository_pages_deduplicates_across_pages() {
+        let repo = |id: i64| Repository { id, ..create_valid_test_repository() };
+        let mut pages = RepositoryPages::default();
+
+        let added = pages.absorb(SearchResponse {
+            total_count: 3,
+            incomplete_results: false,
+            items: vec![repo(1), repo(2)],
+            quarantined: Vec::new(),
+        });
+        assert_eq!(added, 2);
+
+        let added = pages.absorb(SearchResponse {
+            total_count: 3,
+            incomplete_results: true,
+            items: vec![repo(2), repo(3)],
+            quarantined: Vec::new(),
+        });
+        assert_eq!(added, 1);
+
+        assert_eq!(pages.pages_fetched, 2);
+        assert_eq!(pages.fetched_count, 4);
+        assert_eq!(pages.duplicate_count, 1);
+        assert_eq!(pages.unique_count(), 3);
+        assert!(pages.incomplete_results);
+        let ids: Vec<i64> = pages.items.iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_result_filter() {
+        let repo = |id: i64, login: &str, fork: bool, archived: bool| {
+            let mut repo = Repository { id, fork, archived, ..create_valid_test_repository() };
+            repo.owner.login = login.to_string();
+            repo
+        };
+        let filter = ResultFilter {
+            exclude_owners: vec!["spam-org".to_string()],
+            only_owners: Vec::new(),
+            exclude_forks: true,
+            exclude_archived: true,
+            ..ResultFilter::default()
+        };
+        let mut pages = RepositoryPages::default();
+        let added = pages.absorb_matching(
+            SearchResponse {
+                total_count: 4,
+                incomplete_results: false,
+                items: vec![
+                    repo(1, "rust-lang", false, false),
+                    repo(2, "Spam-Org", false, false),
+                    repo(3, "tokio-rs", true, false),
+                    repo(4, "tokio-rs", false, true),
+                ],
+                quarantined: Vec::new(),
+            },
+            &filter,
+        );
+        assert_eq!(added, 1);
+        assert_eq!(pages.filtered_count, 3);
+        assert_eq!(pages.fetched_count, 4);
+        assert_eq!(
+            filter.to_arguments().as_deref(),
+            Some("--exclude-owner spam-org --exclude-forks --exclude-archived")
+        );
+
+        let only = ResultFilter { only_owners: vec!["tokio-rs".to_string()], ..ResultFilter::default() };
+        assert!(only.matches(&repo(5, "Tokio-RS", true, false)));
+        assert!(!only.matches(&repo(6, "rust-lang", false, false)));
+        assert!(ResultFilter::default().is_empty());
+        assert_eq!(ResultFilter::default().to_arguments(), None);
+
+        assert!(only.validate().is_ok());
+        let invalid = ResultFilter { exclude_owners: vec!["not an owner".to_string()], ..ResultFilter::default() };
+        assert!(invalid.validate().is_err());
+        let conflicting = ResultFilter { exclude_owners: vec!["tokio-rs".to_string()], ..only };
+        assert!(conflicting.validate().is_err());
+    }
+
+    #[test]
+    fn test_result_filter_data_quality() {
+        let filter = ResultFilter {
+            min_stars: Some(100),
+            pushed_within: Some(chrono::Duration::days(180)),
+            has_license: true,
+            has_description: true,
+            ..ResultFilter::default()
+        };
+        let mut repo = create_valid_test_repository();
+        repo.stargazers_count = 100;
+        repo.pushed_at = Some(Utc::now() - chrono::Duration::days(10));
+        repo.description = Some("A test repository".to_string());
+        repo.license = Some(RepositoryLicense {
+            key: "mit".to_string(),
+            name: "MIT License".to_string(),
+            spdx_id: Some("MIT".to_string()),
+            url: None,
+        });
+        assert!(filter.matches(&repo));
+
+        let rejected = [
+            Repository { stargazers_count: 99, ..repo.clone() },
+            Repository { pushed_at: Some(Utc::now() - chrono::Duration::days(200)), ..repo.clone() },
+            Repository { pushed_at: None, ..repo.clone() },
+            Repository { license: None, ..repo.clone() },
+            Repository { description: Some("  ".to_string()), ..repo.clone() },
+        ];
+        for repo in &rejected {
+            assert!(!filter.matches(repo));
+        }
+
+        let now: DateTime<Utc> = "2024-03-01T12:00:00Z".parse().unwrap();
+        assert_eq!(
+            filter.qualify_query("language:rust", now),
+            "language:rust stars:>=100 pushed:>=2023-09-03"
+        );
+        assert_eq!(
+            filter.qualify_query("language:rust stars:>10 pushed:>2024-01-01", now),
+            "language:rust stars:>10 pushed:>2024-01-01"
+        );
+        assert_eq!(
+            filter.to_arguments().as_deref(),
+            Some("--min-stars 100 --pushed-within 180d --has-license --has-description")
+        );
+    }
+
+    #[test]
+    fn test_parse_label() {
+        assert_eq!(
+            QueryMetadata::parse_label("experiment=alpha").unwrap(),
+            ("experiment".to_string(), "alpha".to_string())
+        );
+        assert_eq!(QueryMetadata::parse_label(" team.search = a=b ").unwrap().1, "a=b");
+        for label in ["experiment", "=alpha", "Experiment=alpha", "exp riment=alpha", "experiment="] {
+            assert!(QueryMetadata::parse_label(label).is_err(), "{}", label);
+        }
+        assert!(QueryMetadata::parse_label(&format!("k={}", "v".repeat(256))).is_err());
+    }
+
     #[test]
     fn test_table_name_generation_format() {
         let table_name = QueryMetadata::generate_table_name();
@@ -277,6 +484,50 @@ mod unit_tests {
         assert!(timestamp_part.chars().all(|c| c.is_ascii_digit()));
     }
 
+    #[test]
+    fn test_topic_search_response_deserialization() {
+        let json = r#"{
+            "total_count": 1,
+            "incomplete_results": false,
+            "items": [{
+                "name": "rust",
+                "display_name": "Rust",
+                "short_description": "Rust is a systems programming language.",
+                "description": null,
+                "created_by": "Graydon Hoare",
+                "released": "July 7, 2010",
+                "created_at": "2016-12-07T17:07:18Z",
+                "updated_at": "2023-02-14T17:31:24Z",
+                "featured": true,
+                "curated": true,
+                "score": 1.0
+            }]
+        }"#;
+
+        let response: TopicSearchResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.total_count, 1);
+        let topic = &response.items[0];
+        assert_eq!(topic.name, "rust");
+        assert_eq!(topic.display_name.as_deref(), Some("Rust"));
+        assert!(topic.curated);
+        assert!(topic.featured);
+        assert_eq!(topic.repository_count, None);
+        assert!(topic.validate().is_ok());
+    }
+
+    #[test]
+    fn test_topic_validation() {
+        let mut topic: Topic = serde_json::from_str(r#"{"name": "rust"}"#).unwrap();
+        assert!(topic.validate().is_ok());
+
+        topic.repository_count = Some(-1);
+        assert!(topic.validate().is_err());
+
+        topic.repository_count = Some(10);
+        topic.name = String::new();
+        assert!(topic.validate().is_err());
+    }
+
     fn create_valid_test_repository() -> Repository {
         Repository {
             id: 123456789,
@@ -309,6 +560,9 @@ mod unit_tests {
             has_wiki: true,
             has_pages: false,
             has_downloads: true,
+            score: None,
+            text_matches: Vec::new(),
+            matched_language_filter: None,
         }
     }
 
@@ -361,6 +615,7 @@ mod property_tests {
                 total_count,
                 incomplete_results,
                 items: repos.clone(),
+                quarantined: Vec::new(),
             };
 
             let json = serde_json::to_string(&search_response)?;
@@ -451,6 +706,9 @@ mod property_tests {
             has_wiki: true,
             has_pages: false,
             has_downloads: true,
+            score: None,
+            text_matches: Vec::new(),
+            matched_language_filter: None,
         }
     }
 }
\ No newline at end of file
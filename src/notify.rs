@@ -0,0 +1,255 @@
+//! # Notify Module
+//!
+//! Completion notifications for scheduled crawls. With `--notify-url` the
+//! run summary is POSTed to a webhook when the run finishes or fails,
+//! rendered by a [`NotificationTemplate`] chosen with `--notify-format`.
+
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{AppError, HttpConfig, Result, RunReport};
+
+/// Renders a run report into a webhook request body
+pub trait NotificationTemplate: Send + Sync {
+    /// Build the JSON body for the given report
+    fn render(&self, report: &RunReport) -> Value;
+}
+
+/// Generic payload: an event name plus the full run report
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonTemplate;
+
+impl NotificationTemplate for JsonTemplate {
+    fn render(&self, report: &RunReport) -> Value {
+        let event = if report.success { "run.succeeded" } else { "run.failed" };
+        json!({ "event": event, "report": report })
+    }
+}
+
+/// Slack incoming-webhook message
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SlackTemplate;
+
+impl NotificationTemplate for SlackTemplate {
+    fn render(&self, report: &RunReport) -> Value {
+        let table = report.table_name.as_deref().unwrap_or("-");
+        let text = if report.success {
+            let mut text = format!(
+                ":white_check_mark: github-pg-query run succeeded\n*Query:* `{}`\n*Table:* `{}`\n*Stored:* {} new, {} updated in {:.1}s",
+                report.query,
+                table,
+                report.counts.inserted,
+                report.counts.updated,
+                report.duration_ms as f64 / 1000.0
+            );
+            if !report.alerts.is_empty() {
+                text.push_str("\n*Alerts:*");
+                for alert in &report.alerts {
+                    text.push_str(&format!("\n• {}", alert.message()));
+                }
+            }
+            text
+        } else {
+            format!(
+                ":x: github-pg-query run failed\n*Query:* `{}`\n*Table:* `{}`\n*Error:* {}",
+                report.query,
+                table,
+                report.error.as_deref().unwrap_or("unknown error")
+            )
+        };
+        json!({ "text": text })
+    }
+}
+
+/// Built-in notification templates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyFormat {
+    /// Event name plus the full run report
+    Json,
+    /// Slack incoming-webhook message
+    Slack,
+}
+
+impl NotifyFormat {
+    /// All supported formats
+    pub const ALL: &'static [NotifyFormat] = &[NotifyFormat::Json, NotifyFormat::Slack];
+
+    /// Name used on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotifyFormat::Json => "json",
+            NotifyFormat::Slack => "slack",
+        }
+    }
+
+    /// Template that renders this format
+    pub fn template(&self) -> Box<dyn NotificationTemplate> {
+        match self {
+            NotifyFormat::Json => Box::new(JsonTemplate),
+            NotifyFormat::Slack => Box::new(SlackTemplate),
+        }
+    }
+}
+
+impl fmt::Display for NotifyFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NotifyFormat {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        NotifyFormat::ALL
+            .iter()
+            .copied()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| {
+                AppError::configuration(format!(
+                    "Unknown notification format '{}' (supported: json, slack)",
+                    value
+                ))
+            })
+    }
+}
+
+/// Sends run notifications to a webhook
+pub struct Notifier {
+    client: Client,
+    url: String,
+    template: Box<dyn NotificationTemplate>,
+}
+
+impl Notifier {
+    /// Create a notifier using one of the built-in formats
+    pub fn new(url: impl Into<String>, format: NotifyFormat, http_config: &HttpConfig) -> Result<Self> {
+        Self::with_template(url, format.template(), http_config)
+    }
+
+    /// Create a notifier with a custom template
+    pub fn with_template(
+        url: impl Into<String>,
+        template: Box<dyn NotificationTemplate>,
+        http_config: &HttpConfig,
+    ) -> Result<Self> {
+        Ok(Self {
+            client: http_config.build_client()?,
+            url: url.into(),
+            template,
+        })
+    }
+
+    /// POST the rendered report to the webhook
+    pub async fn send(&self, report: &RunReport) -> Result<()> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&self.template.render(report))
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            Err(AppError::internal(format!(
+                "Notification webhook returned HTTP {}: {}",
+                status, body
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Alert;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn report(outcome: Result<()>) -> RunReport {
+        let mut report = RunReport::new("repositories", "language:rust");
+        report.table_name = Some("repos_20240101000000".to_string());
+        report.counts.inserted = 5;
+        report.finish(&outcome);
+        report
+    }
+
+    #[test]
+    fn test_notify_format_round_trip() {
+        for format in NotifyFormat::ALL {
+            assert_eq!(format.as_str().parse::<NotifyFormat>().unwrap(), *format);
+        }
+        assert!("teams".parse::<NotifyFormat>().is_err());
+    }
+
+    #[test]
+    fn test_json_template() {
+        let body = JsonTemplate.render(&report(Ok(())));
+        assert_eq!(body["event"], "run.succeeded");
+        assert_eq!(body["report"]["counts"]["inserted"], 5);
+
+        let body = JsonTemplate.render(&report(Err(AppError::rate_limit("soon"))));
+        assert_eq!(body["event"], "run.failed");
+    }
+
+    #[test]
+    fn test_slack_template() {
+        let text = SlackTemplate.render(&report(Ok(())))["text"].as_str().unwrap().to_string();
+        assert!(text.contains("succeeded"));
+        assert!(text.contains("repos_20240101000000"));
+
+        let mut alerted = report(Ok(()));
+        alerted.alerts.push(Alert {
+            rule: "10k".to_string(),
+            full_name: "serde-rs/serde".to_string(),
+            stargazers_count: 10_012,
+            previous_stars: Some(9_950),
+            rank: None,
+        });
+        let text = SlackTemplate.render(&alerted)["text"].as_str().unwrap().to_string();
+        assert!(text.ends_with("*Alerts:*\n• 10k: serde-rs/serde (9950 → 10012 stars)"));
+
+        let failed = report(Err(AppError::authentication("Invalid or expired GitHub token")));
+        let text = SlackTemplate.render(&failed)["text"].as_str().unwrap().to_string();
+        assert!(text.contains("failed"));
+        assert!(text.contains("Invalid or expired GitHub token"));
+    }
+
+    #[tokio::test]
+    async fn test_send_posts_rendered_report() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(body_partial_json(json!({"event": "run.succeeded"})))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let notifier = Notifier::new(
+            format!("{}/hook", server.uri()),
+            NotifyFormat::Json,
+            &HttpConfig::default(),
+        )
+        .unwrap();
+        notifier.send(&report(Ok(()))).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_send_reports_webhook_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("no such hook"))
+            .mount(&server)
+            .await;
+
+        let notifier = Notifier::new(server.uri(), NotifyFormat::Slack, &HttpConfig::default()).unwrap();
+        let error = notifier.send(&report(Ok(()))).await.unwrap_err();
+        assert!(error.to_string().contains("HTTP 404"));
+    }
+}
@@ -0,0 +1,232 @@
+//! # Search Qualifier Module
+//!
+//! Local checks of the `key:value` qualifiers in repository search queries.
+//! GitHub treats an unknown qualifier such as `lanugage:rust` as free text
+//! and silently returns unrelated results, so typos, unknown qualifiers,
+//! qualifiers without a value, and unbalanced quotes are rejected before any
+//! API call, with a suggestion where one is close enough. Default qualifiers
+//! (`DEFAULT_QUALIFIERS`) are merged into queries here as well.
+
+use crate::{AppError, Result};
+
+/// Qualifiers GitHub accepts in repository searches
+pub const REPOSITORY_QUALIFIERS: &[&str] = &[
+    "archived",
+    "created",
+    "followers",
+    "fork",
+    "forks",
+    "good-first-issues",
+    "has",
+    "help-wanted-issues",
+    "in",
+    "is",
+    "language",
+    "license",
+    "mirror",
+    "org",
+    "owner",
+    "pushed",
+    "repo",
+    "size",
+    "sort",
+    "stars",
+    "template",
+    "topic",
+    "topics",
+    "user",
+];
+
+/// Largest edit distance at which a known qualifier is suggested
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Check the qualifiers of a repository search query
+///
+/// Terms without a colon, URLs, and `{{placeholder}}` templates are left
+/// alone; everything else must use a qualifier from [`REPOSITORY_QUALIFIERS`].
+pub fn check_repository_qualifiers(query: &str) -> Result<()> {
+    let terms = split_terms(query).ok_or_else(|| {
+        AppError::invalid_query(query, "Unbalanced quotes: close every '\"' opened in the query")
+    })?;
+
+    for (index, term) in terms.iter().enumerate() {
+        let Some((key, value)) = qualifier(term) else {
+            continue;
+        };
+        if !REPOSITORY_QUALIFIERS.contains(&key) {
+            let reason = match suggest(key) {
+                Some(known) => format!("Unknown qualifier '{}:' - did you mean '{}:'?", key, known),
+                None => format!(
+                    "Unknown qualifier '{}:' (supported: {})",
+                    key,
+                    REPOSITORY_QUALIFIERS.join(", ")
+                ),
+            };
+            return Err(AppError::invalid_query(query, reason));
+        }
+        if value.is_empty() {
+            let reason = match terms.get(index + 1) {
+                Some(next) => format!(
+                    "Qualifier '{}:' has no value; write '{}:{}' without the space, or quote multi-word values ('{}:\"{} ...\"')",
+                    key, key, next, key, next
+                ),
+                None => format!("Qualifier '{}:' has no value", key),
+            };
+            return Err(AppError::invalid_query(query, reason));
+        }
+    }
+    Ok(())
+}
+
+/// Append the `defaults` terms that `query` does not already set
+///
+/// A default qualifier is skipped when the query uses the same key, negated
+/// or not, so `fork:true` in the query overrides a default `fork:false`.
+/// Other default terms are skipped when the query already has them.
+pub fn apply_default_qualifiers(query: &str, defaults: &str) -> String {
+    let (Some(terms), Some(default_terms)) = (split_terms(query), split_terms(defaults)) else {
+        return query.to_string();
+    };
+    let keys: Vec<&str> = terms.iter().filter_map(|term| qualifier(term).map(|(key, _)| key)).collect();
+
+    let mut merged = query.trim().to_string();
+    for term in default_terms {
+        let present = match qualifier(term) {
+            Some((key, _)) => keys.contains(&key),
+            None => terms.contains(&term),
+        };
+        if !present {
+            if !merged.is_empty() {
+                merged.push(' ');
+            }
+            merged.push_str(term);
+        }
+    }
+    merged
+}
+
+/// Whitespace-separated terms, keeping quoted phrases together
+///
+/// Returns `None` when a quote is not closed.
+fn split_terms(query: &str) -> Option<Vec<&str>> {
+    let mut terms = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+    for (index, character) in query.char_indices() {
+        match character {
+            '"' => {
+                quoted = !quoted;
+                start.get_or_insert(index);
+            }
+            character if character.is_whitespace() && !quoted => {
+                if let Some(begin) = start.take() {
+                    terms.push(&query[begin..index]);
+                }
+            }
+            _ => {
+                start.get_or_insert(index);
+            }
+        }
+    }
+    if quoted {
+        return None;
+    }
+    if let Some(begin) = start {
+        terms.push(&query[begin..]);
+    }
+    Some(terms)
+}
+
+/// Key and value of a `key:value` term, ignoring negation and grouping
+fn qualifier(term: &str) -> Option<(&str, &str)> {
+    let term = term.trim_start_matches(['-', '(']);
+    let (key, value) = term.split_once(':')?;
+    let is_key = !key.is_empty() && key.chars().all(|c| c.is_ascii_lowercase() || c == '-' || c == '_');
+    if !is_key || value.starts_with("//") {
+        return None;
+    }
+    Some((key, value.trim_end_matches(')')))
+}
+
+/// Closest known qualifier to `key`, if it is a plausible typo
+fn suggest(key: &str) -> Option<&'static str> {
+    REPOSITORY_QUALIFIERS
+        .iter()
+        .map(|known| (edit_distance(key, known), *known))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known)
+}
+
+/// Levenshtein distance between two ASCII strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.as_bytes();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, &ca) in a.as_bytes().iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reason(query: &str) -> String {
+        match check_repository_qualifiers(query).unwrap_err() {
+            AppError::InvalidQuery { reason, .. } => reason,
+            error => panic!("unexpected error: {}", error),
+        }
+    }
+
+    #[test]
+    fn test_accepts_valid_queries() {
+        for query in [
+            "rust language:rust stars:>1000",
+            "-language:javascript created:>{{today-30d}}",
+            "language:\"visual basic\" topic:cli",
+            "(language:rust OR language:go) is:public",
+            "\"machine learning\" in:description",
+            "https://example.com in:readme",
+            "pushed:>={{last_run|today-7d}} good-first-issues:>2",
+        ] {
+            assert!(check_repository_qualifiers(query).is_ok(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_suggests_close_qualifiers() {
+        assert_eq!(reason("lanugage:rust"), "Unknown qualifier 'lanugage:' - did you mean 'language:'?");
+        assert!(reason("stras:>10").contains("did you mean 'stars:'?"));
+        assert!(reason("colour:blue").contains("(supported: archived, created"));
+    }
+
+    #[test]
+    fn test_rejects_missing_values_and_open_quotes() {
+        assert!(reason("language: rust").contains("write 'language:rust' without the space"));
+        assert_eq!(reason("rust stars:"), "Qualifier 'stars:' has no value");
+        assert!(reason("language:\"visual basic").starts_with("Unbalanced quotes"));
+    }
+
+    #[test]
+    fn test_apply_default_qualifiers() {
+        let defaults = "archived:false fork:false";
+        assert_eq!(apply_default_qualifiers("language:rust", defaults), "language:rust archived:false fork:false");
+        assert_eq!(apply_default_qualifiers("language:rust fork:true", defaults), "language:rust fork:true archived:false");
+        assert_eq!(apply_default_qualifiers("-archived:true", defaults), "-archived:true fork:false");
+        assert_eq!(apply_default_qualifiers("cli", "cli is:public"), "cli is:public");
+        assert_eq!(apply_default_qualifiers("language:rust", ""), "language:rust");
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("lanugage", "language"), 2);
+        assert_eq!(edit_distance("org", "org"), 0);
+        assert_eq!(edit_distance("", "is"), 2);
+    }
+}
@@ -0,0 +1,187 @@
+//! # API Recording Module
+//!
+//! `--record DIR` writes every GitHub response a run receives to `DIR`, one
+//! JSON file per response with its status, headers, and decoded body.
+//! `--replay DIR` answers the same requests from those files without
+//! contacting GitHub, so an ingest bug can be debugged offline against the
+//! exact payload that caused it. Responses are matched by method, path, and
+//! query string; a request sent several times (retries, repeated pages) is
+//! answered with its recordings in the order they were made.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{AppError, Result};
+
+/// Longest path prefix kept in recording file names
+const MAX_SLUG_LENGTH: usize = 60;
+
+/// Whether responses are written to or read from the directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingMode {
+    /// Send requests to GitHub and store the responses
+    Record,
+    /// Answer requests from stored responses only
+    Replay,
+}
+
+/// One response as stored on disk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub method: String,
+    /// Path and query string of the request
+    pub path: String,
+    pub status: u16,
+    /// Response headers in the order GitHub sent them
+    pub headers: Vec<(String, String)>,
+    /// Body after decompression
+    pub body: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Directory of recorded responses used by a `GitHubClient`
+///
+/// Clones share the per-request counters, so concurrent requests of one run
+/// are numbered consistently.
+#[derive(Debug, Clone)]
+pub struct ApiRecording {
+    dir: PathBuf,
+    mode: RecordingMode,
+    sequence: Arc<Mutex<HashMap<String, u32>>>,
+}
+
+impl PartialEq for ApiRecording {
+    fn eq(&self, other: &Self) -> bool {
+        self.dir == other.dir && self.mode == other.mode
+    }
+}
+
+impl ApiRecording {
+    /// Store responses in `dir`
+    pub fn record(dir: impl Into<PathBuf>) -> Self {
+        Self::new(dir.into(), RecordingMode::Record)
+    }
+
+    /// Answer requests from the responses stored in `dir`
+    pub fn replay(dir: impl Into<PathBuf>) -> Self {
+        Self::new(dir.into(), RecordingMode::Replay)
+    }
+
+    fn new(dir: PathBuf, mode: RecordingMode) -> Self {
+        Self {
+            dir,
+            mode,
+            sequence: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn mode(&self) -> RecordingMode {
+        self.mode
+    }
+
+    /// File of the next response to `method path`
+    ///
+    /// Named after the path for browsing, with a hash of the full request and
+    /// a counter that tells repeated requests apart.
+    fn next_file(&self, method: &str, path: &str) -> PathBuf {
+        let request = format!("{} {}", method, path);
+        let occurrence = {
+            let mut sequence = self.sequence.lock().expect("recording sequence lock poisoned");
+            let count = sequence.entry(request.clone()).or_insert(0);
+            *count += 1;
+            *count
+        };
+        let digest = Sha256::digest(request.as_bytes());
+        let hash: String = digest[..6].iter().map(|byte| format!("{:02x}", byte)).collect();
+        let endpoint = path.split('?').next().unwrap_or_default();
+        let slug: String = endpoint
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+            .collect::<String>()
+            .split('-')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        let slug: String = slug.chars().take(MAX_SLUG_LENGTH).collect();
+        self.dir.join(format!("{}-{}-{:03}.json", slug, hash, occurrence))
+    }
+
+    /// Store a response to `method path`
+    pub async fn save(&self, response: &RecordedResponse) -> Result<()> {
+        let file = self.next_file(&response.method, &response.path);
+        tokio::fs::create_dir_all(&self.dir).await.map_err(|error| {
+            AppError::configuration(format!("Cannot create recording directory {}: {}", self.dir.display(), error))
+        })?;
+        let json = serde_json::to_vec_pretty(response)?;
+        tokio::fs::write(&file, json).await.map_err(|error| {
+            AppError::configuration(format!("Cannot write recorded response {}: {}", file.display(), error))
+        })
+    }
+
+    /// The next stored response to `method path`
+    pub async fn load(&self, method: &str, path: &str) -> Result<RecordedResponse> {
+        let file = self.next_file(method, path);
+        let json = tokio::fs::read(&file).await.map_err(|error| {
+            AppError::configuration(format!(
+                "No recorded response for {} {} in {} ({}: {}); record the run again with --record",
+                method,
+                path,
+                self.dir.display(),
+                file.display(),
+                error
+            ))
+        })?;
+        Ok(serde_json::from_slice(&json)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(path: &str, body: &str) -> RecordedResponse {
+        RecordedResponse {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            status: 200,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: body.to_string(),
+            recorded_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_returns_recordings_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = "/search/repositories?q=language%3Arust&per_page=30&page=1";
+        let recording = ApiRecording::record(dir.path());
+        recording.save(&response(path, "first")).await.unwrap();
+        recording.save(&response(path, "second")).await.unwrap();
+        recording.save(&response("/user", "{}")).await.unwrap();
+
+        let replay = ApiRecording::replay(dir.path());
+        assert_eq!(replay.load("GET", path).await.unwrap().body, "first");
+        assert_eq!(replay.load("GET", path).await.unwrap().body, "second");
+        assert!(replay.load("GET", path).await.is_err());
+        assert_eq!(replay.load("GET", "/user").await.unwrap().body, "{}");
+        assert!(replay.load("GET", "/rate_limit").await.is_err());
+    }
+
+    #[test]
+    fn test_file_names_follow_the_path() {
+        let recording = ApiRecording::record("/tmp/recording");
+        let file = recording.next_file("GET", "/repos/octocat/Hello-World/topics");
+        let name = file.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("repos-octocat-hello-world-topics-"), "{}", name);
+        assert!(name.ends_with("-001.json"));
+    }
+}
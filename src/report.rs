@@ -0,0 +1,180 @@
+//! # Report Module
+//!
+//! Machine-readable run summary written by `--report <path>` when the
+//! process ends, so pipelines that orchestrate crawls can inspect the
+//! outcome without scraping console output.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::{Alert, ApiUsage, RejectedRow, Result};
+
+/// Summary of a single run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunReport {
+    /// Version of this tool
+    pub tool_version: String,
+    /// Search endpoint used (`repositories` or `topics`)
+    pub search_type: String,
+    /// Query sent to GitHub, including any qualifiers added by the run
+    pub query: String,
+    /// Table the results were stored in
+    pub table_name: Option<String>,
+    /// ID of the query_history row for this run
+    pub query_id: Option<uuid::Uuid>,
+    /// Whether the run completed without error
+    pub success: bool,
+    /// Error that ended the run, if any
+    pub error: Option<String>,
+    /// When the run started
+    pub started_at: DateTime<Utc>,
+    /// When the run ended
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Wall-clock duration of the whole run in milliseconds
+    pub duration_ms: i64,
+    /// Time spent on GitHub search requests in milliseconds
+    pub search_duration_ms: Option<i64>,
+    /// Result pages fetched
+    pub pages_fetched: u32,
+    /// Whether GitHub still reported incomplete results
+    pub incomplete_results: bool,
+    /// Result and storage counts
+    pub counts: ReportCounts,
+    /// Requests sent and rate limit quota observed
+    pub api_usage: Option<ApiUsage>,
+    /// Repositories left out under `--on-invalid skip`
+    pub rejects: Vec<RejectedRow>,
+    /// `--alert-rules` alerts raised against the previous snapshot
+    pub alerts: Vec<Alert>,
+}
+
+/// Counts collected while fetching, storing, and enriching results
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReportCounts {
+    /// Total matches reported by GitHub
+    pub total_count: i64,
+    /// Items returned across all fetched pages
+    pub fetched: usize,
+    /// Items repeated across pages and dropped
+    pub duplicates: usize,
+    /// Repositories dropped by `--exclude-owner`, `--only-owner`, `--exclude-forks`, or `--exclude-archived`
+    pub filtered: usize,
+    /// Repositories skipped because they did not change since the baseline
+    pub unchanged: usize,
+    /// Rows inserted
+    pub inserted: i64,
+    /// Existing rows updated in place
+    pub updated: i64,
+    /// Rows rolled back because their insert batch failed
+    pub failed: i64,
+    /// Rows skipped under `--on-invalid skip`
+    pub rejected: usize,
+    /// Search items that could not be read as repositories
+    pub quarantined: usize,
+    /// Messages published to `--sink` brokers
+    pub published: i64,
+    /// Repositories enriched
+    pub enriched: i64,
+    /// Repositories with nothing to enrich
+    pub enrichment_skipped: i64,
+    /// Enrichment lookups that failed
+    pub enrichment_failed: i64,
+}
+
+impl RunReport {
+    /// Start a report for a run of the given query
+    pub fn new(search_type: impl Into<String>, query: impl Into<String>) -> Self {
+        Self {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            search_type: search_type.into(),
+            query: query.into(),
+            table_name: None,
+            query_id: None,
+            success: false,
+            error: None,
+            started_at: Utc::now(),
+            finished_at: None,
+            duration_ms: 0,
+            search_duration_ms: None,
+            pages_fetched: 0,
+            incomplete_results: false,
+            counts: ReportCounts::default(),
+            api_usage: None,
+            rejects: Vec::new(),
+            alerts: Vec::new(),
+        }
+    }
+
+    /// Record the outcome of the run and stamp the finish time
+    pub fn finish(&mut self, outcome: &Result<()>) {
+        let finished_at = Utc::now();
+        self.success = outcome.is_ok();
+        self.error = outcome.as_ref().err().map(|error| error.to_string());
+        self.duration_ms = (finished_at - self.started_at).num_milliseconds();
+        self.finished_at = Some(finished_at);
+    }
+
+    /// Whether the run completed but some batches were rolled back or enrichment lookups failed
+    pub fn is_partial(&self) -> bool {
+        self.success && (self.counts.failed > 0 || self.counts.enrichment_failed > 0)
+    }
+
+    /// Write the report as pretty-printed JSON
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AppError;
+
+    #[test]
+    fn test_finish_records_outcome() {
+        let mut report = RunReport::new("repositories", "language:rust");
+        report.finish(&Ok(()));
+        assert!(report.success);
+        assert!(report.error.is_none());
+        assert!(report.finished_at.is_some());
+
+        let mut report = RunReport::new("repositories", "language:rust");
+        report.finish(&Err(AppError::authentication("Invalid or expired GitHub token")));
+        assert!(!report.success);
+        assert!(report.error.unwrap().contains("Invalid or expired GitHub token"));
+    }
+
+    #[test]
+    fn test_is_partial() {
+        let mut report = RunReport::new("repositories", "language:rust");
+        report.counts.enrichment_failed = 2;
+        assert!(!report.is_partial());
+        report.finish(&Ok(()));
+        assert!(report.is_partial());
+
+        let mut report = RunReport::new("repositories", "language:rust");
+        report.counts.failed = 1;
+        report.finish(&Ok(()));
+        assert!(report.is_partial());
+    }
+
+    #[test]
+    fn test_write_report() {
+        let path = std::env::temp_dir().join(format!("report-{}.json", uuid::Uuid::new_v4()));
+        let mut report = RunReport::new("topics", "rust");
+        report.table_name = Some("topics_20240101000000".to_string());
+        report.counts.inserted = 3;
+        report.finish(&Ok(()));
+        report.write(&path).unwrap();
+
+        let written: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written["search_type"], "topics");
+        assert_eq!(written["table_name"], "topics_20240101000000");
+        assert_eq!(written["counts"]["inserted"], 3);
+        assert_eq!(written["success"], true);
+    }
+}
@@ -0,0 +1,108 @@
+//! # OSSF Scorecard Module
+//!
+//! Minimal client for the public OSSF Scorecard API
+//! (<https://api.securityscorecards.dev>), used by the `scorecard`
+//! enrichment pass.
+
+use reqwest::{Client, StatusCode};
+
+use crate::{AppError, HttpConfig, Result, Scorecard};
+
+/// Client for the public OSSF Scorecard API
+#[derive(Debug, Clone)]
+pub struct ScorecardClient {
+    client: Client,
+    base_url: String,
+}
+
+impl ScorecardClient {
+    /// Create a client for the public Scorecard API
+    pub fn new() -> Result<Self> {
+        Self::new_with_config(&HttpConfig::default())
+    }
+
+    /// Create a client for the public Scorecard API with custom HTTP settings
+    pub fn new_with_config(http_config: &HttpConfig) -> Result<Self> {
+        Ok(Self {
+            client: http_config.build_client()?,
+            base_url: "https://api.securityscorecards.dev".to_string(),
+        })
+    }
+
+    /// Create a client with a custom base URL (for testing)
+    pub fn with_base_url(base_url: String) -> Result<Self> {
+        Ok(Self {
+            client: HttpConfig::default().build_client()?,
+            base_url,
+        })
+    }
+
+    /// Fetch the scorecard for a GitHub repository
+    ///
+    /// Returns `Ok(None)` when the project has not been scored.
+    ///
+    /// # Arguments
+    /// * `full_name` - Repository full name in `owner/repo` form
+    pub async fn get_scorecard(&self, full_name: &str) -> Result<Option<Scorecard>> {
+        let url = format!("{}/projects/github.com/{}", self.base_url, full_name);
+        let response = self.client.get(&url).send().await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(Some(response.json().await?)),
+            StatusCode::NOT_FOUND => Ok(None),
+            status => {
+                let error_body = response.text().await.unwrap_or_default();
+                Err(AppError::github_api(format!(
+                    "Scorecard lookup for {} failed: HTTP {}: {}",
+                    full_name, status, error_body
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_get_scorecard_success() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/projects/github.com/octocat/Hello-World"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "date": "2024-01-01",
+                "repo": {"name": "github.com/octocat/Hello-World", "commit": "abc"},
+                "scorecard": {"version": "v4.13.1", "commit": "def"},
+                "score": 6.4,
+                "checks": [
+                    {"name": "Code-Review", "score": 8, "reason": "found 8/10 approved changesets"},
+                    {"name": "Fuzzing", "score": -1, "reason": null}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ScorecardClient::with_base_url(server.uri()).unwrap();
+        let scorecard = client.get_scorecard("octocat/Hello-World").await.unwrap().unwrap();
+
+        assert_eq!(scorecard.score, 6.4);
+        assert_eq!(scorecard.checks.len(), 2);
+        assert_eq!(scorecard.checks[1].score, -1);
+    }
+
+    #[tokio::test]
+    async fn test_get_scorecard_missing_project() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = ScorecardClient::with_base_url(server.uri()).unwrap();
+        assert!(client.get_scorecard("octocat/unscored").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,466 @@
+//! # Server Module
+//!
+//! `github-pg-query serve` exposes the tool as a small REST service so other
+//! systems can trigger searches and read results without shelling out:
+//!
+//! * `GET /health` - liveness check
+//! * `POST /searches` - queue a search (`{"query": "...", "pages": 2}`), returns `202` with the job
+//! * `GET /searches`, `GET /searches/{id}` - job status and, once run, the run report
+//! * `GET /snapshots` - recent entries from `query_history`
+//! * `GET /snapshots/{table}/stats` - [`TableStats`] for a snapshot table
+//! * `GET /snapshots/{table}/repositories` - stored repositories, filtered by
+//!   `language`, `owner`, and `min_stars`, sorted by `order_by`, and paged
+//!   with `limit` and `offset`
+//!
+//! Requested searches are stored in the `search_jobs` table and executed by
+//! [`run_job_workers`] with a concurrency limit, retrying failures with
+//! exponential backoff. Jobs interrupted by a restart are queued again, so
+//! long crawls survive it. The binary supplies the [`SearchLauncher`] that
+//! executes a search with the server's configuration.
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::json;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::Path as FilePath;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::Semaphore;
+
+use crate::{
+    check_repository_qualifiers, AppError, CliConfig, DatabaseManager, JobStatus, QueryMetadata, RepositoryFilter, Result, RunReport, SearchJob,
+    StoredRepository, TableStats, DEFAULT_TOP_LANGUAGES,
+};
+
+/// `query_history` entries returned by `GET /snapshots` by default
+pub const DEFAULT_SNAPSHOT_LIMIT: i64 = 50;
+
+/// Repositories returned by `GET /snapshots/{table}/repositories` by default
+pub const DEFAULT_REPOSITORY_LIMIT: i64 = 100;
+
+/// Address `serve` listens on unless `--bind`/`--port` are given
+pub const DEFAULT_SERVE_ADDR: &str = "127.0.0.1:8080";
+
+/// Jobs listed by `GET /searches` by default
+pub const DEFAULT_JOB_LIMIT: i64 = 50;
+
+/// How searches are taken off the job queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobOptions {
+    /// Searches run at the same time
+    pub concurrency: usize,
+    /// Attempts per job before it is marked failed
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles with each further attempt
+    pub retry_delay: Duration,
+    /// Wait between queue polls while no job is runnable
+    pub poll_interval: Duration,
+}
+
+impl Default for JobOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 1,
+            max_attempts: 3,
+            retry_delay: Duration::from_secs(30),
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl JobOptions {
+    /// Delay before retrying a job that has failed `attempts` times
+    pub fn backoff(&self, attempts: i32) -> Duration {
+        let exponent = attempts.clamp(1, 10) as u32 - 1;
+        self.retry_delay * 2u32.pow(exponent)
+    }
+}
+
+/// Body of `POST /searches`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SearchRequest {
+    /// GitHub repository search query
+    pub query: String,
+    /// Results per page (1-100); the server's `--per-page` when omitted
+    #[serde(default)]
+    pub per_page: Option<u32>,
+    /// Consecutive pages to fetch; the server's `--pages` when omitted
+    #[serde(default)]
+    pub pages: Option<u32>,
+}
+
+impl SearchRequest {
+    /// The search a queued job stands for
+    pub fn from_job(job: &SearchJob) -> Self {
+        Self {
+            query: job.search_query.clone(),
+            per_page: job.per_page.map(|value| value as u32),
+            pages: job.pages.map(|value| value as u32),
+        }
+    }
+
+    /// Check the request the same way the command line checks its arguments
+    pub fn validate(&self) -> Result<()> {
+        CliConfig::validate_search_query(&self.query)?;
+        check_repository_qualifiers(&self.query)?;
+        if matches!(self.per_page, Some(per_page) if !(1..=100).contains(&per_page)) {
+            return Err(AppError::validation("per_page", "must be between 1 and 100"));
+        }
+        if self.pages == Some(0) {
+            return Err(AppError::validation("pages", "must be at least 1"));
+        }
+        Ok(())
+    }
+}
+
+/// Runs a search in the background and returns its report
+pub type SearchLauncher = Arc<dyn Fn(SearchRequest) -> Pin<Box<dyn Future<Output = RunReport> + Send>> + Send + Sync>;
+
+/// Shared state of the API server and its job workers
+#[derive(Clone)]
+pub struct ServerState {
+    db_manager: DatabaseManager,
+    launcher: SearchLauncher,
+    options: JobOptions,
+}
+
+impl ServerState {
+    pub fn new(db_manager: DatabaseManager, launcher: SearchLauncher, options: JobOptions) -> Self {
+        Self {
+            db_manager,
+            launcher,
+            options,
+        }
+    }
+
+    /// Queue a search
+    pub async fn enqueue(&self, request: &SearchRequest) -> Result<SearchJob> {
+        request.validate()?;
+        self.db_manager
+            .enqueue_job(&request.query, request.per_page, request.pages, self.options.max_attempts)
+            .await
+    }
+
+    /// Run a claimed job and record the outcome
+    async fn run_job(&self, job: SearchJob) -> Result<JobStatus> {
+        let report = (self.launcher)(SearchRequest::from_job(&job)).await;
+        let report_json = serde_json::to_value(&report)?;
+
+        if report.success {
+            self.db_manager.complete_job(job.id, report.query_id, &report_json).await?;
+            Ok(JobStatus::Done)
+        } else {
+            let error = report.error.as_deref().unwrap_or("unknown error");
+            self.db_manager
+                .fail_job(job.id, error, report.query_id, &report_json, self.options.backoff(job.attempts))
+                .await
+        }
+    }
+}
+
+/// Take jobs off the queue and run them until the process is stopped
+///
+/// At most `concurrency` searches run at once. Jobs left running by a
+/// previous server are queued again first.
+pub async fn run_job_workers(state: ServerState) -> Result<()> {
+    state.db_manager.requeue_running_jobs().await?;
+    let slots = Arc::new(Semaphore::new(state.options.concurrency.max(1)));
+
+    loop {
+        let slot = slots.clone().acquire_owned().await.expect("job semaphore is never closed");
+        let job = match state.db_manager.claim_next_job().await {
+            Ok(Some(job)) => job,
+            Ok(None) => {
+                drop(slot);
+                tokio::time::sleep(state.options.poll_interval).await;
+                continue;
+            }
+            Err(error) => {
+                drop(slot);
+                eprintln!("Failed to claim a search job: {}", error);
+                tokio::time::sleep(state.options.poll_interval).await;
+                continue;
+            }
+        };
+
+        let state = state.clone();
+        tokio::spawn(async move {
+            let id = job.id;
+            if let Err(error) = state.run_job(job).await {
+                eprintln!("Failed to record the outcome of search job {}: {}", id, error);
+            }
+            drop(slot);
+        });
+    }
+}
+
+/// Queries listed in a file, one per line; blank lines and `#` comments are skipped
+pub fn read_queries_file(path: &FilePath) -> Result<Vec<String>> {
+    let contents = std::fs::read_to_string(path)?;
+    let queries: Vec<String> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect();
+    for query in &queries {
+        CliConfig::validate_search_query(query)?;
+        check_repository_qualifiers(query)?;
+    }
+    Ok(queries)
+}
+
+/// Routes of the REST API
+pub fn router(state: ServerState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/searches", get(list_searches).post(start_search))
+        .route("/searches/{id}", get(get_search))
+        .route("/snapshots", get(list_snapshots))
+        .route("/snapshots/{table}/stats", get(snapshot_stats))
+        .route("/snapshots/{table}/repositories", get(snapshot_repositories))
+        .with_state(state)
+}
+
+/// Serve the API on `listener` and run the job workers until the process is stopped
+pub async fn serve(listener: TcpListener, state: ServerState) -> Result<()> {
+    let api = async {
+        axum::serve(listener, router(state.clone())).await?;
+        Ok(())
+    };
+    tokio::try_join!(api, run_job_workers(state.clone()))?;
+    Ok(())
+}
+
+/// Bind `addr` and serve the API on it
+pub async fn bind_and_serve(addr: SocketAddr, state: ServerState) -> Result<()> {
+    serve(TcpListener::bind(addr).await?, state).await
+}
+
+/// HTTP status reported for an error
+pub fn error_status(error: &AppError) -> StatusCode {
+    match error {
+        AppError::Configuration { .. } | AppError::Validation { .. } | AppError::InvalidQuery { .. } => {
+            StatusCode::BAD_REQUEST
+        }
+        AppError::RateLimit { .. } => StatusCode::TOO_MANY_REQUESTS,
+        AppError::Timeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+        AppError::CircuitOpen { .. } => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        (error_status(&self), Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+fn not_found(what: String) -> Response {
+    (StatusCode::NOT_FOUND, Json(json!({ "error": format!("{} not found", what) }))).into_response()
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+async fn start_search(
+    State(state): State<ServerState>,
+    Json(request): Json<SearchRequest>,
+) -> Result<(StatusCode, Json<SearchJob>)> {
+    Ok((StatusCode::ACCEPTED, Json(state.enqueue(&request).await?)))
+}
+
+/// Query parameters of `GET /searches`
+#[derive(Debug, Deserialize)]
+struct SearchesQuery {
+    status: Option<String>,
+    limit: Option<i64>,
+}
+
+async fn list_searches(
+    State(state): State<ServerState>,
+    Query(query): Query<SearchesQuery>,
+) -> Result<Json<Vec<SearchJob>>> {
+    let status = query
+        .status
+        .map(|status| {
+            status.parse::<JobStatus>().map_err(|_| {
+                AppError::validation("status", "must be queued, running, failed, or done")
+            })
+        })
+        .transpose()?;
+    let limit = query.limit.unwrap_or(DEFAULT_JOB_LIMIT).clamp(1, 1000);
+    Ok(Json(state.db_manager.list_jobs(status, limit).await?))
+}
+
+async fn get_search(State(state): State<ServerState>, Path(id): Path<uuid::Uuid>) -> Response {
+    match state.db_manager.get_job(id).await {
+        Ok(Some(job)) => Json(job).into_response(),
+        Ok(None) => not_found(format!("Search {}", id)),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Query parameters of `GET /snapshots`
+#[derive(Debug, Deserialize)]
+struct SnapshotsQuery {
+    limit: Option<i64>,
+    #[serde(default)]
+    success_only: bool,
+}
+
+async fn list_snapshots(
+    State(state): State<ServerState>,
+    Query(query): Query<SnapshotsQuery>,
+) -> Result<Json<Vec<QueryMetadata>>> {
+    let limit = query.limit.unwrap_or(DEFAULT_SNAPSHOT_LIMIT).clamp(1, 1000);
+    let history = state.db_manager.get_query_history(Some(limit), query.success_only).await?;
+    Ok(Json(history))
+}
+
+/// Query parameters of `GET /snapshots/{table}/stats`
+#[derive(Debug, Deserialize)]
+struct StatsQuery {
+    top: Option<usize>,
+}
+
+async fn snapshot_stats(
+    State(state): State<ServerState>,
+    Path(table): Path<String>,
+    Query(query): Query<StatsQuery>,
+) -> std::result::Result<Json<TableStats>, Response> {
+    DatabaseManager::validate_table_name(&table).map_err(IntoResponse::into_response)?;
+    if !state.db_manager.table_exists(&table).await.map_err(IntoResponse::into_response)? {
+        return Err(not_found(format!("Table {}", table)));
+    }
+
+    let top = query.top.unwrap_or(DEFAULT_TOP_LANGUAGES);
+    let stats = state
+        .db_manager
+        .get_table_stats_with_limit(&table, top)
+        .await
+        .map_err(IntoResponse::into_response)?;
+    Ok(Json(stats))
+}
+
+/// Query parameters of `GET /snapshots/{table}/repositories`
+#[derive(Debug, Deserialize)]
+struct RepositoriesQuery {
+    language: Option<String>,
+    owner: Option<String>,
+    min_stars: Option<i64>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    order_by: Option<String>,
+}
+
+impl RepositoriesQuery {
+    fn into_filter(self) -> Result<RepositoryFilter> {
+        Ok(RepositoryFilter {
+            language: self.language,
+            owner: self.owner,
+            min_stars: self.min_stars,
+            limit: Some(self.limit.unwrap_or(DEFAULT_REPOSITORY_LIMIT).clamp(1, 1000)),
+            offset: self.offset,
+            order_by: self.order_by.as_deref().map(str::parse).transpose()?.unwrap_or_default(),
+        })
+    }
+}
+
+async fn snapshot_repositories(
+    State(state): State<ServerState>,
+    Path(table): Path<String>,
+    Query(query): Query<RepositoriesQuery>,
+) -> std::result::Result<Json<Vec<StoredRepository>>, Response> {
+    DatabaseManager::validate_table_name(&table).map_err(IntoResponse::into_response)?;
+    let filter = query.into_filter().map_err(IntoResponse::into_response)?;
+    if !state.db_manager.table_exists(&table).await.map_err(IntoResponse::into_response)? {
+        return Err(not_found(format!("Table {}", table)));
+    }
+
+    let repositories = state
+        .db_manager
+        .fetch_repositories(&table, &filter)
+        .await
+        .map_err(IntoResponse::into_response)?;
+    Ok(Json(repositories))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_request_validate() {
+        let request: SearchRequest = serde_json::from_str(r#"{"query": "language:rust"}"#).unwrap();
+        assert_eq!(request.pages, None);
+        assert!(request.validate().is_ok());
+
+        let request = SearchRequest { query: " ".to_string(), per_page: None, pages: None };
+        assert!(request.validate().is_err());
+        let request = SearchRequest { query: "rust".to_string(), per_page: Some(101), pages: None };
+        assert!(request.validate().is_err());
+        let request = SearchRequest { query: "rust".to_string(), per_page: None, pages: Some(0) };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_job_backoff() {
+        let options = JobOptions {
+            retry_delay: Duration::from_secs(10),
+            ..JobOptions::default()
+        };
+        assert_eq!(options.backoff(1), Duration::from_secs(10));
+        assert_eq!(options.backoff(3), Duration::from_secs(40));
+        assert_eq!(options.backoff(50), Duration::from_secs(10 * 512));
+    }
+
+    #[test]
+    fn test_read_queries_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("queries.txt");
+        std::fs::write(&path, "# nightly crawl\nlanguage:rust stars:>1000\n\n  topic:cli  \n").unwrap();
+        assert_eq!(
+            read_queries_file(&path).unwrap(),
+            vec!["language:rust stars:>1000".to_string(), "topic:cli".to_string()]
+        );
+        assert!(read_queries_file(&dir.path().join("missing.txt")).is_err());
+    }
+
+    #[test]
+    fn test_repositories_query_into_filter() {
+        let query = |limit, order_by: Option<&str>| RepositoriesQuery {
+            language: Some("Rust".to_string()),
+            owner: None,
+            min_stars: None,
+            limit,
+            offset: Some(20),
+            order_by: order_by.map(str::to_string),
+        };
+        let filter = query(None, Some("stars")).into_filter().unwrap();
+        assert_eq!(filter.limit, Some(DEFAULT_REPOSITORY_LIMIT));
+        assert_eq!(filter.offset, Some(20));
+        assert_eq!(filter.order_by, crate::RepositoryOrder::Stars);
+        assert_eq!(query(Some(5000), None).into_filter().unwrap().limit, Some(1000));
+        assert_eq!(query(Some(5), None).into_filter().unwrap().order_by, crate::RepositoryOrder::Inserted);
+        assert!(query(None, Some("id; DROP TABLE x")).into_filter().is_err());
+    }
+
+    #[test]
+    fn test_error_status() {
+        assert_eq!(error_status(&AppError::invalid_query("", "empty")), StatusCode::BAD_REQUEST);
+        assert_eq!(error_status(&AppError::rate_limit("soon")), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            error_status(&AppError::Database(sqlx::Error::PoolTimedOut)),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! # Telemetry Module
+//!
+//! OpenTelemetry export for the `tracing` spans emitted around GitHub
+//! requests, result pages, insert batches, and metadata saves. Spans are
+//! always recorded through `tracing`; they are only exported when the
+//! binary is built with the `otel` feature and `--otel-endpoint` is set.
+
+use crate::Result;
+
+/// Service name reported with exported spans
+pub const SERVICE_NAME: &str = "github-pg-query";
+
+/// Installed OTLP exporter; call [`Telemetry::shutdown`] before exiting to flush spans
+pub struct Telemetry {
+    #[cfg(feature = "otel")]
+    provider: opentelemetry_sdk::trace::SdkTracerProvider,
+}
+
+impl Telemetry {
+    /// Export spans over OTLP/HTTP to the given collector
+    ///
+    /// `endpoint` is the collector base URL (e.g., `http://localhost:4318`);
+    /// `/v1/traces` is appended unless already present.
+    #[cfg(feature = "otel")]
+    pub fn init(endpoint: &str) -> Result<Self> {
+        use opentelemetry::trace::TracerProvider as _;
+        use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+        use opentelemetry_sdk::trace::SdkTracerProvider;
+        use opentelemetry_sdk::Resource;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        use crate::AppError;
+
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(traces_url(endpoint))
+            .build()
+            .map_err(|e| AppError::configuration(format!("Failed to create OTLP exporter: {}", e)))?;
+
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+            .build();
+
+        let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME));
+        tracing::subscriber::set_global_default(tracing_subscriber::registry().with(layer))
+            .map_err(|e| AppError::configuration(format!("Failed to install tracing subscriber: {}", e)))?;
+
+        Ok(Self { provider })
+    }
+
+    /// Export spans over OTLP/HTTP (unavailable: built without the `otel` feature)
+    #[cfg(not(feature = "otel"))]
+    pub fn init(_endpoint: &str) -> Result<Self> {
+        Err(crate::AppError::configuration(
+            "--otel-endpoint requires building with the `otel` feature",
+        ))
+    }
+
+    /// Flush pending spans and stop the exporter
+    pub fn shutdown(self) {
+        #[cfg(feature = "otel")]
+        if let Err(error) = self.provider.shutdown() {
+            eprintln!("Failed to flush OpenTelemetry spans: {}", error);
+        }
+    }
+}
+
+/// Collector URL for trace export
+#[cfg_attr(not(feature = "otel"), allow(dead_code))]
+fn traces_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{}/v1/traces", endpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traces_url() {
+        assert_eq!(traces_url("http://localhost:4318"), "http://localhost:4318/v1/traces");
+        assert_eq!(traces_url("http://localhost:4318/"), "http://localhost:4318/v1/traces");
+        assert_eq!(traces_url("https://otel.example.com/v1/traces"), "https://otel.example.com/v1/traces");
+    }
+
+    #[cfg(not(feature = "otel"))]
+    #[test]
+    fn test_init_without_feature() {
+        let error = Telemetry::init("http://localhost:4318").err().unwrap();
+        assert!(error.to_string().contains("otel"));
+    }
+}
@@ -0,0 +1,291 @@
+//! # Query Template Module
+//!
+//! `{{placeholder}}` interpolation for search queries. Besides `--param`
+//! values of saved queries, a few date helpers are built in so scheduled
+//! crawls need no shell date math:
+//!
+//! - `{{today}}` - the current UTC date, e.g. `2024-03-01`
+//! - `{{now}}` - the current UTC time, e.g. `2024-03-01T12:00:00Z`
+//! - `{{last_run}}` - when the previous successful run of the same query started
+//!
+//! Each helper takes an offset in hours, days, or weeks (`{{today-30d}}`,
+//! `{{now-6h}}`, `{{last_run-1h}}`), and `{{last_run|today-7d}}` falls back
+//! to the second helper on the first run.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{AppError, Result};
+
+/// Date helpers recognized in query templates
+const BUILTINS: &[&str] = &["today", "now", "last_run"];
+
+/// Values placeholders are filled in with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryVariables {
+    /// Time `{{today}}` and `{{now}}` refer to
+    pub now: DateTime<Utc>,
+    /// Start of the previous successful run, for `{{last_run}}`
+    pub last_run: Option<DateTime<Utc>>,
+    /// Saved query parameters, as (name, value)
+    pub params: Vec<(String, String)>,
+}
+
+impl QueryVariables {
+    /// Variables for a run starting now, without a previous run or parameters
+    pub fn new() -> Self {
+        Self {
+            now: Utc::now(),
+            last_run: None,
+            params: Vec::new(),
+        }
+    }
+}
+
+impl Default for QueryVariables {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether `query` contains any `{{...}}` placeholder
+pub fn has_placeholders(query: &str) -> bool {
+    query.contains("{{")
+}
+
+/// Whether a template refers to `{{last_run}}`, which needs the query history
+pub fn uses_last_run(template: &str) -> Result<bool> {
+    Ok(parse(template)?.iter().any(|segment| match segment {
+        Segment::Text(_) => false,
+        Segment::Placeholder(choices) => choices.iter().any(|term| term.name == "last_run"),
+    }))
+}
+
+/// Parameter names a template expects from `--param`, in the order they first appear
+pub fn template_parameters(template: &str) -> Result<Vec<String>> {
+    let mut parameters: Vec<String> = Vec::new();
+    for segment in parse(template)? {
+        if let Segment::Placeholder(choices) = segment {
+            for term in choices {
+                if !BUILTINS.contains(&term.name) && !parameters.iter().any(|known| known == term.name) {
+                    parameters.push(term.name.to_string());
+                }
+            }
+        }
+    }
+    Ok(parameters)
+}
+
+/// Fill in the placeholders of `template`
+///
+/// Every parameter placeholder needs a value and every value a placeholder,
+/// so a mistyped `--param` name is reported rather than ignored.
+pub fn render_query(template: &str, variables: &QueryVariables) -> Result<String> {
+    let mut query = String::with_capacity(template.len());
+    for segment in parse(template)? {
+        match segment {
+            Segment::Text(text) => query.push_str(text),
+            Segment::Placeholder(choices) => {
+                let value = choices
+                    .iter()
+                    .map(|term| term.resolve(variables))
+                    .find_map(|value| value.transpose())
+                    .transpose()?
+                    .ok_or_else(|| {
+                        AppError::configuration(
+                            "No previous successful run for {{last_run}}; add a fallback such as {{last_run|today-7d}}",
+                        )
+                    })?;
+                query.push_str(&value);
+            }
+        }
+    }
+
+    let parameters = template_parameters(template)?;
+    if let Some((unused, _)) = variables.params.iter().find(|(name, _)| !parameters.contains(name)) {
+        return Err(AppError::configuration(format!("Query template has no parameter '{}'", unused)));
+    }
+    Ok(query)
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    /// Alternatives separated by `|`, the first with a value wins
+    Placeholder(Vec<Term<'a>>),
+}
+
+/// One helper or parameter with an optional offset
+struct Term<'a> {
+    name: &'a str,
+    offset: Option<Duration>,
+}
+
+impl Term<'_> {
+    /// The value of the term; `Ok(None)` only for `last_run` before the first run
+    fn resolve(&self, variables: &QueryVariables) -> Result<Option<String>> {
+        let shift = |at: DateTime<Utc>| at + self.offset.unwrap_or_default();
+        let value = match self.name {
+            "today" => Some(shift(variables.now).format("%Y-%m-%d").to_string()),
+            "now" => Some(shift(variables.now).format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+            "last_run" => variables
+                .last_run
+                .map(|at| shift(at).format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+            name => {
+                let (_, value) = variables.params.iter().find(|(key, _)| key == name).ok_or_else(|| {
+                    AppError::configuration(format!("Query template needs --param {}=<value>", name))
+                })?;
+                Some(value.clone())
+            }
+        };
+        Ok(value)
+    }
+}
+
+fn parse(template: &str) -> Result<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let end = rest[start..].find("}}").ok_or_else(|| {
+            AppError::configuration(format!("Unclosed '{{{{' in query template '{}'", template))
+        })?;
+        let placeholder = &rest[start..start + end + 2];
+        let choices = rest[start + 2..start + end]
+            .split('|')
+            .map(|term| parse_term(term.trim()))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                AppError::configuration(format!("Invalid placeholder '{}' in query template", placeholder))
+            })?;
+        segments.push(Segment::Text(&rest[..start]));
+        segments.push(Segment::Placeholder(choices));
+        rest = &rest[start + end + 2..];
+    }
+    segments.push(Segment::Text(rest));
+    Ok(segments)
+}
+
+/// `name`, or a date helper with an offset such as `today-30d`
+fn parse_term(term: &str) -> Option<Term<'_>> {
+    let (name, offset) = match term.find(['+', '-']) {
+        Some(at) => (&term[..at], Some(parse_offset(&term[at..])?)),
+        None => (term, None),
+    };
+    let valid_name = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid_name || (offset.is_some() && !BUILTINS.contains(&name)) {
+        return None;
+    }
+    Some(Term { name, offset })
+}
+
+/// `+7d`, `-30d`, `-2w`, or `-6h`
+fn parse_offset(offset: &str) -> Option<Duration> {
+    let (sign, amount) = offset.split_at(1);
+    let duration = parse_amount(amount)?;
+    Some(if sign == "-" { -duration } else { duration })
+}
+
+/// `30d`, `2w`, or `6h`
+fn parse_amount(amount: &str) -> Option<Duration> {
+    let unit = amount.chars().last()?;
+    let count: i64 = amount[..amount.len() - unit.len_utf8()].parse().ok()?;
+    match unit {
+        'h' => Duration::try_hours(count),
+        'd' => Duration::try_days(count),
+        'w' => Duration::try_weeks(count),
+        _ => None,
+    }
+}
+
+/// Parse a period such as `180d`, `6w`, or `12h`, as taken by `--pushed-within`
+pub fn parse_period(period: &str) -> Result<Duration> {
+    parse_amount(period.trim())
+        .filter(|duration| *duration > Duration::zero())
+        .ok_or_else(|| {
+            AppError::configuration(format!(
+                "Invalid period '{}': expected a count of hours, days, or weeks such as 180d",
+                period
+            ))
+        })
+}
+
+/// Format a period in the largest unit that divides it, the inverse of [`parse_period`]
+pub fn format_period(period: Duration) -> String {
+    if period.num_seconds() % Duration::weeks(1).num_seconds() == 0 {
+        format!("{}w", period.num_weeks())
+    } else if period.num_seconds() % Duration::days(1).num_seconds() == 0 {
+        format!("{}d", period.num_days())
+    } else {
+        format!("{}h", period.num_hours())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> QueryVariables {
+        QueryVariables {
+            now: "2024-03-01T12:00:00Z".parse().unwrap(),
+            last_run: None,
+            params: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_render_date_helpers() {
+        let vars = variables();
+        assert_eq!(render_query("created:>{{today-30d}}", &vars).unwrap(), "created:>2024-01-31");
+        assert_eq!(render_query("created:<{{ today+1w }}", &vars).unwrap(), "created:<2024-03-08");
+        assert_eq!(render_query("pushed:>{{now-6h}}", &vars).unwrap(), "pushed:>2024-03-01T06:00:00Z");
+        assert_eq!(render_query("language:rust", &vars).unwrap(), "language:rust");
+    }
+
+    #[test]
+    fn test_render_last_run_with_fallback() {
+        let mut vars = variables();
+        assert!(render_query("pushed:>{{last_run}}", &vars).is_err());
+        assert_eq!(
+            render_query("pushed:>{{last_run|today-7d}}", &vars).unwrap(),
+            "pushed:>2024-02-23"
+        );
+
+        vars.last_run = Some("2024-02-29T23:30:00Z".parse().unwrap());
+        assert_eq!(
+            render_query("pushed:>{{last_run|today-7d}}", &vars).unwrap(),
+            "pushed:>2024-02-29T23:30:00Z"
+        );
+        assert_eq!(render_query("pushed:>{{last_run-1h}}", &vars).unwrap(), "pushed:>2024-02-29T22:30:00Z");
+        assert!(uses_last_run("pushed:>{{last_run|today}}").unwrap());
+        assert!(!uses_last_run("pushed:>{{today}}").unwrap());
+    }
+
+    #[test]
+    fn test_render_params_alongside_helpers() {
+        let mut vars = variables();
+        vars.params = vec![("min_stars".to_string(), "500".to_string())];
+        let template = "stars:>{{min_stars}} created:>{{today-1d}}";
+        assert_eq!(render_query(template, &vars).unwrap(), "stars:>500 created:>2024-02-29");
+        assert_eq!(template_parameters(template).unwrap(), vec!["min_stars".to_string()]);
+
+        vars.params.push(("minstars".to_string(), "1".to_string()));
+        assert!(render_query(template, &vars).is_err());
+        assert!(render_query(template, &variables()).is_err());
+    }
+
+    #[test]
+    fn test_invalid_placeholders() {
+        for template in ["{{today", "{{today-30x}}", "{{today-d}}", "{{min_stars-1d}}", "{{}}", "{{a b}}"] {
+            assert!(render_query(template, &variables()).is_err(), "{} should be rejected", template);
+        }
+    }
+
+    #[test]
+    fn test_parse_and_format_period() {
+        assert_eq!(parse_period("180d").unwrap(), Duration::days(180));
+        assert_eq!(format_period(parse_period("180d").unwrap()), "180d");
+        assert_eq!(format_period(parse_period("14d").unwrap()), "2w");
+        assert_eq!(format_period(parse_period("36h").unwrap()), "36h");
+        for period in ["", "180", "d", "-3d", "0d", "3m"] {
+            assert!(parse_period(period).is_err(), "{} should be rejected", period);
+        }
+    }
+}
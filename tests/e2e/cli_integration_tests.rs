@@ -175,7 +175,7 @@ async fn test_cli_search_workflow_success() {
     assert_eq!(search_response.items.len(), 1);
     
     // Create table and insert results
-    let table_name = DatabaseManager::generate_table_name();
+    let table_name = db_manager.new_table_name();
     db_manager.create_repository_table(&table_name).await.unwrap();
     
     let inserted_count = db_manager.insert_repositories(&table_name, &search_response.items).await.unwrap();
@@ -528,7 +528,7 @@ async fn test_cli_complete_workflow_with_metadata() {
     let db_manager = DatabaseManager::new(&config.database_url).await.unwrap();
     
     // Generate table name and create table
-    let table_name = DatabaseManager::generate_table_name();
+    let table_name = db_manager.new_table_name();
     db_manager.create_repository_table(&table_name).await.unwrap();
     
     // Execute search
@@ -80,7 +80,7 @@ fn test_workflow_components_integration() {
 #[test]
 fn test_table_name_generation_consistency() {
     // Test that table name generation follows the expected format
-    let table_name = github_pg_query::DatabaseManager::generate_table_name();
+    let table_name = github_pg_query::QueryMetadata::generate_table_name();
     
     // Should follow the expected format
     assert!(table_name.starts_with("repos_"));
@@ -68,7 +68,7 @@ async fn test_complete_workflow_components_integration() -> Result<()> {
     assert_eq!(failure_metadata.error_message, Some("Test error".to_string()));
 
     // Test 5: Table name generation consistency
-    let table_name1 = github_pg_query::DatabaseManager::generate_table_name();
+    let table_name1 = github_pg_query::QueryMetadata::generate_table_name();
     
     // Verify table name format
     assert!(table_name1.starts_with("repos_"));
@@ -1,15 +1,15 @@
+//! Comprehensive test runner for the GitHub PostgreSQL Query tool
+//! 
+//! This module provides utilities to run different types of tests:
+//! - Unit tests with proper mocking
+//! - Integration tests with test containers
+//! - End-to-end CLI tests
+//! - Property-based tests for data validation
+//! - Performance tests for large result sets
+
 use std::process::Command;
 use std::env;
 
-/// Comprehensive test runner for the GitHub PostgreSQL Query tool
-/// 
-/// This module provides utilities to run different types of tests:
-/// - Unit tests with proper mocking
-/// - Integration tests with test containers
-/// - End-to-end CLI tests
-/// - Property-based tests for data validation
-/// - Performance tests for large result sets
-
 pub struct TestRunner {
     pub verbose: bool,
     pub test_database_url: Option<String>,
@@ -28,7 +28,7 @@ impl TestRunner {
         println!("🧪 Running unit tests...");
         
         let mut cmd = Command::new("cargo");
-        cmd.args(&["test", "--lib"]);
+        cmd.args(["test", "--lib"]);
         
         if self.verbose {
             cmd.arg("--verbose");
@@ -57,9 +57,9 @@ impl TestRunner {
         }
         
         let mut cmd = Command::new("cargo");
-        cmd.args(&["test", "--test", "database_integration_tests"]);
-        cmd.args(&["--test", "integration_workflow_tests"]);
-        cmd.args(&["--test", "main_workflow_integration_test"]);
+        cmd.args(["test", "--test", "database_integration_tests"]);
+        cmd.args(["--test", "integration_workflow_tests"]);
+        cmd.args(["--test", "main_workflow_integration_test"]);
         
         if self.verbose {
             cmd.arg("--verbose");
@@ -87,7 +87,7 @@ impl TestRunner {
         println!("🎯 Running end-to-end tests...");
         
         let mut cmd = Command::new("cargo");
-        cmd.args(&["test", "--test", "cli_integration_tests"]);
+        cmd.args(["test", "--test", "cli_integration_tests"]);
         
         if self.verbose {
             cmd.arg("--verbose");
@@ -110,7 +110,7 @@ impl TestRunner {
         println!("🎲 Running property-based tests...");
         
         let mut cmd = Command::new("cargo");
-        cmd.args(&["test", "property_tests"]);
+        cmd.args(["test", "property_tests"]);
         
         if self.verbose {
             cmd.arg("--verbose");
@@ -133,7 +133,7 @@ impl TestRunner {
         println!("⚡ Running performance benchmarks...");
         
         let mut cmd = Command::new("cargo");
-        cmd.args(&["bench"]);
+        cmd.args(["bench"]);
         
         if self.verbose {
             cmd.arg("--verbose");
@@ -176,7 +176,7 @@ impl TestRunner {
     /// Check if Docker is available for integration tests
     fn check_docker_available(&self) -> bool {
         Command::new("docker")
-            .args(&["--version"])
+            .args(["--version"])
             .output()
             .map(|output| output.status.success())
             .unwrap_or(false)
@@ -188,12 +188,12 @@ impl TestRunner {
         
         // Install cargo-tarpaulin if not available
         let mut cmd = Command::new("cargo");
-        cmd.args(&["install", "cargo-tarpaulin"]);
+        cmd.args(["install", "cargo-tarpaulin"]);
         let _ = cmd.output(); // Ignore if already installed
         
         // Generate coverage report
         let mut cmd = Command::new("cargo");
-        cmd.args(&["tarpaulin", "--out", "Html", "--output-dir", "target/coverage"]);
+        cmd.args(["tarpaulin", "--out", "Html", "--output-dir", "target/coverage"]);
         
         if self.verbose {
             cmd.arg("--verbose");
@@ -216,7 +216,7 @@ impl TestRunner {
         println!("🔍 Running filtered tests: {}", filter);
         
         let mut cmd = Command::new("cargo");
-        cmd.args(&["test", filter]);
+        cmd.args(["test", filter]);
         
         if self.verbose {
             cmd.arg("--verbose");
@@ -241,24 +241,6 @@ impl Default for TestRunner {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_runner_creation() {
-        let runner = TestRunner::new();
-        assert!(!runner.verbose || env::var("VERBOSE").is_ok());
-    }
-
-    #[test]
-    fn test_docker_availability_check() {
-        let runner = TestRunner::new();
-        // This test will pass regardless of Docker availability
-        let _docker_available = runner.check_docker_available();
-    }
-}
-
 /// Main function for running tests from command line
 pub fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
@@ -290,4 +272,22 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
             Ok(())
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runner_creation() {
+        let runner = TestRunner::new();
+        assert!(!runner.verbose || env::var("VERBOSE").is_ok());
+    }
+
+    #[test]
+    fn test_docker_availability_check() {
+        let runner = TestRunner::new();
+        // This test will pass regardless of Docker availability
+        let _docker_available = runner.check_docker_available();
+    }
 }
\ No newline at end of file
@@ -99,12 +99,12 @@ async fn test_database_manager_initialization() {
 #[tokio::test]
 #[serial]
 async fn test_table_name_generation() {
-    let table_name1 = DatabaseManager::generate_table_name();
+    let table_name1 = QueryMetadata::generate_table_name();
     
     // Small delay to ensure different timestamps
     tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
     
-    let table_name2 = DatabaseManager::generate_table_name();
+    let table_name2 = QueryMetadata::generate_table_name();
 
     // Both should have correct format
     assert!(table_name1.starts_with("repos_"));
@@ -487,7 +487,7 @@ proptest! {
     fn test_table_name_generation_properties(
         _seed in 0u64..1000
     ) {
-        let table_name = DatabaseManager::generate_table_name();
+        let table_name = QueryMetadata::generate_table_name();
         
         // Should always start with "repos_"
         prop_assert!(table_name.starts_with("repos_"));
@@ -517,6 +517,11 @@ proptest! {
             unique_owners,
             avg_stars: max_stars as f64 / 2.0,
             max_stars,
+            median_stars: 0.0,
+            fork_ratio: 0.0,
+            archived_ratio: 0.0,
+            top_languages: Vec::new(),
+            licenses: Vec::new(),
             oldest_repo: Some(Utc::now()),
             newest_repo: Some(Utc::now()),
         };
@@ -545,6 +550,11 @@ fn test_table_stats_creation() {
         unique_owners: 25,
         avg_stars: 42.5,
         max_stars: 1000,
+        median_stars: 12.0,
+        fork_ratio: 0.1,
+        archived_ratio: 0.02,
+        top_languages: Vec::new(),
+        licenses: Vec::new(),
         oldest_repo: Some(Utc::now()),
         newest_repo: Some(Utc::now()),
     };
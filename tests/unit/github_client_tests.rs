@@ -110,6 +110,7 @@ async fn test_search_repositories_rate_limit() {
         initial_backoff_ms: 10,
         max_backoff_ms: 100,
         backoff_multiplier: 2.0,
+        pacing: None,
     };
     
     let result = client.search_repositories_with_config(
@@ -239,6 +240,7 @@ async fn test_search_repositories_with_retry_success() {
         initial_backoff_ms: 10,
         max_backoff_ms: 100,
         backoff_multiplier: 2.0,
+        pacing: None,
     };
     
     let result = client.search_repositories_with_config(
@@ -410,6 +412,7 @@ proptest! {
             initial_backoff_ms: initial_backoff,
             max_backoff_ms: max_backoff,
             backoff_multiplier: multiplier,
+            pacing: None,
         };
 
         let mut backoff = config.initial_backoff_ms;